@@ -0,0 +1,42 @@
+// full decode against header only decode, over payloads from the mock feed.
+// run with cargo bench --bench headers
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use tsc_solution::mock::MockFeed;
+use tsc_solution::PriceQuote;
+
+const QUOTES: usize = 10_000;
+const ROUNDS: u32 = 50;
+
+fn time_per_quote(payloads: &[Vec<u8>], decode: impl Fn(&[u8])) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for payload in payloads {
+            decode(black_box(payload));
+        }
+    }
+    start.elapsed() / (ROUNDS * payloads.len() as u32)
+}
+
+fn main() {
+    let mut feed = MockFeed::new(50, 1);
+    let payloads: Vec<Vec<u8>> = (0..QUOTES)
+        .map(|_| feed.next_quote().encode_b6034())
+        .collect();
+
+    let full = time_per_quote(&payloads, |payload| {
+        black_box(PriceQuote::from_bytes(Duration::ZERO, payload).unwrap());
+    });
+    let headers = time_per_quote(&payloads, |payload| {
+        black_box(PriceQuote::header_from_bytes(Duration::ZERO, payload).unwrap());
+    });
+
+    println!("full decode:   {:?} per quote", full);
+    println!("headers only:  {:?} per quote", headers);
+    println!(
+        "speedup:       {:.1}x",
+        full.as_secs_f64() / headers.as_secs_f64()
+    );
+}
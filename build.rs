@@ -0,0 +1,22 @@
+fn main() {
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+// include/tsc_solution.h, from src/ffi.rs alone so nothing else in the crate
+// ends up in the header
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+        .expect("couldn't read cbindgen.toml");
+    cbindgen::Builder::new()
+        .with_src(format!("{}/src/ffi.rs", crate_dir))
+        .with_config(config)
+        .generate()
+        .expect("couldn't generate the C header")
+        .write_to_file(format!("{}/include/tsc_solution.h", crate_dir));
+}
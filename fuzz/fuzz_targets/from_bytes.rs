@@ -0,0 +1,22 @@
+#![no_main]
+
+// a single quote payload, straight into the decoder. anything that decodes
+// gets formatted too, since Display trusts the decoded bytes the most
+
+use std::time::Duration;
+
+use libfuzzer_sys::fuzz_target;
+use tsc_solution::PriceQuote;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(quote) = PriceQuote::from_bytes(Duration::ZERO, data) {
+        let _ = quote.to_string();
+        let _ = quote.accept_time();
+        let _ = quote.seq();
+        let _ = quote.market();
+        let _ = quote.market_status();
+    }
+    if let Ok(header) = PriceQuote::header_from_bytes(Duration::ZERO, data) {
+        let _ = header.to_string();
+    }
+});
@@ -0,0 +1,28 @@
+#![no_main]
+
+// one captured frame through the whole per-packet path: pcap record, link
+// layer and ip via etherparse, decapsulation, port and prefix checks, then
+// the decoder. the frame is wrapped in a pcap so detection and framing are
+// the real ones too
+
+use libfuzzer_sys::fuzz_target;
+use pcap_file::pcap::{PcapPacket, PcapWriter};
+use tsc_solution::{parse_price_quotes_from_bytes, ParseOptions};
+
+fuzz_target!(|frame: &[u8]| {
+    let mut writer = PcapWriter::new(Vec::new()).unwrap();
+    writer
+        .write_packet(&PcapPacket::new(
+            std::time::Duration::ZERO,
+            frame.len() as u32,
+            frame,
+        ))
+        .unwrap();
+    let pcap = writer.into_writer();
+
+    if let Ok((quotes, _)) = parse_price_quotes_from_bytes(&pcap, &ParseOptions::default()) {
+        for quote in &quotes {
+            let _ = quote.to_string();
+        }
+    }
+});
@@ -0,0 +1,54 @@
+#ifndef TSC_SOLUTION_H
+#define TSC_SOLUTION_H
+
+/* generated by build.rs with cbindgen, don't edit */
+
+#include <stdint.h>
+#include <stddef.h>
+
+#define TSC_OK 0
+
+#define TSC_ERR_NULL -1
+
+#define TSC_ERR_SHORT -2
+
+#define TSC_ERR_INVALID -3
+
+#define TSC_LEVELS 5
+
+#define TSC_ISSUE_CODE_SIZE 13
+
+typedef struct TscLevel {
+  uint64_t price;
+  uint64_t qty;
+  uint32_t order_count;
+} TscLevel;
+
+typedef struct TscQuote {
+  uint16_t data_type;
+  uint16_t information_type;
+  uint8_t market_type;
+  char issue_code[TSC_ISSUE_CODE_SIZE];
+  uint32_t issue_seq_no;
+  uint16_t market_status_type;
+  uint64_t total_bid_quote_volume;
+  uint64_t total_ask_quote_volume;
+  struct TscLevel bids[TSC_LEVELS];
+  struct TscLevel asks[TSC_LEVELS];
+  uint64_t no_of_best_bid_valid_quote_total;
+  uint64_t no_of_best_ask_valid_quote_total;
+  uint64_t quote_accept_time;
+} TscQuote;
+
+/**
+ * Decodes one B6034 payload into `out`. Returns `TSC_OK`, or one of the
+ * `TSC_ERR_*` codes, in which case `out` is left untouched.
+ *
+ * # Safety
+ *
+ * `buf` must point to `len` readable bytes and `out` to a writable
+ * `TscQuote`. Either may be null, which is reported as `TSC_ERR_NULL`.
+ */
+int32_t tsc_decode_quote(const uint8_t *buf, size_t len, struct TscQuote *out);
+
+#endif  /* TSC_SOLUTION_H */
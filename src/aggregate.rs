@@ -0,0 +1,112 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::ops::ControlFlow;
+
+use crate::error::ParseFileError;
+use crate::parsing::concat_price_quotes_with_port;
+use crate::price_quote::{Level, PriceQuote, LEVELS};
+use crate::{PacketParseStats, ParseOptions};
+
+// one book out of two quotes for the same issue, for --aggregate-book. each
+// side keeps the best LEVELS prices offered by either. a price both offer
+// is taken from the quote with the higher total volume on that side, which
+// also supplies the side's totals. everything else comes from the newer
+// quote, a on a tie
+pub fn merge_books(a: &PriceQuote, b: &PriceQuote) -> PriceQuote {
+    let mut merged = if b.packet_rcv_time > a.packet_rcv_time {
+        b.clone()
+    } else {
+        a.clone()
+    };
+
+    let (bid_source, bid_other) = if b.total_bid_quote_volume > a.total_bid_quote_volume {
+        (b, a)
+    } else {
+        (a, b)
+    };
+    merged.bids = merge_side(&bid_source.bids, &bid_other.bids, true);
+    merged.total_bid_quote_volume = bid_source.total_bid_quote_volume;
+    merged.no_of_best_bid_valid_quote_total = bid_source.no_of_best_bid_valid_quote_total;
+
+    let (ask_source, ask_other) = if b.total_ask_quote_volume > a.total_ask_quote_volume {
+        (b, a)
+    } else {
+        (a, b)
+    };
+    merged.asks = merge_side(&ask_source.asks, &ask_other.asks, false);
+    merged.total_ask_quote_volume = ask_source.total_ask_quote_volume;
+    merged.no_of_best_ask_valid_quote_total = ask_source.no_of_best_ask_valid_quote_total;
+
+    merged
+}
+
+// the levels of both, preferred's winning on price, best first. empty
+// levels are dropped and the end is padded with them again
+fn merge_side(preferred: &[Level], other: &[Level], bids: bool) -> [Level; LEVELS] {
+    let mut levels: Vec<Level> = preferred
+        .iter()
+        .filter(|level| !level.price.is_zero())
+        .copied()
+        .collect();
+    for level in other {
+        if !level.price.is_zero() && !levels.iter().any(|l| l.price == level.price) {
+            levels.push(*level);
+        }
+    }
+    if bids {
+        levels.sort_by_key(|level| Reverse(level.price));
+    } else {
+        levels.sort_by_key(|level| level.price);
+    }
+
+    let mut side = [Level::default(); LEVELS];
+    for (slot, level) in side.iter_mut().zip(levels) {
+        *slot = level;
+    }
+    side
+}
+
+// the latest quote each port sent for each issue
+#[derive(Debug, Default)]
+pub struct BookAggregator {
+    latest: HashMap<String, BTreeMap<u16, PriceQuote>>,
+}
+
+impl BookAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // takes in a quote and hands back its issue's book merged across every
+    // port heard from so far. raw input counts as port 0
+    pub fn push(&mut self, price_quote: PriceQuote, port: Option<u16>) -> PriceQuote {
+        let port = port.unwrap_or(0);
+        let by_port = self
+            .latest
+            .entry(price_quote.issue_code.clone())
+            .or_default();
+        let book = by_port
+            .iter()
+            .filter(|(other_port, _)| **other_port != port)
+            .fold(price_quote.clone(), |book, (_, other)| {
+                merge_books(&book, other)
+            });
+        by_port.insert(port, price_quote);
+        book
+    }
+}
+
+// every quote replaced by its issue's book across all ports at that point
+pub fn aggregate_books_from_readers<R: Read>(
+    readers: Vec<R>,
+    options: &ParseOptions,
+) -> Result<(Vec<PriceQuote>, PacketParseStats), ParseFileError> {
+    let mut aggregator = BookAggregator::new();
+    let mut books = vec![];
+    let parse_stats = concat_price_quotes_with_port(readers, options, |price_quote, port| {
+        books.push(aggregator.push(price_quote, port));
+        ControlFlow::Continue(())
+    })?;
+    Ok((books, parse_stats))
+}
@@ -0,0 +1,703 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::price_quote::{fmt_rcv_time_iso, PriceQuote};
+
+// pearson's r over two equally long series. NaN when there are fewer than two
+// points or either series is flat
+pub fn pearson_correlation(xs: &[f64], ys: &[f64]) -> f64 {
+    let n = xs.len().min(ys.len());
+    if n < 2 {
+        return f64::NAN;
+    }
+    let (xs, ys) = (&xs[..n], &ys[..n]);
+    let mean_x = xs.iter().sum::<f64>() / n as f64;
+    let mean_y = ys.iter().sum::<f64>() / n as f64;
+
+    let mut covariance = 0.0;
+    let mut variance_x = 0.0;
+    let mut variance_y = 0.0;
+    for (x, y) in xs.iter().zip(ys) {
+        let dx = x - mean_x;
+        let dy = y - mean_y;
+        covariance += dx * dy;
+        variance_x += dx * dx;
+        variance_y += dy * dy;
+    }
+    if variance_x == 0.0 || variance_y == 0.0 {
+        return f64::NAN;
+    }
+    covariance / (variance_x.sqrt() * variance_y.sqrt())
+}
+
+// the n issue codes with the most quotes, busiest first. ties go to the
+// smaller issue code so the result is stable
+pub fn top_issues_by_count(quotes: &[PriceQuote], n: usize) -> Vec<String> {
+    let mut counts: HashMap<&str, u64> = HashMap::new();
+    for quote in quotes {
+        *counts.entry(quote.issue_code.as_str()).or_insert(0) += 1;
+    }
+    let mut counts: Vec<(&str, u64)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    counts
+        .into_iter()
+        .take(n)
+        .map(|(issue, _)| issue.to_string())
+        .collect()
+}
+
+// mid price of each issue sampled once per capture second, carrying the last
+// known value forward. None until an issue's first two sided quote. mids
+// are scaled by scale
+pub fn mid_price_grid(
+    quotes: &[PriceQuote],
+    issues: &[String],
+    scale: u32,
+) -> Vec<Vec<Option<f64>>> {
+    let column: HashMap<&str, usize> = issues
+        .iter()
+        .enumerate()
+        .map(|(i, issue)| (issue.as_str(), i))
+        .collect();
+    let mut relevant: Vec<&PriceQuote> = quotes
+        .iter()
+        .filter(|q| column.contains_key(q.issue_code.as_str()))
+        .collect();
+    if relevant.is_empty() {
+        return vec![];
+    }
+    relevant.sort_by_key(|q| q.packet_rcv_time);
+
+    let first_second = relevant[0].packet_rcv_time.as_secs();
+    let last_second = relevant[relevant.len() - 1].packet_rcv_time.as_secs();
+
+    let mut grid = Vec::with_capacity((last_second - first_second + 1) as usize);
+    let mut current = vec![None; issues.len()];
+    let mut next = 0;
+    for second in first_second..=last_second {
+        while next < relevant.len() && relevant[next].packet_rcv_time.as_secs() <= second {
+            let quote = relevant[next];
+            if let Some(mid) = quote.mid_price(scale) {
+                current[column[quote.issue_code.as_str()]] = Some(mid);
+            }
+            next += 1;
+        }
+        grid.push(current.clone());
+    }
+    grid
+}
+
+// correlation of every pair of the top n issues, using only the seconds
+// where both have a price. correlation doesn't care about scale, so the
+// mids are left raw
+pub fn correlation_matrix(quotes: &[PriceQuote], n: usize) -> (Vec<String>, Vec<Vec<f64>>) {
+    let issues = top_issues_by_count(quotes, n);
+    let grid = mid_price_grid(quotes, &issues, 0);
+
+    let mut matrix = vec![vec![f64::NAN; issues.len()]; issues.len()];
+    for i in 0..issues.len() {
+        for j in i..issues.len() {
+            let (xs, ys): (Vec<f64>, Vec<f64>) = grid
+                .iter()
+                .filter_map(|row| Some((row[i]?, row[j]?)))
+                .unzip();
+            let r = pearson_correlation(&xs, &ys);
+            matrix[i][j] = r;
+            matrix[j][i] = r;
+        }
+    }
+    (issues, matrix)
+}
+
+pub fn format_correlation_tsv(issues: &[String], matrix: &[Vec<f64>]) -> String {
+    let mut result = String::from("issue_code");
+    for issue in issues {
+        result.push('\t');
+        result.push_str(issue.trim());
+    }
+    result.push('\n');
+    for (issue, row) in issues.iter().zip(matrix) {
+        result.push_str(issue.trim());
+        for r in row {
+            result.push_str(&format!("\t{:.4}", r));
+        }
+        result.push('\n');
+    }
+    result
+}
+
+// time weighted average mid price of each issue over the capture. a mid
+// counts from its quote until the issue's next two sided quote, the last one
+// until the end of the capture, and the first one also back to the start.
+// so an issue quoted once gets that mid, and every issue is averaged over
+// the same window. one sided quotes are ignored, and mids are scaled by
+// scale
+pub fn compute_twap(quotes: &[PriceQuote], scale: u32) -> HashMap<String, f64> {
+    let (Some(start), Some(end)) = (
+        quotes.iter().map(|q| q.packet_rcv_time).min(),
+        quotes.iter().map(|q| q.packet_rcv_time).max(),
+    ) else {
+        return HashMap::new();
+    };
+
+    let mut by_issue: HashMap<&str, Vec<(Duration, f64)>> = HashMap::new();
+    for quote in quotes {
+        if let Some(mid) = quote.mid_price(scale) {
+            by_issue
+                .entry(quote.issue_code.as_str())
+                .or_default()
+                .push((quote.packet_rcv_time, mid));
+        }
+    }
+
+    let session = (end - start).as_secs_f64();
+    by_issue
+        .into_iter()
+        .map(|(issue, mut mids)| {
+            mids.sort_by_key(|&(time, _)| time);
+            // every quote at the same instant, nothing to weight by
+            if session == 0.0 {
+                return (issue.to_string(), mids[mids.len() - 1].1);
+            }
+            let mut weighted = 0.0;
+            for (i, &(time, mid)) in mids.iter().enumerate() {
+                let from = if i == 0 { start } else { time };
+                let until = mids.get(i + 1).map_or(end, |&(next, _)| next);
+                weighted += mid * (until - from).as_secs_f64();
+            }
+            (issue.to_string(), weighted / session)
+        })
+        .collect()
+}
+
+pub fn format_twap_tsv(twap: &HashMap<String, f64>) -> String {
+    let mut issues: Vec<_> = twap.iter().collect();
+    issues.sort_by(|a, b| a.0.cmp(b.0));
+    let mut result = String::from("issue_code\ttwap\n");
+    for (issue, price) in issues {
+        result.push_str(&format!("{}\t{:.4}\n", issue.trim(), price));
+    }
+    result
+}
+
+// nearest rank percentile of an already sorted slice, p from 0 to 100
+pub fn percentile(sorted: &[i64], p: f64) -> Option<i64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+// what --latency-report buckets up to by default, a minute
+pub const DEFAULT_LATENCY_MAX_US: u64 = 60_000_000;
+
+// each power of two is split into this many buckets, so a bucketed value is
+// known to within about 3%
+const SUB_BUCKET_BITS: u32 = 5;
+const SUB_BUCKETS: u64 = 1 << SUB_BUCKET_BITS;
+
+// below 2 * SUB_BUCKETS every microsecond gets its own bucket
+fn bucket_index(us: u64) -> usize {
+    if us < 2 * SUB_BUCKETS {
+        return us as usize;
+    }
+    let shift = 63 - us.leading_zeros() - SUB_BUCKET_BITS;
+    (shift as u64 * SUB_BUCKETS + (us >> shift)) as usize
+}
+
+// the values bucket_index puts in bucket i, from low up to but not including
+// high
+fn bucket_bounds(i: usize) -> (u64, u64) {
+    let i = i as u64;
+    if i < 2 * SUB_BUCKETS {
+        return (i, i + 1);
+    }
+    let shift = i / SUB_BUCKETS - 1;
+    let low = (i % SUB_BUCKETS + SUB_BUCKETS) << shift;
+    (low, low + (1 << shift))
+}
+
+// feed latencies in microseconds, counted into log sized buckets the way an
+// hdr histogram does it, so memory stays fixed however many quotes there
+// are. latencies past max_us are counted but not bucketed. negative ones,
+// where the capture clock is behind the exchange's, are counted on their own
+// and kept out of the percentiles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    max_us: u64,
+    counts: Vec<u64>,
+    above_max: u64,
+    negative: u64,
+    most_negative_us: Option<i64>,
+    min_us: Option<i64>,
+    max_seen_us: Option<i64>,
+}
+
+impl LatencyHistogram {
+    pub fn new(max_us: u64) -> Self {
+        LatencyHistogram {
+            max_us,
+            counts: vec![0; bucket_index(max_us) + 1],
+            above_max: 0,
+            negative: 0,
+            most_negative_us: None,
+            min_us: None,
+            max_seen_us: None,
+        }
+    }
+
+    pub fn record(&mut self, latency_us: i64) {
+        if latency_us < 0 {
+            self.negative += 1;
+            self.most_negative_us = Some(
+                self.most_negative_us
+                    .map_or(latency_us, |min| min.min(latency_us)),
+            );
+            return;
+        }
+        self.min_us = Some(self.min_us.map_or(latency_us, |min| min.min(latency_us)));
+        self.max_seen_us = Some(
+            self.max_seen_us
+                .map_or(latency_us, |max| max.max(latency_us)),
+        );
+        if latency_us as u64 > self.max_us {
+            self.above_max += 1;
+        } else {
+            self.counts[bucket_index(latency_us as u64)] += 1;
+        }
+    }
+
+    // latencies recorded that weren't negative
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum::<u64>() + self.above_max
+    }
+
+    pub fn negative(&self) -> u64 {
+        self.negative
+    }
+
+    // nearest rank, p from 0 to 100, over the latencies that weren't
+    // negative. the answer is the top of the bucket the rank falls in, or
+    // the largest latency seen if it falls past max_us
+    pub fn percentile(&self, p: f64) -> Option<i64> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+        let rank = ((p / 100.0 * count as f64).ceil() as u64).clamp(1, count);
+        let max_seen_us = self.max_seen_us?;
+        let mut seen = 0;
+        for (i, bucket_count) in self.counts.iter().enumerate() {
+            seen += bucket_count;
+            if seen >= rank {
+                let (_, high) = bucket_bounds(i);
+                return Some((high as i64 - 1).min(max_seen_us));
+            }
+        }
+        Some(max_seen_us)
+    }
+
+    // None until something has been recorded
+    pub fn report(&self) -> Option<LatencyReport> {
+        if self.count() == 0 && self.negative == 0 {
+            return None;
+        }
+        let buckets = self
+            .counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(i, &count)| {
+                let (low_us, high_us) = bucket_bounds(i);
+                LatencyBucket {
+                    low_us,
+                    high_us,
+                    count,
+                }
+            })
+            .collect();
+        Some(LatencyReport {
+            count: self.count(),
+            min_us: self.min_us,
+            p50_us: self.percentile(50.0),
+            p90_us: self.percentile(90.0),
+            p99_us: self.percentile(99.0),
+            p999_us: self.percentile(99.9),
+            max_us: self.max_seen_us,
+            deciles_us: (1..10).map(|i| self.percentile(i as f64 * 10.0)).collect(),
+            negative: self.negative,
+            most_negative_us: self.most_negative_us,
+            histogram_max_us: self.max_us,
+            above_max: self.above_max,
+            buckets,
+        })
+    }
+}
+
+// latencies from low_us up to but not including high_us
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct LatencyBucket {
+    pub low_us: u64,
+    pub high_us: u64,
+    pub count: u64,
+}
+
+// summary of feed latency in microseconds. the percentiles are over the
+// latencies that weren't negative, and are None if there were none
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct LatencyReport {
+    pub count: u64,
+    pub min_us: Option<i64>,
+    pub p50_us: Option<i64>,
+    pub p90_us: Option<i64>,
+    pub p99_us: Option<i64>,
+    pub p999_us: Option<i64>,
+    pub max_us: Option<i64>,
+    // p10 through p90
+    pub deciles_us: Vec<Option<i64>>,
+    // capture clock behind the exchange's
+    pub negative: u64,
+    pub most_negative_us: Option<i64>,
+    // past histogram_max_us, so in count but not in any bucket
+    pub histogram_max_us: u64,
+    pub above_max: u64,
+    // every bucket with anything in it, smallest first
+    pub buckets: Vec<LatencyBucket>,
+}
+
+impl LatencyReport {
+    pub fn from_latencies(latencies_us: Vec<i64>) -> Option<Self> {
+        let mut histogram = LatencyHistogram::new(DEFAULT_LATENCY_MAX_US);
+        for latency_us in latencies_us {
+            histogram.record(latency_us);
+        }
+        histogram.report()
+    }
+}
+
+impl fmt::Display for LatencyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const BAR_WIDTH: u64 = 40;
+        let fmt_us = |us: Option<i64>| us.map_or("-".to_string(), |us| format!("{}us", us));
+
+        writeln!(f, "  Latency ({} quotes):", self.count)?;
+        writeln!(f, "    min: {}", fmt_us(self.min_us))?;
+        writeln!(f, "    p50: {}", fmt_us(self.p50_us))?;
+        writeln!(f, "    p90: {}", fmt_us(self.p90_us))?;
+        writeln!(f, "    p99: {}", fmt_us(self.p99_us))?;
+        writeln!(f, "    p99.9: {}", fmt_us(self.p999_us))?;
+        writeln!(f, "    max: {}", fmt_us(self.max_us))?;
+        if self.negative > 0 {
+            writeln!(
+                f,
+                "    Negative (clock skew): {}, down to {}",
+                self.negative,
+                fmt_us(self.most_negative_us)
+            )?;
+        }
+        if self.above_max > 0 {
+            writeln!(
+                f,
+                "    Over {}us: {}",
+                self.histogram_max_us, self.above_max
+            )?;
+        }
+
+        // one row per power of two, the buckets are too fine to show as is
+        let mut rows: Vec<(u64, u64, u64)> = vec![];
+        for bucket in &self.buckets {
+            let (low, high) = match bucket.low_us {
+                0 => (0, 1),
+                low => {
+                    let low = 1 << (63 - low.leading_zeros());
+                    (low, low * 2)
+                }
+            };
+            match rows.last_mut() {
+                Some(row) if row.0 == low => row.2 += bucket.count,
+                _ => rows.push((low, high, bucket.count)),
+            }
+        }
+        let most = rows.iter().map(|row| row.2).max().unwrap_or(0);
+        for (low, high, count) in rows {
+            let bar = (count * BAR_WIDTH).div_ceil(most);
+            writeln!(
+                f,
+                "    {:>12} {:<width$} {}",
+                format!("{}-{}us", low, high),
+                "#".repeat(bar as usize),
+                count,
+                width = BAR_WIDTH as usize
+            )?;
+        }
+
+        // bars for the deciles are against the slowest of them
+        let slowest = self.deciles_us.iter().flatten().max().copied().unwrap_or(0);
+        if slowest > 0 {
+            writeln!(f, "    Deciles:")?;
+            for (i, decile_us) in self.deciles_us.iter().enumerate() {
+                let us = decile_us.unwrap_or(0).max(0) as u64;
+                let bar = (us * BAR_WIDTH).div_ceil(slowest as u64);
+                writeln!(
+                    f,
+                    "      p{}0 {:<width$} {}",
+                    i + 1,
+                    "#".repeat(bar as usize),
+                    fmt_us(*decile_us),
+                    width = BAR_WIDTH as usize
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// a duration like 500ms, 1s or 1m, for --rate-buckets. ns, us, ms, s, m and
+// h are understood, a bare number is seconds
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: u64 = number.parse().ok()?;
+    match unit.trim() {
+        "ns" => Some(Duration::from_nanos(number)),
+        "us" => Some(Duration::from_micros(number)),
+        "ms" => Some(Duration::from_millis(number)),
+        "" | "s" => Some(Duration::from_secs(number)),
+        "m" => Some(Duration::from_secs(number.checked_mul(60)?)),
+        "h" => Some(Duration::from_secs(number.checked_mul(3600)?)),
+        _ => None,
+    }
+}
+
+// quotes counted into fixed width slices of capture time, lined up on the
+// epoch. only slices something landed in are kept until series fills in
+// the rest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateCounter {
+    width_ns: u64,
+    counts: BTreeMap<u64, u64>,
+}
+
+impl RateCounter {
+    pub fn new(width: Duration) -> Self {
+        assert!(!width.is_zero(), "rate bucket width can't be zero");
+        RateCounter {
+            width_ns: width.as_nanos() as u64,
+            counts: BTreeMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, rcv_time: Duration) {
+        let bucket = rcv_time.as_nanos() as u64 / self.width_ns;
+        *self.counts.entry(bucket).or_insert(0) += 1;
+    }
+
+    // None until something has been recorded
+    pub fn series(&self) -> Option<RateSeries> {
+        let (&first, _) = self.counts.first_key_value()?;
+        let (&last, _) = self.counts.last_key_value()?;
+        let counts: Vec<u64> = (first..=last)
+            .map(|bucket| self.counts.get(&bucket).copied().unwrap_or(0))
+            .collect();
+        // the earliest of the busiest
+        let (peak_index, &peak_count) = counts
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, count)| **count)?;
+        Some(RateSeries {
+            start_ns: first * self.width_ns,
+            bucket_ns: self.width_ns,
+            peak_start_ns: (first + peak_index as u64) * self.width_ns,
+            peak_count,
+            counts,
+        })
+    }
+}
+
+// quotes per bucket from the first busy bucket to the last, empty ones in
+// between included. times are epoch nanos
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct RateSeries {
+    pub start_ns: u64,
+    pub bucket_ns: u64,
+    pub peak_start_ns: u64,
+    pub peak_count: u64,
+    pub counts: Vec<u64>,
+}
+
+impl fmt::Display for RateSeries {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const BARS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        const LINE_WIDTH: usize = 60;
+
+        writeln!(
+            f,
+            "  Quote Rate ({} buckets of {:?} from {}):",
+            self.counts.len(),
+            Duration::from_nanos(self.bucket_ns),
+            fmt_rcv_time_iso(Duration::from_nanos(self.start_ns))
+        )?;
+        // empty buckets are blank, anything else gets at least the lowest bar
+        let bars: Vec<char> = self
+            .counts
+            .iter()
+            .map(|&count| BARS[(count * 8).div_ceil(self.peak_count.max(1)) as usize])
+            .collect();
+        for line in bars.chunks(LINE_WIDTH) {
+            writeln!(f, "    |{}|", line.iter().collect::<String>())?;
+        }
+        writeln!(
+            f,
+            "    Peak: {} quotes at {}",
+            self.peak_count,
+            fmt_rcv_time_iso(Duration::from_nanos(self.peak_start_ns))
+        )
+    }
+}
+
+// how many of a burst's issues to name
+const BURST_TOP_ISSUES: usize = 3;
+
+// --burst-window and --burst-threshold. slides a window of the given width
+// over capture time, ending at each quote as it comes in. a burst starts
+// once the window holds more than threshold quotes and ends when it's back
+// down to threshold, and is reported by its busiest window. quotes have to
+// come in capture order, one that's earlier than the last is taken as
+// arriving with it
+#[derive(Debug, Clone)]
+pub struct BurstDetector {
+    window: Duration,
+    threshold: u64,
+    // capture time and issue of every quote in the window, oldest first
+    in_window: VecDeque<(Duration, String)>,
+    issue_counts: HashMap<String, u64>,
+    // the busiest window so far of the burst under way
+    current: Option<Burst>,
+    worst: Option<Burst>,
+}
+
+impl BurstDetector {
+    pub fn new(window: Duration, threshold: u64) -> Self {
+        assert!(!window.is_zero(), "burst window can't be zero");
+        BurstDetector {
+            window,
+            threshold,
+            in_window: VecDeque::new(),
+            issue_counts: HashMap::new(),
+            current: None,
+            worst: None,
+        }
+    }
+
+    // hands back the burst this quote ended, if it ended one
+    pub fn record(&mut self, rcv_time: Duration, issue_code: &str) -> Option<Burst> {
+        let rcv_time = match self.in_window.back() {
+            Some((latest, _)) => rcv_time.max(*latest),
+            None => rcv_time,
+        };
+        while let Some((oldest, _)) = self.in_window.front() {
+            if *oldest + self.window > rcv_time {
+                break;
+            }
+            let (_, issue_code) = self.in_window.pop_front().unwrap();
+            let count = self.issue_counts.get_mut(&issue_code).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                self.issue_counts.remove(&issue_code);
+            }
+        }
+        self.in_window.push_back((rcv_time, issue_code.to_string()));
+        *self.issue_counts.entry(issue_code.to_string()).or_insert(0) += 1;
+
+        let count = self.in_window.len() as u64;
+        if count <= self.threshold {
+            // the window only shrinks by the time a burst is over, so the
+            // quote that ends it is never part of it
+            return self.finish();
+        }
+        if self
+            .current
+            .as_ref()
+            .is_none_or(|busiest| count > busiest.count)
+        {
+            self.current = Some(self.snapshot());
+        }
+        None
+    }
+
+    // ends the burst under way, if there is one
+    pub fn finish(&mut self) -> Option<Burst> {
+        let burst = self.current.take()?;
+        if self
+            .worst
+            .as_ref()
+            .is_none_or(|worst| burst.count > worst.count)
+        {
+            self.worst = Some(burst.clone());
+        }
+        Some(burst)
+    }
+
+    // the busiest window of every burst that's ended, the earliest on a tie
+    pub fn worst(&self) -> Option<&Burst> {
+        self.worst.as_ref()
+    }
+
+    fn snapshot(&self) -> Burst {
+        let mut top_issues: Vec<(String, u64)> = self
+            .issue_counts
+            .iter()
+            .map(|(issue_code, count)| (issue_code.clone(), *count))
+            .collect();
+        top_issues.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top_issues.truncate(BURST_TOP_ISSUES);
+        Burst {
+            start_ns: self.in_window.front().unwrap().0.as_nanos() as u64,
+            window_ns: self.window.as_nanos() as u64,
+            count: self.in_window.len() as u64,
+            top_issues,
+        }
+    }
+}
+
+// a window that held more quotes than --burst-threshold. start_ns is the
+// epoch nanos of its first quote
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Burst {
+    pub start_ns: u64,
+    pub window_ns: u64,
+    pub count: u64,
+    // the issues with the most quotes in it, most first
+    pub top_issues: Vec<(String, u64)>,
+}
+
+impl fmt::Display for Burst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let top_issues: Vec<String> = self
+            .top_issues
+            .iter()
+            .map(|(issue_code, count)| format!("{} ({})", issue_code, count))
+            .collect();
+        write!(
+            f,
+            "{} quotes within {:?} from {}, mostly {}",
+            self.count,
+            Duration::from_nanos(self.window_ns),
+            fmt_rcv_time_iso(Duration::from_nanos(self.start_ns)),
+            top_issues.join(", ")
+        )
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::price_quote::{accept_hundredths, PriceQuote};
+
+// one issue's quotes over one window of accept time. the mids are None
+// when none of its quotes had both sides of the book
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Bucket {
+    pub issue_code: String,
+    // where the window starts, milliseconds since midnight
+    pub start_ms: u64,
+    pub updates: u64,
+    pub first_mid: Option<f64>,
+    pub last_mid: Option<f64>,
+    pub min_mid: Option<f64>,
+    pub max_mid: Option<f64>,
+}
+
+impl Bucket {
+    fn observe(&mut self, mid: Option<f64>) {
+        self.updates += 1;
+        let Some(mid) = mid else {
+            return;
+        };
+        self.first_mid.get_or_insert(mid);
+        self.last_mid = Some(mid);
+        self.min_mid = Some(self.min_mid.map_or(mid, |min| min.min(mid)));
+        self.max_mid = Some(self.max_mid.map_or(mid, |max| max.max(mid)));
+    }
+}
+
+impl fmt::Display for Bucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mid = |mid: Option<f64>| mid.map_or("-".to_string(), |mid| mid.to_string());
+        write!(
+            f,
+            "{:02}:{:02}:{:02}.{:03} {} {} updates, mid first {} last {} min {} max {}",
+            self.start_ms / 3_600_000,
+            self.start_ms / 60_000 % 60,
+            self.start_ms / 1000 % 60,
+            self.start_ms % 1000,
+            self.issue_code,
+            self.updates,
+            mid(self.first_mid),
+            mid(self.last_mid),
+            mid(self.min_mid),
+            mid(self.max_mid)
+        )
+    }
+}
+
+// --bucket, every issue's quotes grouped into fixed windows of accept time
+// starting at midnight. accept times only go down to hundredths, so windows
+// narrower than 10ms leave gaps. quotes arriving late still land in the
+// window they were accepted in, so nothing is handed back until the end
+pub struct Bucketer {
+    width_ms: u64,
+    // --price-scale, what the mids are in
+    scale: u32,
+    buckets: BTreeMap<(u64, String), Bucket>,
+    // quotes without a readable accept time, which can't go anywhere
+    pub unbucketed: u64,
+}
+
+impl Bucketer {
+    pub fn new(width_ms: u64, scale: u32) -> Self {
+        assert!(width_ms > 0, "bucket width can't be zero");
+        Bucketer {
+            width_ms,
+            scale,
+            buckets: BTreeMap::new(),
+            unbucketed: 0,
+        }
+    }
+
+    pub fn observe(&mut self, q: &PriceQuote) {
+        let Some(hundredths) = accept_hundredths(q.quote_accept_time) else {
+            self.unbucketed += 1;
+            return;
+        };
+        let start_ms = hundredths as u64 * 10 / self.width_ms * self.width_ms;
+        self.buckets
+            .entry((start_ms, q.issue_code.clone()))
+            .or_insert_with(|| Bucket {
+                issue_code: q.issue_code.clone(),
+                start_ms,
+                updates: 0,
+                first_mid: None,
+                last_mid: None,
+                min_mid: None,
+                max_mid: None,
+            })
+            .observe(q.mid_price(self.scale));
+    }
+
+    // by window, then issue code
+    pub fn finish(self) -> Vec<Bucket> {
+        self.buckets.into_values().collect()
+    }
+}
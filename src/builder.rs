@@ -0,0 +1,200 @@
+use std::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{NaiveTime, Timelike};
+
+use crate::market::{encode_ascii_code, MarketStatus, MarketType};
+use crate::price::{Price, Qty};
+use crate::price_quote::{Level, PriceQuote, LEVELS};
+
+// builds quotes for tests and docs without filling every field by hand.
+// setters take the logical values and store them the way the decoder would
+// have, so encode_b6034 on the result gives realistic wire bytes.
+//
+// unless set explicitly, the accept time is 09:00:00.00 plus one hundredth
+// per sequence number (so quotes built with rising seq are in order), and
+// the total volumes are the sums of the levels
+#[derive(Debug, Clone)]
+pub struct PriceQuoteBuilder {
+    quote: PriceQuote,
+    seq: u32,
+    // already encoded, so building doesn't need chrono
+    accept_time: Option<u64>,
+    total_bid_volume: Option<Qty>,
+    total_ask_volume: Option<Qty>,
+}
+
+impl Default for PriceQuoteBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PriceQuoteBuilder {
+    pub fn new() -> Self {
+        let mut quote = PriceQuote {
+            // 2011-02-16 09:00:00 KST
+            packet_rcv_time: Duration::from_secs(1297814400),
+            data_type: u16::from_le_bytes(*b"B6"),
+            information_type: u16::from_le_bytes(*b"03"),
+            issue_code: "KR4301F42629".to_string(),
+            ..Default::default()
+        };
+
+        let bids = [(525, 24), (520, 32), (515, 10), (510, 5), (505, 3)];
+        let asks = [(530, 1), (535, 7), (540, 12), (545, 4), (550, 9)];
+        for (level, (price, qty)) in quote.bids.iter_mut().zip(bids) {
+            *level = Level {
+                price: Price(price),
+                qty: Qty(qty),
+                order_count: encode_ascii_code(1, 4) as u32,
+            };
+        }
+        for (level, (price, qty)) in quote.asks.iter_mut().zip(asks) {
+            *level = Level {
+                price: Price(price),
+                qty: Qty(qty),
+                order_count: encode_ascii_code(1, 4) as u32,
+            };
+        }
+        quote.no_of_best_bid_valid_quote_total = encode_ascii_code(LEVELS as u64, 5);
+        quote.no_of_best_ask_valid_quote_total = encode_ascii_code(LEVELS as u64, 5);
+
+        PriceQuoteBuilder {
+            quote,
+            seq: 0,
+            accept_time: None,
+            total_bid_volume: None,
+            total_ask_volume: None,
+        }
+        .market_type(MarketType::Derivatives)
+        .market_status(MarketStatus::Continuous)
+        .seq(1)
+    }
+
+    pub fn issue_code(mut self, issue_code: &str) -> Self {
+        self.quote.issue_code = issue_code.to_string();
+        self
+    }
+
+    // issue sequence number, three ascii digits on the wire
+    pub fn seq(mut self, seq: u32) -> Self {
+        self.seq = seq;
+        let digits = encode_ascii_code(seq as u64, 3).to_le_bytes();
+        self.quote.issue_seq_no = u32::from_be_bytes([0, digits[0], digits[1], digits[2]]);
+        self
+    }
+
+    pub fn rcv_time(mut self, rcv_time: Duration) -> Self {
+        self.quote.packet_rcv_time = rcv_time;
+        self
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn accept_time(mut self, accept_time: NaiveTime) -> Self {
+        self.accept_time = Some(encode_accept_time(accept_time));
+        self
+    }
+
+    pub fn market_type(mut self, market_type: MarketType) -> Self {
+        self.quote.market_type = encode_ascii_code(market_type.code() as u64, 1) as u8;
+        self
+    }
+
+    pub fn market_status(mut self, market_status: MarketStatus) -> Self {
+        self.quote.market_status_type = encode_ascii_code(market_status.code() as u64, 2) as u16;
+        self
+    }
+
+    // level 0 is the best
+    pub fn bid(mut self, level: usize, price: u64, qty: u64) -> Self {
+        self.quote.bids[level].price = Price(price);
+        self.quote.bids[level].qty = Qty(qty);
+        self
+    }
+
+    pub fn ask(mut self, level: usize, price: u64, qty: u64) -> Self {
+        self.quote.asks[level].price = Price(price);
+        self.quote.asks[level].qty = Qty(qty);
+        self
+    }
+
+    // replaces the whole side, best first. levels past the end are emptied
+    pub fn bids(mut self, levels: &[(u64, u64)]) -> Self {
+        Self::fill_side(&mut self.quote.bids, levels);
+        self
+    }
+
+    pub fn asks(mut self, levels: &[(u64, u64)]) -> Self {
+        Self::fill_side(&mut self.quote.asks, levels);
+        self
+    }
+
+    pub fn bid_orders(mut self, level: usize, count: u32) -> Self {
+        self.quote.bids[level].order_count = encode_ascii_code(count as u64, 4) as u32;
+        self
+    }
+
+    pub fn ask_orders(mut self, level: usize, count: u32) -> Self {
+        self.quote.asks[level].order_count = encode_ascii_code(count as u64, 4) as u32;
+        self
+    }
+
+    pub fn total_bid_volume(mut self, volume: u64) -> Self {
+        self.total_bid_volume = Some(Qty(volume));
+        self
+    }
+
+    pub fn total_ask_volume(mut self, volume: u64) -> Self {
+        self.total_ask_volume = Some(Qty(volume));
+        self
+    }
+
+    pub fn build(self) -> PriceQuote {
+        let mut quote = self.quote;
+
+        quote.quote_accept_time = self.accept_time.unwrap_or_else(|| {
+            // wraps at midnight like a NaiveTime would
+            let hundredths = (9 * 360_000 + self.seq as u64) % (24 * 360_000);
+            encode_hundredths(hundredths)
+        });
+
+        quote.total_bid_quote_volume = self
+            .total_bid_volume
+            .unwrap_or_else(|| quote.bid_level_volume());
+        quote.total_ask_quote_volume = self
+            .total_ask_volume
+            .unwrap_or_else(|| quote.ask_level_volume());
+        quote
+    }
+
+    fn fill_side(side: &mut [Level; LEVELS], levels: &[(u64, u64)]) {
+        for (i, level) in side.iter_mut().enumerate() {
+            let (price, qty) = levels.get(i).copied().unwrap_or((0, 0));
+            level.price = Price(price);
+            level.qty = Qty(qty);
+        }
+    }
+}
+
+// HHMMSScc as ascii, packed little endian like the decoder stores it
+#[cfg(feature = "chrono")]
+pub fn encode_accept_time(time: NaiveTime) -> u64 {
+    encode_hundredths(
+        time.num_seconds_from_midnight() as u64 * 100 + (time.nanosecond() / 10_000_000) as u64,
+    )
+}
+
+// hundredths of a second since midnight, encoded as above
+fn encode_hundredths(hundredths: u64) -> u64 {
+    let digits = format!(
+        "{:02}{:02}{:02}{:02}",
+        hundredths / 360_000,
+        hundredths / 6_000 % 60,
+        hundredths / 100 % 60,
+        hundredths % 100
+    );
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(digits.as_bytes());
+    u64::from_le_bytes(bytes)
+}
@@ -0,0 +1,32 @@
+use std::io::{self, Write};
+
+use byteorder::{LittleEndian, WriteBytesExt};
+
+use crate::price_quote::PriceQuote;
+
+pub type CborError = ciborium::ser::Error<std::io::Error>;
+
+// encodes one quote as a cbor map keyed by the struct field names
+pub fn cbor_encode(q: &PriceQuote) -> Result<Vec<u8>, CborError> {
+    let mut buf = Vec::new();
+    ciborium::into_writer(q, &mut buf)?;
+    Ok(buf)
+}
+
+// writes a quote as a u32 little endian length followed by the cbor bytes,
+// so a reader can walk the file record by record
+pub fn write_cbor_record<W: Write>(writer: &mut W, q: &PriceQuote) -> Result<(), CborError> {
+    let record = cbor_encode(q)?;
+    writer.write_u32::<LittleEndian>(record.len() as u32)?;
+    writer.write_all(&record)?;
+    Ok(())
+}
+
+// the io error underneath, for callers that only deal in io::Result. a
+// quote always encodes, so the only other kind can't really happen
+pub fn into_io_error(err: CborError) -> io::Error {
+    match err {
+        ciborium::ser::Error::Io(err) => err,
+        ciborium::ser::Error::Value(msg) => io::Error::other(msg),
+    }
+}
@@ -0,0 +1,135 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use chrono::{TimeZone, Utc};
+
+use crate::price_quote::{
+    PriceQuote, ASK_PRICE_FIELDS, ASK_QTY_FIELDS, BID_PRICE_FIELDS, BID_QTY_FIELDS, LEVELS,
+};
+
+// the same columns as the xlsx output, one row per quote. prices and
+// quantities are exact decimals, an empty side leaves spread and mid empty
+pub struct CsvWriter<W: Write> {
+    writer: W,
+    // --price-scale, the implied decimal places of the wire prices
+    scale: u32,
+}
+
+impl<W: Write> CsvWriter<W> {
+    // writes the header row straight away
+    pub fn new(mut writer: W, scale: u32) -> io::Result<Self> {
+        let mut headers = vec![
+            "packet_rcv_time",
+            "quote_accept_time",
+            "issue_code",
+            "issue_seq_no",
+            "market_status_type",
+            "spread",
+            "mid_price",
+            "total_bid_quote_volume",
+            "total_ask_quote_volume",
+        ];
+        for i in 0..LEVELS {
+            headers.push(BID_PRICE_FIELDS[i]);
+            headers.push(BID_QTY_FIELDS[i]);
+        }
+        for i in 0..LEVELS {
+            headers.push(ASK_PRICE_FIELDS[i]);
+            headers.push(ASK_QTY_FIELDS[i]);
+        }
+        writeln!(writer, "{}", headers.join(","))?;
+        Ok(CsvWriter { writer, scale })
+    }
+
+    pub fn write(&mut self, quote: &PriceQuote) -> io::Result<()> {
+        let rcv_time = Utc
+            .timestamp_opt(
+                quote.packet_rcv_time.as_secs() as i64,
+                quote.packet_rcv_time.subsec_nanos(),
+            )
+            .single()
+            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S%.6f").to_string())
+            .unwrap_or_default();
+        let accept_time = quote
+            .accept_time()
+            .map(|t| t.format("%H:%M:%S%.3f").to_string())
+            .unwrap_or_default();
+
+        // signed, like the xlsx output, so crossed books show up
+        let best_bid = quote.bids[0].price;
+        let best_ask = quote.asks[0].price;
+        let spread = if !best_bid.is_zero() && !best_ask.is_zero() {
+            (best_ask.to_decimal(self.scale) - best_bid.to_decimal(self.scale)).to_string()
+        } else {
+            String::new()
+        };
+        // the numbers the ascii spells rather than the raw wire fields
+        let seq = quote.seq().map(|seq| seq.to_string()).unwrap_or_default();
+        let mid = quote
+            .mid_price(self.scale)
+            .map(|mid| mid.to_string())
+            .unwrap_or_default();
+
+        write!(
+            self.writer,
+            "{},{},{},{},{},{},{},{},{}",
+            rcv_time,
+            accept_time,
+            csv_field(&quote.issue_code),
+            seq,
+            quote.market_status().code(),
+            spread,
+            mid,
+            quote.total_bid_quote_volume,
+            quote.total_ask_quote_volume
+        )?;
+        for level in quote.bids.iter().chain(quote.asks.iter()) {
+            // exact, whatever --price-precision says
+            write!(
+                self.writer,
+                ",{},{}",
+                level.price.to_decimal(self.scale),
+                level.qty
+            )?;
+        }
+        writeln!(self.writer)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+// quotes to path as zstd compressed csv, compressed as they're written so
+// the plain csv never touches the disk. finish must be called to write the
+// end of the zstd frame
+pub struct ZstdCsvWriter {
+    csv: CsvWriter<zstd::stream::write::Encoder<'static, BufWriter<File>>>,
+}
+
+impl ZstdCsvWriter {
+    pub fn create(path: &str, level: i32, scale: u32) -> io::Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        let encoder = zstd::stream::write::Encoder::new(file, level)?;
+        Ok(ZstdCsvWriter {
+            csv: CsvWriter::new(encoder, scale)?,
+        })
+    }
+
+    pub fn write(&mut self, quote: &PriceQuote) -> io::Result<()> {
+        self.csv.write(quote)
+    }
+
+    pub fn finish(self) -> io::Result<()> {
+        self.csv.into_inner().finish()?.flush()
+    }
+}
+
+// quoted only when it has to be. issue codes never do on a healthy feed
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
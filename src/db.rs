@@ -0,0 +1,129 @@
+use duckdb::types::{TimeUnit, Value};
+use duckdb::{params_from_iter, Connection};
+
+use chrono::Timelike;
+
+use crate::price_quote::{
+    PriceQuote, ASK_COUNT_FIELDS, ASK_PRICE_FIELDS, ASK_QTY_FIELDS, BID_COUNT_FIELDS,
+    BID_PRICE_FIELDS, BID_QTY_FIELDS, LEVELS,
+};
+
+// rows per insert transaction
+pub const BATCH_SIZE: usize = 10_000;
+
+// the quotes table's columns and their types, in insert order. like the npy
+// output the numbers are what the fields spell, NULL where they're
+// unreadable, and prices are doubles scaled by --price-scale
+fn columns() -> Vec<(&'static str, &'static str)> {
+    let mut columns = vec![
+        ("packet_rcv_time", "TIMESTAMP"),
+        ("quote_accept_time", "TIME"),
+        ("issue_code", "VARCHAR"),
+        ("issue_seq_no", "INTEGER"),
+        ("market_type", "VARCHAR"),
+        ("market_status_type", "USMALLINT"),
+        ("total_bid_quote_volume", "UBIGINT"),
+        ("total_ask_quote_volume", "UBIGINT"),
+    ];
+    for i in 0..LEVELS {
+        columns.push((BID_PRICE_FIELDS[i], "DOUBLE"));
+        columns.push((BID_QTY_FIELDS[i], "UBIGINT"));
+        columns.push((BID_COUNT_FIELDS[i], "UBIGINT"));
+    }
+    for i in 0..LEVELS {
+        columns.push((ASK_PRICE_FIELDS[i], "DOUBLE"));
+        columns.push((ASK_QTY_FIELDS[i], "UBIGINT"));
+        columns.push((ASK_COUNT_FIELDS[i], "UBIGINT"));
+    }
+    columns.push(("no_of_best_bid_valid_quote_total", "UBIGINT"));
+    columns.push(("no_of_best_ask_valid_quote_total", "UBIGINT"));
+    columns
+}
+
+fn row(quote: &PriceQuote, scale: u32) -> Vec<Value> {
+    let count = |n: Option<u64>| n.map_or(Value::Null, Value::UBigInt);
+    let mut row = vec![
+        Value::Timestamp(
+            TimeUnit::Microsecond,
+            quote.packet_rcv_time.as_micros() as i64,
+        ),
+        quote.accept_time().map_or(Value::Null, |t| {
+            Value::Time64(
+                TimeUnit::Microsecond,
+                t.num_seconds_from_midnight() as i64 * 1_000_000 + t.nanosecond() as i64 / 1_000,
+            )
+        }),
+        Value::Text(quote.issue_code.clone()),
+        quote
+            .seq()
+            .map_or(Value::Null, |seq| Value::Int(seq as i32)),
+        Value::Text(quote.market().to_string()),
+        Value::USmallInt(quote.market_status().code()),
+        Value::UBigInt(quote.total_bid_quote_volume.raw()),
+        Value::UBigInt(quote.total_ask_quote_volume.raw()),
+    ];
+    for level in quote.bids.iter().chain(&quote.asks) {
+        row.push(Value::Double(level.price.to_f64(scale)));
+        row.push(Value::UBigInt(level.qty.raw()));
+        row.push(count(level.orders()));
+    }
+    row.push(count(quote.bid_order_total()));
+    row.push(count(quote.ask_order_total()));
+    row
+}
+
+// the quotes table, if it isn't there already
+pub fn create_duckdb_schema(conn: &Connection) -> duckdb::Result<()> {
+    let columns: Vec<String> = columns()
+        .iter()
+        .map(|(name, kind)| format!("{} {}", name, kind))
+        .collect();
+    conn.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS quotes ({});",
+        columns.join(", ")
+    ))
+}
+
+// built after the rows are in, which is cheaper than keeping it up to date
+// through the load
+pub fn create_duckdb_index(conn: &Connection) -> duckdb::Result<()> {
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS quotes_issue_accept_time \
+         ON quotes (issue_code, quote_accept_time);",
+    )
+}
+
+// appends to the quotes table, BATCH_SIZE rows to a transaction. prices
+// are scaled by scale
+pub fn insert_quotes(
+    conn: &mut Connection,
+    quotes: &[PriceQuote],
+    scale: u32,
+) -> duckdb::Result<()> {
+    let names: Vec<&str> = columns().iter().map(|(name, _)| *name).collect();
+    let placeholders = vec!["?"; names.len()].join(", ");
+    let sql = format!(
+        "INSERT INTO quotes ({}) VALUES ({})",
+        names.join(", "),
+        placeholders
+    );
+    for batch in quotes.chunks(BATCH_SIZE) {
+        let tx = conn.transaction()?;
+        {
+            let mut statement = tx.prepare_cached(&sql)?;
+            for quote in batch {
+                statement.execute(params_from_iter(row(quote, scale)))?;
+            }
+        }
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+// creates the database at path if needed and adds quotes to it
+pub fn write_quotes_to_duckdb(quotes: &[PriceQuote], path: &str, scale: u32) -> duckdb::Result<()> {
+    let mut conn = Connection::open(path)?;
+    create_duckdb_schema(&conn)?;
+    insert_quotes(&mut conn, quotes, scale)?;
+    create_duckdb_index(&conn)
+}
@@ -0,0 +1,58 @@
+use etherparse::{SlicedPacket, TransportSlice};
+
+const IP_PROTOCOL_GRE: u8 = 47;
+
+// gre protocol types for what's inside
+const GRE_TRANSPARENT_ETHERNET: u16 = 0x6558;
+const GRE_ERSPAN_II: u16 = 0x88be;
+const GRE_ERSPAN_III: u16 = 0x22eb;
+
+const GRE_CHECKSUM_PRESENT: u16 = 0x8000;
+const GRE_KEY_PRESENT: u16 = 0x2000;
+const GRE_SEQUENCE_PRESENT: u16 = 0x1000;
+const GRE_VERSION_MASK: u16 = 0x0007;
+
+// mirrored traffic shows up as the original ethernet frame inside gre,
+// optionally with an erspan header in between. hands back the inner frame,
+// or None when this isn't a gre packet carrying ethernet
+pub fn decapsulate(frame: &[u8]) -> Option<&[u8]> {
+    let outer = SlicedPacket::from_ethernet(frame).ok()?;
+    if outer.ip.as_ref()?.is_fragmenting_payload() {
+        return None;
+    }
+    match outer.transport {
+        Some(TransportSlice::Unknown(IP_PROTOCOL_GRE)) => strip_gre(outer.payload),
+        _ => None,
+    }
+}
+
+fn strip_gre(gre: &[u8]) -> Option<&[u8]> {
+    let flags = u16::from_be_bytes([*gre.first()?, *gre.get(1)?]);
+    let protocol = u16::from_be_bytes([*gre.get(2)?, *gre.get(3)?]);
+    // version 1 is pptp, which never carries ethernet
+    if flags & GRE_VERSION_MASK != 0 {
+        return None;
+    }
+
+    let mut header_len = 4;
+    for optional in [GRE_CHECKSUM_PRESENT, GRE_KEY_PRESENT, GRE_SEQUENCE_PRESENT] {
+        if flags & optional != 0 {
+            header_len += 4;
+        }
+    }
+    let inner = gre.get(header_len..)?;
+
+    match protocol {
+        GRE_TRANSPARENT_ETHERNET => Some(inner),
+        // type I has no erspan header at all and is told apart from type II
+        // by the missing sequence number
+        GRE_ERSPAN_II if flags & GRE_SEQUENCE_PRESENT == 0 => Some(inner),
+        GRE_ERSPAN_II => inner.get(8..),
+        GRE_ERSPAN_III => {
+            // the o bit says an 8 byte platform specific subheader follows
+            let has_subheader = inner.get(11)? & 0x01 != 0;
+            inner.get(if has_subheader { 20 } else { 12 }..)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::price_quote::{
+    Level, PriceQuote, ASK_COUNT_FIELDS, ASK_PRICE_FIELDS, ASK_QTY_FIELDS, BID_COUNT_FIELDS,
+    BID_PRICE_FIELDS, BID_QTY_FIELDS, LEVELS,
+};
+
+// quotes that only one side has, matched on content. the hash only narrows
+// it down, a hit still has to agree on every field changed_fields looks at.
+// duplicates count, so a quote seen twice in a and once in b shows up once
+// in only_in_a
+pub struct ContentDiff<'a> {
+    pub only_in_a: Vec<&'a PriceQuote>,
+    pub only_in_b: Vec<&'a PriceQuote>,
+}
+
+impl ContentDiff<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.only_in_a.is_empty() && self.only_in_b.is_empty()
+    }
+}
+
+pub fn content_diff<'a>(a: &'a [PriceQuote], b: &'a [PriceQuote]) -> ContentDiff<'a> {
+    let mut remaining_in_b: HashMap<u64, Vec<&PriceQuote>> = HashMap::new();
+    for price_quote in b {
+        remaining_in_b
+            .entry(price_quote.content_hash())
+            .or_default()
+            .push(price_quote);
+    }
+
+    let mut only_in_a = vec![];
+    for price_quote in a {
+        let matched = remaining_in_b
+            .get_mut(&price_quote.content_hash())
+            .and_then(|quotes| {
+                let i = quotes
+                    .iter()
+                    .position(|other| changed_fields(price_quote, other).is_empty())?;
+                Some(quotes.swap_remove(i))
+            });
+        if matched.is_none() {
+            only_in_a.push(price_quote);
+        }
+    }
+
+    // keep b's leftovers in file order
+    let mut only_in_b: Vec<&PriceQuote> = remaining_in_b.into_values().flatten().collect();
+    only_in_b.sort_by_key(|q| q.packet_rcv_time);
+
+    ContentDiff {
+        only_in_a,
+        only_in_b,
+    }
+}
+
+// a field two quotes with the same key disagree on, both values as they'd
+// be printed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub a: String,
+    pub b: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuoteChange<'a> {
+    // only in a
+    Removed(&'a PriceQuote),
+    // only in b
+    Added(&'a PriceQuote),
+    Changed {
+        a: &'a PriceQuote,
+        b: &'a PriceQuote,
+        fields: Vec<FieldChange>,
+    },
+}
+
+impl QuoteChange<'_> {
+    // a's quote where there is one
+    fn quote(&self) -> &PriceQuote {
+        match self {
+            QuoteChange::Removed(quote) | QuoteChange::Added(quote) => quote,
+            QuoteChange::Changed { a, .. } => a,
+        }
+    }
+}
+
+// two captures compared quote by quote. the quotes content_diff can't match
+// are paired on issue code and accept time, in file order when a key turns
+// up more than once
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoteSetDiff<'a> {
+    // by accept time, like the quotes themselves sort
+    pub changes: Vec<QuoteChange<'a>>,
+    // quotes of a that b has too, field for field
+    pub unchanged: usize,
+}
+
+impl QuoteSetDiff<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn removed(&self) -> usize {
+        self.count(|change| matches!(change, QuoteChange::Removed(_)))
+    }
+
+    pub fn added(&self) -> usize {
+        self.count(|change| matches!(change, QuoteChange::Added(_)))
+    }
+
+    pub fn changed(&self) -> usize {
+        self.count(|change| matches!(change, QuoteChange::Changed { .. }))
+    }
+
+    fn count(&self, f: impl Fn(&QuoteChange) -> bool) -> usize {
+        self.changes.iter().filter(|change| f(change)).count()
+    }
+}
+
+pub fn diff_quote_sets<'a>(a: &'a [PriceQuote], b: &'a [PriceQuote]) -> QuoteSetDiff<'a> {
+    // whatever content_diff matches is unchanged, only its leftovers are
+    // paired up by key, so the two never disagree on what differs
+    let content = content_diff(a, b);
+    let unchanged = a.len() - content.only_in_a.len();
+
+    let mut remaining_in_b: HashMap<(&str, u64), VecDeque<&PriceQuote>> = HashMap::new();
+    for price_quote in content.only_in_b {
+        remaining_in_b
+            .entry((&price_quote.issue_code, price_quote.quote_accept_time))
+            .or_default()
+            .push_back(price_quote);
+    }
+
+    let mut changes = vec![];
+    for price_quote in content.only_in_a {
+        let matched = remaining_in_b
+            .get_mut(&(&price_quote.issue_code, price_quote.quote_accept_time))
+            .and_then(|quotes| quotes.pop_front());
+        changes.push(match matched {
+            Some(other) => QuoteChange::Changed {
+                a: price_quote,
+                b: other,
+                fields: changed_fields(price_quote, other),
+            },
+            None => QuoteChange::Removed(price_quote),
+        });
+    }
+    changes.extend(
+        remaining_in_b
+            .into_values()
+            .flatten()
+            .map(QuoteChange::Added),
+    );
+    // stable, so removals and changes keep a's order and additions b's
+    // among themselves
+    changes.sort_by(|x, y| x.quote().cmp(y.quote()));
+
+    QuoteSetDiff { changes, unchanged }
+}
+
+// every wire field a and b disagree on. the capture time is left out, like
+// content_hash leaves it out, since two captures of the same feed never
+// agree on it
+pub fn changed_fields(a: &PriceQuote, b: &PriceQuote) -> Vec<FieldChange> {
+    let mut fields = vec![];
+    let mut compare = |field: &'static str, x: String, y: String| {
+        if x != y {
+            fields.push(FieldChange { field, a: x, b: y });
+        }
+    };
+    let seq = |q: &PriceQuote| q.seq().map_or("-".to_string(), |seq| seq.to_string());
+    let orders = |level: &Level| level.orders().map_or("-".to_string(), |n| n.to_string());
+    let total = |total: Option<u64>| total.map_or("-".to_string(), |n| n.to_string());
+
+    compare(
+        "data_type",
+        a.data_type.to_string(),
+        b.data_type.to_string(),
+    );
+    compare(
+        "information_type",
+        a.information_type.to_string(),
+        b.information_type.to_string(),
+    );
+    compare(
+        "market_type",
+        a.market().to_string(),
+        b.market().to_string(),
+    );
+    compare("issue_code", a.issue_code.clone(), b.issue_code.clone());
+    compare("issue_seq_no", seq(a), seq(b));
+    compare(
+        "market_status_type",
+        a.market_status().to_string(),
+        b.market_status().to_string(),
+    );
+    compare(
+        "total_bid_quote_volume",
+        a.total_bid_quote_volume.to_string(),
+        b.total_bid_quote_volume.to_string(),
+    );
+    compare(
+        "total_ask_quote_volume",
+        a.total_ask_quote_volume.to_string(),
+        b.total_ask_quote_volume.to_string(),
+    );
+    for i in 0..LEVELS {
+        let (x, y) = (&a.bids[i], &b.bids[i]);
+        compare(
+            BID_PRICE_FIELDS[i],
+            x.price.to_string(),
+            y.price.to_string(),
+        );
+        compare(BID_QTY_FIELDS[i], x.qty.to_string(), y.qty.to_string());
+        compare(BID_COUNT_FIELDS[i], orders(x), orders(y));
+    }
+    for i in 0..LEVELS {
+        let (x, y) = (&a.asks[i], &b.asks[i]);
+        compare(
+            ASK_PRICE_FIELDS[i],
+            x.price.to_string(),
+            y.price.to_string(),
+        );
+        compare(ASK_QTY_FIELDS[i], x.qty.to_string(), y.qty.to_string());
+        compare(ASK_COUNT_FIELDS[i], orders(x), orders(y));
+    }
+    compare(
+        "no_of_best_bid_valid_quote_total",
+        total(a.bid_order_total()),
+        total(b.bid_order_total()),
+    );
+    compare(
+        "no_of_best_ask_valid_quote_total",
+        total(a.ask_order_total()),
+        total(b.ask_order_total()),
+    );
+    fields
+}
@@ -0,0 +1,143 @@
+use thiserror::Error;
+
+// something wrong inside a single quote payload. offsets are from the start
+// of the payload
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum QuoteDecodeError {
+    #[error("field `{field}` at offset {offset} needs {needed} bytes but only {available} remain")]
+    Truncated {
+        field: &'static str,
+        offset: usize,
+        needed: usize,
+        available: usize,
+    },
+    #[error("field `{field}` at offset {offset} is not a decimal number: {found:?}")]
+    InvalidDigits {
+        field: &'static str,
+        offset: usize,
+        found: String,
+    },
+}
+
+impl QuoteDecodeError {
+    pub fn field(&self) -> &'static str {
+        match self {
+            QuoteDecodeError::Truncated { field, .. } => field,
+            QuoteDecodeError::InvalidDigits { field, .. } => field,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            QuoteDecodeError::Truncated { .. } => "truncated",
+            QuoteDecodeError::InvalidDigits { .. } => "invalid digits",
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        match self {
+            QuoteDecodeError::Truncated { offset, .. } => *offset,
+            QuoteDecodeError::InvalidDigits { offset, .. } => *offset,
+        }
+    }
+}
+
+// a quote pasted as hex that couldn't be read. offsets are byte offsets into
+// the string
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum HexQuoteError {
+    #[error("{found:?} at offset {offset} is not a hex digit")]
+    InvalidChar { offset: usize, found: char },
+    #[error("odd number of hex digits ({digits})")]
+    OddLength { digits: usize },
+    #[error(transparent)]
+    Decode(#[from] QuoteDecodeError),
+}
+
+// why a packet never made it to the quote decoder
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum PacketClassifyError {
+    #[error("malformed frame: {0}")]
+    Malformed(String),
+    #[error("not udp")]
+    NotUdp,
+    #[error("wrong port {port}")]
+    WrongPort { port: u16 },
+    #[error("not a price quote")]
+    NotAPriceQuote { port: u16 },
+    // had the prefix, so it's a quote, just not one of the right length
+    #[error("quote payload is {len} bytes, expected {expected}")]
+    MalformedQuote {
+        port: u16,
+        len: usize,
+        expected: usize,
+    },
+    #[error("bad checksum")]
+    BadChecksum { port: u16 },
+}
+
+// anything that stops a whole capture from being parsed. packet indices
+// count from 1, in file order
+#[derive(Debug, Error)]
+pub enum ParseFileError {
+    #[error("couldn't open {path}: {source}")]
+    Open {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("couldn't read capture: {source}")]
+    Read { source: std::io::Error },
+    #[error("bad glob pattern {pattern}: {reason}")]
+    Glob { pattern: String, reason: String },
+    #[error("no files match {pattern}")]
+    NoMatches { pattern: String },
+    #[error("couldn't write event log: {source}")]
+    EventLog { source: std::io::Error },
+    #[error("not a readable pcap file: {0}")]
+    Header(pcap_file::PcapError),
+    #[error("packet {packet_index}: couldn't read pcap record: {source}")]
+    Record {
+        packet_index: u64,
+        source: pcap_file::PcapError,
+    },
+    #[error("packet {packet_index}: failed to parse price quote: {source}")]
+    Decode {
+        packet_index: u64,
+        source: QuoteDecodeError,
+    },
+}
+
+// anything that ends a quote stream early. packet indices count from 1, in
+// file order
+#[derive(Debug, Error)]
+pub enum QuoteStreamError {
+    #[error("couldn't read capture: {0}")]
+    Read(#[from] std::io::Error),
+    #[error("pcapng captures can't be streamed, only pcap and raw")]
+    UnsupportedPcapNg,
+    #[error("capture ends inside the pcap file header")]
+    TruncatedHeader,
+    #[error("not a pcap file, magic is {magic:#010x}")]
+    BadMagic { magic: u32 },
+    #[error("packet {packet_index}: record claims {len} bytes")]
+    RecordTooLarge { packet_index: u64, len: u32 },
+    #[error("packet {packet_index}: capture ends inside the record")]
+    TruncatedRecord { packet_index: u64 },
+    #[error(transparent)]
+    Parse(#[from] ParseFileError),
+}
+
+// a --filter-script that couldn't be loaded or failed on a quote
+#[cfg(feature = "lua")]
+#[derive(Debug, Error)]
+pub enum ScriptError {
+    #[error("couldn't read {path}: {source}")]
+    Open {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("script doesn't define a filter function")]
+    NoFilter,
+    #[error(transparent)]
+    Lua(#[from] mlua::Error),
+}
@@ -0,0 +1,65 @@
+use std::fmt;
+use std::io::Read;
+use std::mem::size_of;
+use std::ops::ControlFlow;
+
+use crate::error::ParseFileError;
+use crate::parsing::{quote_parser, read_capture, PacketLoop};
+use crate::{ParseOptions, PriceQuote};
+
+// roughly what collecting every quote in a capture would take, for deciding
+// whether it fits in ram before committing to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    pub packets: u64,
+    // packets that pass the port, prefix and length checks. an upper bound,
+    // some may still fail to decode or be filtered out
+    pub quotes: u64,
+    pub bytes: u64,
+}
+
+impl MemoryEstimate {
+    pub fn megabytes(&self) -> f64 {
+        self.bytes as f64 / (1024.0 * 1024.0)
+    }
+}
+
+impl fmt::Display for MemoryEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} packets look like quotes, about {:.2} MB to hold them all",
+            self.quotes,
+            self.packets,
+            self.megabytes()
+        )
+    }
+}
+
+// a pass over the captures that classifies every packet like a real parse
+// would but never decodes a payload
+pub fn estimate_memory<R: Read>(
+    readers: Vec<R>,
+    options: &ParseOptions,
+) -> Result<MemoryEstimate, ParseFileError> {
+    let options = ParseOptions {
+        print_rejected: false,
+        prefix_report_limit: 0,
+        event_log: None,
+        stats_interval: None,
+        ..options.clone()
+    };
+    let mut packet_loop = PacketLoop::new(quote_parser(&options), options);
+    packet_loop.decode = false;
+    for reader in readers {
+        // nothing is decoded, so there's never a message to stop on
+        let _ = read_capture(&mut packet_loop, reader, &mut |_, _| {
+            ControlFlow::Continue(())
+        })?;
+    }
+    Ok(MemoryEstimate {
+        packets: packet_loop.parse_stats.packet_count,
+        quotes: packet_loop.candidates,
+        bytes: packet_loop.candidates * size_of::<PriceQuote>() as u64,
+    })
+}
@@ -0,0 +1,128 @@
+#[cfg(feature = "cli")]
+use std::fs::File;
+#[cfg(feature = "cli")]
+use std::io::BufWriter;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::error::{PacketClassifyError, QuoteDecodeError};
+use crate::price_quote::PriceQuote;
+use crate::schema::{CRATE_VERSION, JSON_SCHEMA_VERSION};
+
+// --event-log, one json object per line for everything notable the packet
+// loop runs into:
+//   {"schema_version": 1, "crate_version": "...", "timestamp": <capture
+//    seconds>, "event_type": "...", "detail": {...}}
+// event types are quote, rejected, checksum_failure, decode_failed and
+// anomalous_market. there are no sequence gap events, B6034 has no message
+// sequence number, issue_seq_no stays the same for an issue all day
+pub struct EventLogger {
+    writer: Box<dyn Write>,
+}
+
+impl EventLogger {
+    pub fn new(writer: impl Write + 'static) -> Self {
+        EventLogger {
+            writer: Box::new(writer),
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    pub fn create(path: &str) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    pub fn log(&mut self, timestamp: Duration, event_type: &str, detail: Value) -> io::Result<()> {
+        // written by hand to keep the keys in this order, serde_json sorts them
+        writeln!(
+            self.writer,
+            "{{\"schema_version\":{},\"crate_version\":{},\"timestamp\":{},\"event_type\":{},\"detail\":{}}}",
+            JSON_SCHEMA_VERSION,
+            Value::from(CRATE_VERSION),
+            Value::from(timestamp.as_secs_f64()),
+            Value::from(event_type),
+            detail
+        )
+    }
+
+    // a decoded quote, plus an anomalous market event if it has one
+    pub fn quote(
+        &mut self,
+        timestamp: Duration,
+        packet_index: u64,
+        quote: &PriceQuote,
+    ) -> io::Result<()> {
+        self.log(
+            timestamp,
+            "quote",
+            json!({
+                "packet": packet_index,
+                "issue_code": quote.issue_code,
+                "seq": quote.seq(),
+                "market_type": quote.market().code(),
+                "market_status": quote.market_status().code(),
+                "best_bid": quote.bids[0].price.raw(),
+                "best_ask": quote.asks[0].price.raw(),
+            }),
+        )?;
+
+        let (market, status) = (quote.market(), quote.market_status());
+        if !market.is_known() || !status.is_known() {
+            self.log(
+                timestamp,
+                "anomalous_market",
+                json!({
+                    "packet": packet_index,
+                    "issue_code": quote.issue_code,
+                    "market_type": market.code(),
+                    "market_status": status.code(),
+                }),
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn rejected(
+        &mut self,
+        timestamp: Duration,
+        packet_index: u64,
+        reason: &PacketClassifyError,
+    ) -> io::Result<()> {
+        if let PacketClassifyError::BadChecksum { port } = reason {
+            return self.log(
+                timestamp,
+                "checksum_failure",
+                json!({ "packet": packet_index, "port": port }),
+            );
+        }
+        self.log(
+            timestamp,
+            "rejected",
+            json!({ "packet": packet_index, "reason": reason.to_string() }),
+        )
+    }
+
+    pub fn decode_failed(
+        &mut self,
+        timestamp: Duration,
+        packet_index: u64,
+        err: &QuoteDecodeError,
+    ) -> io::Result<()> {
+        self.log(
+            timestamp,
+            "decode_failed",
+            json!({
+                "packet": packet_index,
+                "field": err.field(),
+                "kind": err.kind(),
+                "offset": err.offset(),
+            }),
+        )
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
@@ -0,0 +1,80 @@
+use std::io::Read;
+use std::sync::Mutex;
+
+use crate::error::ParseFileError;
+use crate::parsing::{concat_price_quotes_from_readers, parse_price_quotes_from_reader, Stopwatch};
+use crate::{PacketParseStats, ParseOptions, PriceQuote};
+
+// like concat_price_quotes_from_readers, but up to jobs captures are parsed
+// at once, each on its own thread. every capture is independent, so the
+// quotes and stats come out exactly as a sequential read would give them:
+// captures in the order given, packet indices in errors counting across all
+// of them, and the first capture to fail gives the error.
+//
+// an event log needs packets in order through one writer, so with one set
+// this falls back to reading one capture at a time. --require-prefix-exact
+// reports up to its limit per capture rather than overall
+pub fn concat_price_quotes_from_readers_parallel<R: Read + Send>(
+    readers: Vec<R>,
+    options: &ParseOptions,
+    jobs: usize,
+) -> Result<(Vec<PriceQuote>, PacketParseStats), ParseFileError> {
+    if jobs <= 1 || readers.len() <= 1 || options.event_log.is_some() {
+        return concat_price_quotes_from_readers(readers, options);
+    }
+
+    let start = Stopwatch::start();
+    let readers_len = readers.len();
+    let queue = Mutex::new(readers.into_iter().enumerate());
+    let results = Mutex::new(vec![]);
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.min(readers_len) {
+            scope.spawn(|| {
+                loop {
+                    // the lock is only held to take the next capture
+                    let next = queue.lock().unwrap().next();
+                    let Some((index, reader)) = next else {
+                        break;
+                    };
+                    let result = parse_price_quotes_from_reader(reader, options);
+                    results.lock().unwrap().push((index, result));
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    let mut price_quotes = vec![];
+    let mut parse_stats = PacketParseStats::new();
+    for (_, result) in results {
+        let (quotes, stats) =
+            result.map_err(|err| offset_packet_index(err, parse_stats.packet_count))?;
+        price_quotes.extend(quotes);
+        parse_stats.merge(&stats);
+    }
+    parse_stats.parse_time = start.elapsed();
+    Ok((price_quotes, parse_stats))
+}
+
+// an error from one capture, with its packet index moved past the packets of
+// the captures before it
+fn offset_packet_index(err: ParseFileError, offset: u64) -> ParseFileError {
+    match err {
+        ParseFileError::Record {
+            packet_index,
+            source,
+        } => ParseFileError::Record {
+            packet_index: packet_index + offset,
+            source,
+        },
+        ParseFileError::Decode {
+            packet_index,
+            source,
+        } => ParseFileError::Decode {
+            packet_index: packet_index + offset,
+            source,
+        },
+        err => err,
+    }
+}
@@ -0,0 +1,110 @@
+// the quote decoder for C callers. build a library with
+//   cargo rustc --release --features ffi --lib --crate-type staticlib
+// and include include/tsc_solution.h, which the build regenerates
+use std::os::raw::c_char;
+use std::time::Duration;
+
+use crate::error::QuoteDecodeError;
+use crate::price_quote::{Level, PriceQuote, ISSUE_CODE_LEN, LEVELS};
+
+pub const TSC_OK: i32 = 0;
+// buf or out was null
+pub const TSC_ERR_NULL: i32 = -1;
+// buf ends before the message does
+pub const TSC_ERR_SHORT: i32 = -2;
+// a numeric field holds something other than ascii digits
+pub const TSC_ERR_INVALID: i32 = -3;
+
+// spelled out so the header doesn't need the rest of the crate
+pub const TSC_LEVELS: usize = 5;
+// room for the issue code and its nul
+pub const TSC_ISSUE_CODE_SIZE: usize = 13;
+const _: () = assert!(TSC_LEVELS == LEVELS && TSC_ISSUE_CODE_SIZE == ISSUE_CODE_LEN + 1);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TscLevel {
+    pub price: u64,
+    pub qty: u64,
+    pub order_count: u32,
+}
+
+// PriceQuote field for field, except the issue code is a nul terminated
+// string and there's no receive time since there's no capture
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TscQuote {
+    pub data_type: u16,
+    pub information_type: u16,
+    pub market_type: u8,
+    pub issue_code: [c_char; TSC_ISSUE_CODE_SIZE],
+    pub issue_seq_no: u32,
+    pub market_status_type: u16,
+    pub total_bid_quote_volume: u64,
+    pub total_ask_quote_volume: u64,
+    pub bids: [TscLevel; TSC_LEVELS],
+    pub asks: [TscLevel; TSC_LEVELS],
+    pub no_of_best_bid_valid_quote_total: u64,
+    pub no_of_best_ask_valid_quote_total: u64,
+    pub quote_accept_time: u64,
+}
+
+impl From<&Level> for TscLevel {
+    fn from(level: &Level) -> Self {
+        TscLevel {
+            price: level.price.raw(),
+            qty: level.qty.raw(),
+            order_count: level.order_count,
+        }
+    }
+}
+
+impl From<&PriceQuote> for TscQuote {
+    fn from(quote: &PriceQuote) -> Self {
+        let mut issue_code = [0; TSC_ISSUE_CODE_SIZE];
+        for (dst, src) in issue_code
+            .iter_mut()
+            .zip(quote.issue_code.bytes().take(ISSUE_CODE_LEN))
+        {
+            *dst = src as c_char;
+        }
+        TscQuote {
+            data_type: quote.data_type,
+            information_type: quote.information_type,
+            market_type: quote.market_type,
+            issue_code,
+            issue_seq_no: quote.issue_seq_no,
+            market_status_type: quote.market_status_type,
+            total_bid_quote_volume: quote.total_bid_quote_volume.raw(),
+            total_ask_quote_volume: quote.total_ask_quote_volume.raw(),
+            bids: quote.bids.each_ref().map(TscLevel::from),
+            asks: quote.asks.each_ref().map(TscLevel::from),
+            no_of_best_bid_valid_quote_total: quote.no_of_best_bid_valid_quote_total,
+            no_of_best_ask_valid_quote_total: quote.no_of_best_ask_valid_quote_total,
+            quote_accept_time: quote.quote_accept_time,
+        }
+    }
+}
+
+/// Decodes one B6034 payload into `out`. Returns `TSC_OK`, or one of the
+/// `TSC_ERR_*` codes, in which case `out` is left untouched.
+///
+/// # Safety
+///
+/// `buf` must point to `len` readable bytes and `out` to a writable
+/// `TscQuote`. Either may be null, which is reported as `TSC_ERR_NULL`.
+#[no_mangle]
+pub unsafe extern "C" fn tsc_decode_quote(buf: *const u8, len: usize, out: *mut TscQuote) -> i32 {
+    if buf.is_null() || out.is_null() {
+        return TSC_ERR_NULL;
+    }
+    let bytes = std::slice::from_raw_parts(buf, len);
+    match PriceQuote::from_bytes(Duration::ZERO, bytes) {
+        Ok(quote) => {
+            out.write(TscQuote::from(&quote));
+            TSC_OK
+        }
+        Err(QuoteDecodeError::Truncated { .. }) => TSC_ERR_SHORT,
+        Err(QuoteDecodeError::InvalidDigits { .. }) => TSC_ERR_INVALID,
+    }
+}
@@ -0,0 +1,192 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use byteorder::{BigEndian, WriteBytesExt};
+use chrono::{TimeZone, Utc};
+
+use crate::price_quote::{Level, PriceQuote, LEVELS};
+
+const SOH: char = '\x01';
+
+pub const DEFAULT_SENDER: &str = "TSC";
+pub const DEFAULT_TARGET: &str = "CLIENT";
+
+// SenderCompID and TargetCompID of the messages, --fix-sender and
+// --fix-target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompIds {
+    pub sender: String,
+    pub target: String,
+}
+
+impl CompIds {
+    pub fn new(sender: &str, target: &str) -> Self {
+        CompIds {
+            sender: sender.to_string(),
+            target: target.to_string(),
+        }
+    }
+}
+
+impl Default for CompIds {
+    fn default() -> Self {
+        CompIds::new(DEFAULT_SENDER, DEFAULT_TARGET)
+    }
+}
+
+impl PriceQuote {
+    // a FIX 4.2 MarketDataRequest (35=V) for the quote's issue and the
+    // MarketDataSnapshotFullRefresh (35=W) that answers it, both from the
+    // sender comp id so they can share one sequence. seq is the next
+    // MsgSeqNum and ends up two further on. SendingTime is the capture time.
+    // the default comp ids, and prices as they came
+    pub fn to_quickfix_messages(&self, seq: &mut u32) -> (String, String) {
+        self.to_quickfix_messages_with(seq, &CompIds::default(), 0)
+    }
+
+    // to_quickfix_messages between comp_ids, with prices scaled by scale
+    pub fn to_quickfix_messages_with(
+        &self,
+        seq: &mut u32,
+        comp_ids: &CompIds,
+        scale: u32,
+    ) -> (String, String) {
+        let sending_time = Utc
+            .timestamp_opt(
+                self.packet_rcv_time.as_secs() as i64,
+                self.packet_rcv_time.subsec_nanos(),
+            )
+            .single()
+            .map(|dt| dt.format("%Y%m%d-%H:%M:%S%.3f").to_string())
+            .unwrap_or_default();
+        let md_req_id = format!("{}-{}", self.issue_code, seq);
+
+        let request = vec![
+            (262, md_req_id.clone()),
+            // snapshot, of the whole book
+            (263, "0".to_string()),
+            (264, LEVELS.to_string()),
+            (267, "2".to_string()),
+            (269, "0".to_string()),
+            (269, "1".to_string()),
+            (146, "1".to_string()),
+            (55, self.issue_code.clone()),
+        ];
+
+        // empty levels are left out
+        let mut entries = vec![];
+        for (entry_type, side) in [("0", &self.bids), ("1", &self.asks)] {
+            for (i, level) in side.iter().enumerate() {
+                if !level.price.is_zero() {
+                    entries.push((entry_type, i + 1, level));
+                }
+            }
+        }
+        let mut snapshot = vec![
+            (262, md_req_id),
+            (55, self.issue_code.clone()),
+            (268, entries.len().to_string()),
+        ];
+        for (entry_type, position, level) in entries {
+            snapshot.extend(md_entry(entry_type, position, level, scale));
+        }
+
+        let header = |msg_type: &str, seq: u32| {
+            vec![
+                (35, msg_type.to_string()),
+                (49, comp_ids.sender.clone()),
+                (56, comp_ids.target.clone()),
+                (34, seq.to_string()),
+                (52, sending_time.clone()),
+            ]
+        };
+        let request = fix_message([header("V", *seq), request].concat());
+        let snapshot = fix_message([header("W", *seq + 1), snapshot].concat());
+        *seq += 2;
+        (request, snapshot)
+    }
+}
+
+fn md_entry(entry_type: &str, position: usize, level: &Level, scale: u32) -> Vec<(u32, String)> {
+    let mut fields = vec![
+        (269, entry_type.to_string()),
+        (270, level.price.to_decimal(scale).to_string()),
+        (271, level.qty.raw().to_string()),
+        (290, position.to_string()),
+    ];
+    if let Some(orders) = level.orders() {
+        fields.push((346, orders.to_string()));
+    }
+    fields
+}
+
+// BeginString, BodyLength, the fields and CheckSum, each ending in SOH
+fn fix_message(fields: Vec<(u32, String)>) -> String {
+    let body: String = fields
+        .iter()
+        .map(|(tag, value)| format!("{}={}{}", tag, value, SOH))
+        .collect();
+    let mut message = format!("8=FIX.4.2{}9={}{}{}", SOH, body.len(), SOH, body);
+    let checksum = message.bytes().fold(0u8, |sum, b| sum.wrapping_add(b));
+    message.push_str(&format!("10={:03}{}", checksum, SOH));
+    message
+}
+
+// --output quickfix, the message files of a QuickFIX/J FileStore for the
+// sender's session: messages back to back in prefix.body, and for each one
+// its MsgSeqNum, offset and length in prefix.header as big endian i32, i64
+// and i32. the next sequence numbers go in prefix.senderseqnums and
+// prefix.targetseqnums when it's finished
+pub struct QuickFixStore {
+    prefix: String,
+    comp_ids: CompIds,
+    price_scale: u32,
+    body: BufWriter<File>,
+    header: BufWriter<File>,
+    offset: u64,
+    seq: u32,
+}
+
+impl QuickFixStore {
+    pub fn create(prefix: &str, comp_ids: CompIds, price_scale: u32) -> io::Result<Self> {
+        Ok(QuickFixStore {
+            prefix: prefix.to_string(),
+            comp_ids,
+            price_scale,
+            body: BufWriter::new(File::create(format!("{}.body", prefix))?),
+            header: BufWriter::new(File::create(format!("{}.header", prefix))?),
+            offset: 0,
+            seq: 1,
+        })
+    }
+
+    pub fn write(&mut self, quote: &PriceQuote) -> io::Result<()> {
+        let first = self.seq;
+        let (request, snapshot) =
+            quote.to_quickfix_messages_with(&mut self.seq, &self.comp_ids, self.price_scale);
+        for (seq, message) in [(first, request), (first + 1, snapshot)] {
+            self.body.write_all(message.as_bytes())?;
+            self.header.write_i32::<BigEndian>(seq as i32)?;
+            self.header.write_i64::<BigEndian>(self.offset as i64)?;
+            self.header.write_i32::<BigEndian>(message.len() as i32)?;
+            self.offset += message.len() as u64;
+        }
+        Ok(())
+    }
+
+    // nothing has come back from the target, so it's still at 1
+    pub fn finish(mut self) -> io::Result<()> {
+        self.body.flush()?;
+        self.header.flush()?;
+        write_seq_num(&format!("{}.senderseqnums", self.prefix), self.seq)?;
+        write_seq_num(&format!("{}.targetseqnums", self.prefix), 1)
+    }
+}
+
+// the way java's DataOutput.writeUTF does it, a u16 length then the digits
+fn write_seq_num(path: &str, seq: u32) -> io::Result<()> {
+    let digits = seq.to_string();
+    let mut file = File::create(path)?;
+    file.write_u16::<BigEndian>(digits.len() as u16)?;
+    file.write_all(digits.as_bytes())
+}
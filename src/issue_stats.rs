@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::price::{Price, PriceFormat, Qty};
+use crate::price_quote::{Level, PriceQuote};
+use crate::quote_index::{IssueCodeId, IssueInterner};
+
+// --per-issue-stats, for one issue. the volume deltas add up how much the
+// total bid and ask volumes moved between the issue's consecutive quotes,
+// either way
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct IssueStats {
+    pub issue_code: String,
+    pub quotes: u64,
+    pub first_rcv_time: Duration,
+    pub last_rcv_time: Duration,
+    // over the quotes with a spread, one sided and crossed books have none.
+    // raw price units, the mean too
+    pub min_spread: Option<Price>,
+    pub max_spread: Option<Price>,
+    pub mean_spread: Option<f64>,
+    pub bid_volume_delta: u64,
+    pub ask_volume_delta: u64,
+    // mean number of levels with a price on each side
+    pub mean_bid_levels: f64,
+    pub mean_ask_levels: f64,
+    // top of the book in the issue's last quote to arrive
+    pub last_bid: Level,
+    pub last_ask: Level,
+}
+
+// what's kept per issue while quotes are still arriving
+#[derive(Debug, Clone)]
+struct IssueTally {
+    quotes: u64,
+    first_rcv_time: Duration,
+    last_rcv_time: Duration,
+    min_spread: Option<Price>,
+    max_spread: Option<Price>,
+    spread_sum: u128,
+    spread_quotes: u64,
+    last_bid_volume: Qty,
+    last_ask_volume: Qty,
+    bid_volume_delta: u64,
+    ask_volume_delta: u64,
+    bid_levels: u64,
+    ask_levels: u64,
+    last_bid: Level,
+    last_ask: Level,
+}
+
+// builds IssueStats one quote at a time. memory grows with the number of
+// issues, not quotes
+#[derive(Debug, Clone, Default)]
+pub struct IssueStatsCollector {
+    interner: IssueInterner,
+    tallies: HashMap<IssueCodeId, IssueTally>,
+}
+
+impl IssueStatsCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_quotes(price_quotes: &[PriceQuote]) -> Self {
+        let mut collector = Self::new();
+        for price_quote in price_quotes {
+            collector.observe(price_quote);
+        }
+        collector
+    }
+
+    pub fn observe(&mut self, price_quote: &PriceQuote) {
+        let id = self.interner.intern(&price_quote.issue_code);
+        let rcv_time = price_quote.packet_rcv_time;
+        let bid_volume = price_quote.total_bid_quote_volume;
+        let ask_volume = price_quote.total_ask_quote_volume;
+        let tally = self.tallies.entry(id).or_insert_with(|| IssueTally {
+            quotes: 0,
+            first_rcv_time: rcv_time,
+            last_rcv_time: rcv_time,
+            min_spread: None,
+            max_spread: None,
+            spread_sum: 0,
+            spread_quotes: 0,
+            last_bid_volume: bid_volume,
+            last_ask_volume: ask_volume,
+            bid_volume_delta: 0,
+            ask_volume_delta: 0,
+            bid_levels: 0,
+            ask_levels: 0,
+            last_bid: Level::default(),
+            last_ask: Level::default(),
+        });
+
+        tally.quotes += 1;
+        tally.first_rcv_time = tally.first_rcv_time.min(rcv_time);
+        tally.last_rcv_time = tally.last_rcv_time.max(rcv_time);
+        if let Some(spread) = price_quote.spread() {
+            tally.min_spread = Some(tally.min_spread.map_or(spread, |min| min.min(spread)));
+            tally.max_spread = Some(tally.max_spread.map_or(spread, |max| max.max(spread)));
+            tally.spread_sum += spread.raw() as u128;
+            tally.spread_quotes += 1;
+        }
+        tally.bid_volume_delta += bid_volume.raw().abs_diff(tally.last_bid_volume.raw());
+        tally.ask_volume_delta += ask_volume.raw().abs_diff(tally.last_ask_volume.raw());
+        tally.last_bid_volume = bid_volume;
+        tally.last_ask_volume = ask_volume;
+        tally.bid_levels += price_quote.populated_bid_levels() as u64;
+        tally.ask_levels += price_quote.populated_ask_levels() as u64;
+        tally.last_bid = price_quote.bids[0];
+        tally.last_ask = price_quote.asks[0];
+    }
+
+    pub fn len(&self) -> usize {
+        self.tallies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tallies.is_empty()
+    }
+
+    // most quotes first, ties by issue code. top keeps only that many
+    pub fn finish(&self, top: Option<usize>) -> Vec<IssueStats> {
+        let mut issues: Vec<IssueStats> = self
+            .tallies
+            .iter()
+            .map(|(id, tally)| IssueStats {
+                issue_code: self.interner.resolve(*id).to_string(),
+                quotes: tally.quotes,
+                first_rcv_time: tally.first_rcv_time,
+                last_rcv_time: tally.last_rcv_time,
+                min_spread: tally.min_spread,
+                max_spread: tally.max_spread,
+                mean_spread: (tally.spread_quotes > 0)
+                    .then(|| tally.spread_sum as f64 / tally.spread_quotes as f64),
+                bid_volume_delta: tally.bid_volume_delta,
+                ask_volume_delta: tally.ask_volume_delta,
+                mean_bid_levels: tally.bid_levels as f64 / tally.quotes as f64,
+                mean_ask_levels: tally.ask_levels as f64 / tally.quotes as f64,
+                last_bid: tally.last_bid,
+                last_ask: tally.last_ask,
+            })
+            .collect();
+        issues.sort_by(|a, b| {
+            b.quotes
+                .cmp(&a.quotes)
+                .then_with(|| a.issue_code.cmp(&b.issue_code))
+        });
+        if let Some(top) = top {
+            issues.truncate(top);
+        }
+        issues
+    }
+}
+
+pub fn format_issue_stats(issues: &[IssueStats], format: PriceFormat) -> String {
+    // capture time of day in utc, there's no date to spare the width for
+    let fmt_time = |time: Duration| {
+        let secs = time.as_secs() % 86_400;
+        format!(
+            "{:02}:{:02}:{:02}.{:03}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60,
+            time.subsec_millis()
+        )
+    };
+    let fmt_spread = |spread: Option<Price>| match spread {
+        Some(spread) => spread.display(format).to_string(),
+        None => "-".to_string(),
+    };
+    let scale = 10f64.powi(format.scale as i32);
+
+    let mut result = format!(
+        "{:<12} {:>8} {:>12} {:>12} {:>10} {:>10} {:>10} {:>14} {:>14} {:>8} {:>8}\n",
+        "Issue",
+        "Quotes",
+        "First",
+        "Last",
+        "Min Spread",
+        "Max Spread",
+        "Mean",
+        "Bid Vol Delta",
+        "Ask Vol Delta",
+        "Bid Lvls",
+        "Ask Lvls"
+    );
+    for stats in issues {
+        let mean = match stats.mean_spread {
+            Some(mean) => format!("{:.2}", mean / scale),
+            None => "-".to_string(),
+        };
+        result.push_str(&format!(
+            "{:<12} {:>8} {:>12} {:>12} {:>10} {:>10} {:>10} {:>14} {:>14} {:>8.2} {:>8.2}\n",
+            stats.issue_code,
+            stats.quotes,
+            fmt_time(stats.first_rcv_time),
+            fmt_time(stats.last_rcv_time),
+            fmt_spread(stats.min_spread),
+            fmt_spread(stats.max_spread),
+            mean,
+            stats.bid_volume_delta,
+            stats.ask_volume_delta,
+            stats.mean_bid_levels,
+            stats.mean_ask_levels
+        ));
+    }
+    result
+}
+
+// --top, the busiest issues and where their books were left
+pub fn format_top_issues(issues: &[IssueStats], format: PriceFormat) -> String {
+    let fmt_price = |level: &Level| {
+        if level.price.is_zero() {
+            "-".to_string()
+        } else {
+            level.price.display(format).to_string()
+        }
+    };
+    let mut result = format!(
+        "{:<12} {:>8} {:>10} {:>10} {:>10} {:>10}\n",
+        "Issue", "Quotes", "Bid Qty", "Bid", "Ask", "Ask Qty"
+    );
+    for stats in issues {
+        result.push_str(&format!(
+            "{:<12} {:>8} {:>10} {:>10} {:>10} {:>10}\n",
+            stats.issue_code,
+            stats.quotes,
+            stats.last_bid.qty.raw(),
+            fmt_price(&stats.last_bid),
+            fmt_price(&stats.last_ask),
+            stats.last_ask.qty.raw()
+        ));
+    }
+    result
+}
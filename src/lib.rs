@@ -0,0 +1,80 @@
+pub mod aggregate;
+pub mod analytics;
+pub mod bucket;
+pub mod builder;
+#[cfg(feature = "serde")]
+pub mod codec;
+#[cfg(feature = "cli")]
+pub mod csv_writer;
+#[cfg(feature = "duckdb")]
+pub mod db;
+pub mod decap;
+pub mod diff;
+pub mod error;
+pub mod estimate;
+#[cfg(feature = "serde")]
+pub mod event_log;
+#[cfg(feature = "cli")]
+pub mod fanout;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "cli")]
+pub mod fix;
+pub mod issue_stats;
+pub mod market;
+pub mod merge;
+#[cfg(feature = "cli")]
+pub mod mock;
+#[cfg(feature = "cli")]
+pub mod npy_writer;
+pub mod parser;
+pub mod parsing;
+pub mod price;
+pub mod price_quote;
+#[cfg(feature = "cli")]
+pub mod printer;
+pub mod quote_index;
+pub mod record;
+pub mod report;
+pub mod resample;
+#[cfg(feature = "serde")]
+pub mod schema;
+#[cfg(feature = "lua")]
+pub mod script;
+#[cfg(feature = "chrono")]
+pub mod session;
+pub mod sink;
+#[cfg(feature = "cli")]
+pub mod split;
+#[cfg(feature = "async")]
+pub mod stream;
+pub mod testdata;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod thresholds;
+pub mod trade_inferrer;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "cli")]
+pub mod xlsx_writer;
+
+pub use builder::PriceQuoteBuilder;
+pub use estimate::{estimate_memory, MemoryEstimate};
+#[cfg(feature = "cli")]
+pub use fanout::concat_price_quotes_from_readers_parallel;
+pub use merge::{merge_price_quotes_from_readers, merge_price_quotes_with};
+#[cfg(feature = "chrono")]
+pub use parsing::{clamp_accept_times, find_accept_time_violation, ClampedTimes};
+pub use parsing::{
+    classify_packet, concat_price_quotes_from_readers, concat_price_quotes_with,
+    concat_price_quotes_with_payload, concat_price_quotes_with_port, concat_quote_headers_with,
+    describe_prefix, filter_one_sided, parse_price_quotes_from_bytes,
+    parse_price_quotes_from_reader, parse_price_quotes_with, read_issue_list, validate_volumes,
+    InputFormat, PacketParseStats, ParseOptions, PortCounts, StatsInterval,
+};
+#[cfg(feature = "cli")]
+pub use parsing::{expand_capture_paths, parse_packets_from_file, parse_price_quotes_from_file};
+pub use price_quote::{PriceQuote, QuoteHeader};
+#[cfg(feature = "async")]
+pub use stream::{quote_stream, QuoteStream};
+pub use thresholds::{Thresholds, Violation};
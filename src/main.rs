@@ -1,16 +1,119 @@
+use std::collections::HashMap;
 use std::fs::File;
+use std::io::{BufReader, BufWriter, IsTerminal, Write};
+use std::ops::ControlFlow;
+use std::path::Path;
+use std::time::Duration;
 
-use clap::{arg, command, ArgAction};
-use etherparse::{SlicedPacket, TransportSlice};
-use pcap_file::pcap::PcapReader;
-use price_quote::PriceQuote;
+use chrono::{NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+use clap::parser::ValueSource;
+use clap::{arg, command, ArgAction, ArgMatches, Command};
+use tsc_solution::bucket::Bucketer;
+use tsc_solution::csv_writer::ZstdCsvWriter;
+use tsc_solution::issue_stats::{format_top_issues, IssueStatsCollector};
+use tsc_solution::market::MarketStatus;
+use tsc_solution::parser::B6034Parser;
+use tsc_solution::price::PriceFormat;
+use tsc_solution::price_quote::{
+    encode_hex, DisplayStyle, NumericEncoding, PriceQuote, SortKey, B6034_LEN, ISSUE_CODE_LEN,
+};
+use tsc_solution::printer::{QuotePrinter, SpreadThresholds};
+#[cfg(feature = "lua")]
+use tsc_solution::script::ScriptFilter;
+use tsc_solution::session::SessionStats;
+#[cfg(feature = "redis")]
+use tsc_solution::sink::redis::RedisSink;
+#[cfg(feature = "websocket")]
+use tsc_solution::sink::websocket::WsServer;
+use tsc_solution::split::{DateSplitter, IssueSplitter};
+use tsc_solution::trade_inferrer::TradeInferrer;
+use tsc_solution::{
+    aggregate, analytics, codec, diff, fix, mock, npy_writer, record, report, resample, schema,
+    xlsx_writer,
+};
+use tsc_solution::{
+    clamp_accept_times, concat_price_quotes_from_readers,
+    concat_price_quotes_from_readers_parallel, concat_price_quotes_with,
+    concat_price_quotes_with_payload, concat_quote_headers_with, estimate_memory,
+    expand_capture_paths, find_accept_time_violation, merge_price_quotes_from_readers,
+    merge_price_quotes_with, parse_price_quotes_from_file, read_issue_list, validate_volumes,
+    InputFormat, PacketParseStats, ParseOptions, StatsInterval, Thresholds,
+};
 
-pub mod price_quote;
+// the exit status when the capture broke --max-failed, --max-failed-pct or
+// --min-quotes. 1 is an error and 2 a missing feature
+const THRESHOLD_EXIT_CODE: i32 = 3;
+
+// options that only make sense once every quote is in memory. without any of
+// them quotes are printed as they're parsed
+const NEEDS_ALL_QUOTES: [&str; 15] = [
+    "sorted",
+    "sort-by",
+    "canonical",
+    "assert-sorted",
+    "clamp-times",
+    "resample-to-ticks",
+    "aggregate-book",
+    "output-path",
+    "prefix-histogram",
+    "heatmap",
+    "top-by-volume",
+    "session-stats",
+    "first-last-seen",
+    "correlation",
+    "compute-twap",
+];
 
 fn main() {
     let matches = command!() // uses metadata from Cargo.toml
         .about("PCap Parser")
-        .arg(arg!([PATH] "Path to the pcap file").required(true))
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true)
+        .subcommand(
+            Command::new("diff")
+                .about("Report quotes that appear in only one of two capture files")
+                .arg(arg!(<A> "First pcap file"))
+                .arg(arg!(<B> "Second pcap file")),
+        )
+        .subcommand(
+            Command::new("mock-feed")
+                .about("Write a pcap of random but well formed B6034 quotes")
+                .arg(
+                    arg!(--"num-quotes" <N> "Number of quotes to generate")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("1000"),
+                )
+                .arg(
+                    arg!(--"num-issues" <M> "Number of distinct issue codes")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("10"),
+                )
+                .arg(arg!(--"output-path" <PATH> "Where to write the pcap").required(true))
+                .arg(
+                    arg!(--seed <SEED> "Seed for the generator, random if not given")
+                        .value_parser(clap::value_parser!(u64)),
+                ),
+        )
+        .arg(
+            arg!([PATH] "Path to the pcap file, or a glob for a rotated set like 'feed-*.pcap'")
+                .required_unless_present_any(["merge-pcap", "diff-files", "json-schema"]),
+        )
+        .arg(
+            arg!(--"json-schema" "Print the JSON Schema of the quotes --output-dir and --split-by-date write, and exit")
+                .action(ArgAction::SetTrue)
+                .exclusive(true),
+        )
+        .arg(
+            arg!(--"merge-pcap" <FILE> "Merge these pcap files by packet timestamp and parse them as one capture")
+                .num_args(2..)
+                .conflicts_with_all(["PATH", "only_one"]),
+        )
+        .arg(
+            arg!(--"diff-files" <FILE> "Parse two pcap files and show the quotes only one has, and the fields that differ between quotes with the same issue code and accept time")
+                .num_args(2)
+                .conflicts_with_all(["PATH", "merge-pcap", "only_one", "headers-only", "print-issue-list"]),
+        )
         .arg(
             arg!(-r --sorted "Sort Quotes by Quote Accept Time")
                 .default_value("false")
@@ -22,182 +125,1635 @@ fn main() {
                 .action(ArgAction::SetTrue),
         )
         .arg(arg!(-s --only_one "Use this to try parsing just one").action(ArgAction::SetTrue))
+        .arg(
+            arg!(--"price-scale" <DIGITS> "Number of implied decimal places in prices")
+                .default_value("0")
+                .value_parser(clap::value_parser!(u32).range(0..=18)),
+        )
+        .arg(
+            arg!(--"price-precision" <DIGITS> "Decimal places to show prices with when printing them. Defaults to --price-scale")
+                .value_parser(clap::value_parser!(u32).range(0..=18)),
+        )
+        .arg(
+            arg!(--"validate-volumes" "Warn when total volumes don't match the sum of the levels")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"validate-book" "Count quotes whose book is out of order, has gaps, or has more orders at its levels than in total")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"drop-bad-books" "Drop the quotes --validate-book finds problems with")
+                .action(ArgAction::SetTrue)
+                .requires("validate-book"),
+        )
+        .arg(
+            arg!(--"drop-bad-timestamps" "Drop quotes captured outside --timestamps-from and --timestamps-before, which corrupt captures turn up")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"timestamps-from" <DATE> "Earliest sane capture date for --drop-bad-timestamps, UTC")
+                .default_value("2000-01-01")
+                .value_parser(clap::value_parser!(NaiveDate)),
+        )
+        .arg(
+            arg!(--"timestamps-before" <DATE> "Capture date --drop-bad-timestamps treats as too late, UTC")
+                .default_value("2100-01-01")
+                .value_parser(clap::value_parser!(NaiveDate)),
+        )
+        .arg(
+            arg!(--"strict-fail" "Abort on the first price quote that fails to parse")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--output <FORMAT> "How to write the parsed quotes")
+                .default_value("text")
+                .value_parser([
+                    "text",
+                    "cbor",
+                    "bin",
+                    "xlsx",
+                    "zstd-csv",
+                    "npy",
+                    "duckdb",
+                    "websocket",
+                    "redis",
+                    "quickfix",
+                ]),
+        )
+        .arg(
+            arg!(--"fix-sender" <ID> "SenderCompID of the messages --output quickfix writes")
+                .default_value(fix::DEFAULT_SENDER),
+        )
+        .arg(
+            arg!(--"fix-target" <ID> "TargetCompID of the messages --output quickfix writes")
+                .default_value(fix::DEFAULT_TARGET),
+        )
+        .arg(
+            arg!(--"websocket-port" <PORT> "Port on localhost to serve --output websocket on. Quotes are sent to every connected client as json once the first one connects")
+                .value_parser(clap::value_parser!(u16))
+                .required_if_eq("output", "websocket"),
+        )
+        .arg(
+            arg!(--"output-path" <FILE> "File to write binary output formats to. For --output quickfix, the prefix of the QuickFIX/J file store's files")
+                .required_if_eq_any([
+                    ("output", "cbor"),
+                    ("output", "bin"),
+                    ("output", "xlsx"),
+                    ("output", "zstd-csv"),
+                    ("output", "duckdb"),
+                    ("output", "quickfix"),
+                ]),
+        )
+        .arg(
+            arg!(--"compression-level" <N> "Zstandard level for --output zstd-csv, 1 to 22")
+                .default_value("3")
+                .value_parser(clap::value_parser!(i32).range(1..=22)),
+        )
+        .arg(
+            arg!(--"redis-url" <URL> "Redis server to send --output redis to")
+                .default_value("redis://127.0.0.1:6379"),
+        )
+        .arg(
+            arg!(--"redis-stream" <KEY> "Stream --output redis adds quotes to, one entry per quote with a field for each quote field")
+                .required_if_eq("output", "redis"),
+        )
+        .arg(
+            arg!(--"redis-maxlen" <N> "Trim the stream to about N entries as quotes are added, with MAXLEN ~")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            arg!(--"print-rejected" "Print a line to stderr for every rejected packet")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"verify-checksums" "Reject quotes whose IPv4 or UDP checksum doesn't match, counting them as checksum failures")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"spread-colors" "Show the spread, green when it narrowed and red when it widened")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"color-by-spread" "Color each whole quote line by how wide its spread is instead of the usual colors, green when tight, yellow in between and red when wide")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"spread-thresholds" <TIGHT_WIDE> "Widest spreads --color-by-spread still calls tight and in between, in ticks of the smallest price step, like 2,10")
+                .value_parser(parse_spread_thresholds)
+                .requires("color-by-spread"),
+        )
+        .arg(
+            arg!(--"prefix-histogram" <LEN> "Count quotes per issue code prefix of this length")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            arg!(--heatmap "Print a row per issue shaded by how many quotes it had in each slice of the capture")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--bins <N> "Number of time slices for --heatmap")
+                .value_parser(clap::builder::RangedU64ValueParser::<usize>::new().range(1..))
+                .default_value("60")
+                .requires("heatmap"),
+        )
+        .arg(
+            arg!(--"sort-by" <KEY> "Sort quotes by this key, -r is the same as accept-time")
+                .value_parser(SortKey::NAMES),
+        )
+        .arg(
+            arg!(--canonical "Print the same bytes for the same input: sorted by issue, no color, fixed width numbers, ISO-8601 UTC times and no parse time")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["sorted", "sort-by"]),
+        )
+        .arg(
+            arg!(--"require-prefix-exact" [N] "Report the prefix seen on the first N right-port packets that aren't quotes")
+                .value_parser(clap::value_parser!(usize))
+                .default_missing_value("10"),
+        )
+        .arg(
+            arg!(--correlation <N> "Print a TSV correlation matrix of mid prices for the N busiest issues")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            arg!(--"assert-sorted" "Exit with an error unless quotes arrive in accept time order")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"clamp-times" "Report how many quotes arrive with an accept time earlier than one already seen")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"with-clamped-times" "Print each quote with its accept time clamped to never go backwards")
+                .action(ArgAction::SetTrue)
+                .requires("clamp-times")
+                .conflicts_with_all([
+                    "sorted",
+                    "sort-by",
+                    "output-path",
+                    "output-dir",
+                    "resample-to-ticks",
+                ]),
+        )
+        .arg(
+            arg!(--"resample-to-ticks" <INTERVAL_MS> "Replace the quotes with a snapshot of each issue's latest quote every INTERVAL_MS, timed on the grid")
+                .value_parser(clap::value_parser!(u64).range(1..)),
+        )
+        .arg(
+            arg!(--"forward-fill" "With --resample-to-ticks, repeat an issue's last quote on ticks it didn't quote in instead of skipping it")
+                .action(ArgAction::SetTrue)
+                .requires("resample-to-ticks"),
+        )
+        .arg(
+            arg!(--"aggregate-book" "Show each quote as its issue's book merged with the latest quote from the other ports, keeping the best 5 prices of both")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["merge-pcap", "jobs"]),
+        )
+        .arg(
+            arg!(--"compute-twap" "Print a TSV of each issue's time weighted average mid price")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"latency-report" "Add feed latency percentiles and a histogram of them to the stats")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"rate-buckets" <WIDTH> "Add quotes per slice of capture time to the stats, with slices this wide, like 500ms, 1s or 1m")
+                .value_parser(parse_bucket_width),
+        )
+        .arg(
+            arg!(--"burst-window" <WIDTH> "Warn about every burst of more than --burst-threshold quotes within this much capture time, like 1ms, and add the worst to the stats")
+                .value_parser(parse_bucket_width)
+                .requires("burst-threshold"),
+        )
+        .arg(
+            arg!(--"burst-threshold" <QUOTES> "Most quotes a --burst-window can hold before it's a burst")
+                .value_parser(clap::value_parser!(u64))
+                .requires("burst-window"),
+        )
+        .arg(
+            arg!(--"latency-max" <US> "Largest latency in microseconds --latency-report buckets, larger ones are only counted")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("60000000")
+                .requires("latency-report"),
+        )
+        .arg(
+            arg!(--"max-latency-us" <MICROSECONDS> "Warn about every quote that arrives more than this long after its accept time, and count them. Turns on the latency report")
+                .value_parser(parse_latency_threshold),
+        )
+        .arg(
+            arg!(--"session-date" <DATE> "Trading date for accept times, defaults to each quote's capture date")
+                .value_parser(clap::value_parser!(NaiveDate)),
+        )
+        .arg(
+            arg!(--timezone <TZ> "Exchange timezone accept times are in")
+                .default_value("Asia/Seoul")
+                .value_parser(clap::value_parser!(Tz)),
+        )
+        .arg(
+            arg!(--"filter-no-bid" "Skip quotes with no bid at the top of the book")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"filter-no-ask" "Skip quotes with no ask at the top of the book")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"exclude-market-status" <STATUS> "Skip quotes sent in this session state, by code or name. Repeatable")
+                .action(ArgAction::Append)
+                .value_parser(clap::value_parser!(MarketStatus)),
+        )
+        .arg(
+            arg!(--country <CODE> "Keep only quotes whose issue code is an ISIN from this two letter country, like KR. Repeatable")
+                .action(ArgAction::Append)
+                .value_parser(parse_country),
+        )
+        .arg(arg!(--"valid-issues" <FILE> "Keep only quotes whose issue code is listed in this file, one per line"))
+        .arg(
+            arg!(--ports <PORT> "UDP destination ports quotes arrive on, 15515 and 15516 if not given")
+                .num_args(1..)
+                .value_parser(clap::value_parser!(u16)),
+        )
+        .arg(
+            arg!(--"skip-bytes" <N> "Drop N bytes from the front of every frame, for encapsulations that aren't understood")
+                .default_value("0")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            arg!(--"binary-be" "Read volumes, prices and quantities as big endian binary integers instead of the ascii digits B6034 uses")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("binary-le"),
+        )
+        .arg(
+            arg!(--"binary-le" "Read volumes, prices and quantities as little endian binary integers instead of ascii digits")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"input-format" <FORMAT> "Read the input as this format instead of guessing from its first bytes")
+                .value_parser(InputFormat::NAMES),
+        )
+        .arg(
+            arg!(--"session-stats" "Print a per-issue summary of the whole session")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"per-issue-stats" "Add a table of each issue's quote count, capture times, spreads and volume changes to the stats")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--top <N> "Print the N issues with the most quotes and the top of book of their last one. With --per-issue-stats, only show those N in its table instead")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            arg!(--"first-last-seen" "Print each issue's earliest and latest accept time and how many updates it had")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--count "Print how many quotes were parsed instead of the quotes")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(NEEDS_ALL_QUOTES),
+        )
+        .arg(
+            arg!(--"stats-only" "Print only the parse stats")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(NEEDS_ALL_QUOTES)
+                .conflicts_with("count"),
+        )
+        .arg(
+            arg!(-q --quiet "Print the quotes without the parse stats")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("stats-only"),
+        )
+        .arg(
+            arg!(--"output-dir" <DIR> "Write each issue's quotes to <DIR>/<issue_code>.jsonl instead of printing them. With --output npy, where the .npy files go")
+                .required_if_eq("output", "npy")
+                .conflicts_with_all(["output-path", "count", "stats-only"]),
+        )
+        .arg(
+            arg!(--"split-by-date" <DIR> "Write quotes to <DIR>/<YYYY-MM-DD>.jsonl by the date they were captured on in --timezone, instead of printing them")
+                .conflicts_with_all(["output-dir", "output-path", "count", "stats-only"]),
+        )
+        .arg(
+            arg!(--"max-open-files" <N> "Most per-issue files --output-dir keeps open at once, the least recently written is closed to make room")
+                .default_value("64")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(arg!(--"filter-script" <PATH> "Only keep quotes a Lua script's filter(quote) function returns true for. Needs the lua feature"))
+        .arg(arg!(--"event-log" <PATH> "Write a JSONL log of every quote, rejection, decode failure and anomalous market"))
+        .arg(
+            arg!(--"estimate-memory" "Before parsing, print to stderr roughly how much memory holding every quote would take")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            arg!(--"headers-only" "Decode only each quote's header, skipping the book. Much faster when prices aren't needed")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(NEEDS_ALL_QUOTES)
+                .conflicts_with_all([
+                    "merge-pcap",
+                    "output-dir",
+                    "split-by-date",
+                    "event-log",
+                    "filter-script",
+                    "per-issue-stats",
+                    "top",
+                    "validate-volumes",
+                    "validate-book",
+                    "spread-colors",
+                    "color-by-spread",
+                    "filter-no-bid",
+                    "filter-no-ask",
+                ]),
+        )
+        .arg(
+            arg!(--"print-issue-list" "Print every issue code in the capture, sorted, with how many quotes it had")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(NEEDS_ALL_QUOTES)
+                .conflicts_with_all([
+                    "merge-pcap",
+                    "output-dir",
+                    "split-by-date",
+                    "filter-script",
+                    "per-issue-stats",
+                    "top",
+                    "validate-volumes",
+                    "validate-book",
+                    "spread-colors",
+                    "color-by-spread",
+                    "filter-no-bid",
+                    "filter-no-ask",
+                    "count",
+                    "stats-only",
+                    "headers-only",
+                    "jobs",
+                ]),
+        )
+        .arg(
+            arg!(--"issue-list-min-count" <N> "Leave issues with fewer than N quotes out of --print-issue-list")
+                .default_value("1")
+                .value_parser(clap::value_parser!(u64))
+                .requires("print-issue-list"),
+        )
+        .arg(
+            arg!(--"aggregate-to-trades" "Print the trades the quotes suggest instead of the quotes: every time an issue's best bid or ask shrinks or is taken out. These are estimates, a cancel looks the same as a fill")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(NEEDS_ALL_QUOTES)
+                .conflicts_with_all([
+                    "output",
+                    "output-dir",
+                    "split-by-date",
+                    "with-hex",
+                    "headers-only",
+                    "print-issue-list",
+                ]),
+        )
+        .arg(
+            arg!(--bucket <MS> "Print the quotes of each issue grouped into windows of accept time this many milliseconds wide instead of the quotes: each window's update count and its first, last, lowest and highest mid price")
+                .value_parser(clap::builder::RangedU64ValueParser::<u64>::new().range(1..))
+                .conflicts_with_all(NEEDS_ALL_QUOTES)
+                .conflicts_with_all([
+                    "output",
+                    "output-dir",
+                    "split-by-date",
+                    "with-hex",
+                    "headers-only",
+                    "print-issue-list",
+                    "aggregate-to-trades",
+                ]),
+        )
+        .arg(
+            arg!(--"with-hex" "Append the payload each quote was decoded from, as hex, for pasting into bug reports")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(NEEDS_ALL_QUOTES)
+                .conflicts_with_all(["merge-pcap", "output-dir", "split-by-date", "headers-only"]),
+        )
+        .arg(
+            arg!(-j --jobs <N> "Parse up to N of the input files at once, each on its own thread. Output is the same as reading them in order")
+                .value_parser(clap::builder::RangedU64ValueParser::<usize>::new().range(1..))
+                .conflicts_with_all(["merge-pcap", "event-log", "headers-only", "with-hex"]),
+        )
+        .arg(
+            arg!(--"stats-interval" <INTERVAL> "Print a line of progress to stderr this often while parsing, a duration like 5s or a number of millions of packets like 2M")
+                .value_parser(parse_stats_interval)
+                .conflicts_with("jobs"),
+        )
+        .arg(
+            arg!(--"max-failed" <N> "Exit with status 3 if more than N quotes failed to decode")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            arg!(--"max-failed-pct" <PERCENT> "Exit with status 3 if more than this percent of the quotes that reached the decoder failed")
+                .value_parser(clap::value_parser!(f64)),
+        )
+        .arg(
+            arg!(--"min-quotes" <N> "Exit with status 3 if fewer than N quotes decoded")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            arg!(--"top-by-volume" <N> "Print the N issues with the most total quoted volume")
+                .value_parser(clap::value_parser!(usize)),
+        )
         .get_matches();
 
-    let path = matches.get_one::<String>("PATH").expect("no path provided");
+    if let Some(("diff", diff_matches)) = matches.subcommand() {
+        let a = diff_matches.get_one::<String>("A").unwrap();
+        let b = diff_matches.get_one::<String>("B").unwrap();
+        run_diff(a, b);
+        return;
+    }
+    if let Some(("mock-feed", mock_matches)) = matches.subcommand() {
+        run_mock_feed(mock_matches);
+        return;
+    }
+    if *matches.get_one::<bool>("json-schema").unwrap() {
+        let schema = schema::price_quote_json_schema();
+        println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+        return;
+    }
+
+    let paths = input_paths(&matches);
+    let price_format = price_format(&matches);
 
     if *matches.get_one::<bool>("only_one").unwrap() {
-        // load the one file, instantly parse as a price quote, and print it
-        // this isnt a pcap file, just a single price quote in hex
-        let dur = std::time::Duration::new(0, 0);
-        let price_quote = PriceQuote::from_bytes(dur, &std::fs::read(path).unwrap()).unwrap();
-        println!("{}", price_quote);
+        // load the one file, instantly parse as a price quote, and print it.
+        // this isnt a pcap file, just a single price quote, either the raw
+        // payload or the payload written out in hex
+        let path = &paths[0];
+        let bytes = std::fs::read(path).unwrap_or_else(|err| {
+            eprintln!("error: couldn't read {}: {}", path, err);
+            std::process::exit(1);
+        });
+        let price_quote = if bytes.starts_with(B6034Parser::PREFIX) && bytes.len() < B6034_LEN {
+            Err(format!(
+                "quote payload is {} bytes, expected {}",
+                bytes.len(),
+                B6034_LEN
+            ))
+        } else if bytes.starts_with(B6034Parser::PREFIX) {
+            PriceQuote::from_bytes_with(
+                Duration::ZERO,
+                &bytes,
+                ISSUE_CODE_LEN,
+                numeric_encoding(&matches),
+            )
+            .map_err(|err| err.to_string())
+        } else {
+            String::from_utf8_lossy(&bytes)
+                .parse::<PriceQuote>()
+                .map_err(|err| err.to_string())
+        };
+        match price_quote {
+            Ok(price_quote) => println!("{}", price_quote.fmt_with(stdout_style(), price_format)),
+            Err(err) => {
+                eprintln!("error: {}: {}", path, err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let parse_options = ParseOptions {
+        strict_fail: *matches.get_one::<bool>("strict-fail").unwrap(),
+        print_rejected: *matches.get_one::<bool>("print-rejected").unwrap(),
+        verify_checksums: *matches.get_one::<bool>("verify-checksums").unwrap(),
+        prefix_report_limit: matches
+            .get_one::<usize>("require-prefix-exact")
+            .copied()
+            .unwrap_or(0),
+        input_format: matches
+            .get_one::<String>("input-format")
+            .and_then(|name| InputFormat::from_name(name)),
+        skip_bytes: *matches.get_one::<usize>("skip-bytes").unwrap(),
+        numbers: numeric_encoding(&matches),
+        ports: matches.get_many::<u16>("ports").map_or_else(
+            || B6034Parser::PORTS.to_vec(),
+            |ports| ports.copied().collect(),
+        ),
+        event_log: matches.get_one::<String>("event-log").cloned(),
+        filter_no_bid: *matches.get_one::<bool>("filter-no-bid").unwrap(),
+        filter_no_ask: *matches.get_one::<bool>("filter-no-ask").unwrap(),
+        validate_book: *matches.get_one::<bool>("validate-book").unwrap(),
+        drop_bad_books: *matches.get_one::<bool>("drop-bad-books").unwrap(),
+        rcv_time_range: matches
+            .get_one::<bool>("drop-bad-timestamps")
+            .unwrap()
+            .then(|| rcv_time_range(&matches)),
+        exclude_market_statuses: matches
+            .get_many::<MarketStatus>("exclude-market-status")
+            .unwrap_or_default()
+            .copied()
+            .collect(),
+        countries: matches
+            .get_many::<String>("country")
+            .unwrap_or_default()
+            .cloned()
+            .collect(),
+        stats_interval: matches.get_one::<StatsInterval>("stats-interval").copied(),
+        input_len: input_len(&paths),
+        valid_issues: matches.get_one::<String>("valid-issues").map(|path| {
+            let issues = File::open(path).and_then(|file| read_issue_list(BufReader::new(file)));
+            issues.unwrap_or_else(|err| {
+                eprintln!("error: couldn't read {}: {}", path, err);
+                std::process::exit(1);
+            })
+        }),
+        ..Default::default()
+    };
+    if *matches.get_one::<bool>("estimate-memory").unwrap() {
+        match estimate_memory(open_inputs(&paths), &parse_options) {
+            Ok(estimate) => eprintln!("estimate: {}", estimate),
+            Err(err) => {
+                eprintln!("error: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(diff_paths) = matches.get_many::<String>("diff-files") {
+        let diff_paths: Vec<&String> = diff_paths.collect();
+        run_diff_files(diff_paths[0], diff_paths[1], &parse_options, price_format);
+        return;
+    }
+    if *matches.get_one::<bool>("headers-only").unwrap() {
+        run_headers_only(&paths, &parse_options, &matches);
+        return;
+    }
+    if *matches.get_one::<bool>("print-issue-list").unwrap() {
+        run_issue_list(&paths, &parse_options, &matches);
+        return;
+    }
+
+    // npy arrays are written a column at a time
+    if !NEEDS_ALL_QUOTES
+        .iter()
+        .any(|id| matches.value_source(id) == Some(ValueSource::CommandLine))
+        && matches.get_one::<String>("output").unwrap() != "npy"
+    {
+        run_streaming(&paths, &parse_options, &matches);
         return;
     }
 
-    let (mut price_quotes, parse_stats) = parse_price_quotes_from_file(path);
+    let result = if matches.contains_id("merge-pcap") {
+        merge_price_quotes_from_readers(open_inputs(&paths), &parse_options)
+    } else if *matches.get_one::<bool>("aggregate-book").unwrap() {
+        aggregate::aggregate_books_from_readers(open_inputs(&paths), &parse_options)
+    } else if let Some(&jobs) = matches.get_one::<usize>("jobs") {
+        concat_price_quotes_from_readers_parallel(open_inputs(&paths), &parse_options, jobs)
+    } else {
+        concat_price_quotes_from_readers(open_inputs(&paths), &parse_options)
+    };
+    let (mut price_quotes, mut parse_stats) = match result {
+        Ok(result) => result,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+    };
 
-    if *matches.get_one::<bool>("sorted").unwrap() {
-        price_quotes.sort_by(|a, b| a.quote_accept_time.cmp(&b.quote_accept_time));
+    if let Some(filter) = quote_filter(&matches) {
+        let before = price_quotes.len();
+        price_quotes.retain(|q| filter(q));
+        parse_stats.filtered_by_script = (before - price_quotes.len()) as u64;
     }
 
-    for price_quote in price_quotes {
-        println!("{}", price_quote);
+    if *matches.get_one::<bool>("validate-volumes").unwrap() {
+        parse_stats.volume_inconsistencies = validate_volumes(&price_quotes);
     }
 
-    // print the parse stats
-    println!("\n{}", parse_stats);
-}
-
-///////////////////////// PARSING /////////////////////////
-pub struct PacketParseStats {
-    pub parse_time: std::time::Duration,
-    pub packet_count: u64,
-
-    pub successfully_parsed: u64,
-    pub rejected: u64,
-    pub failed: u64,
-
-    pub non_udp: u64,
-    pub wrong_port: u64,
-    pub not_a_price_quote: u64,
-}
-
-impl PacketParseStats {
-    pub fn new() -> Self {
-        PacketParseStats {
-            parse_time: std::time::Duration::new(0, 0),
-            packet_count: 0,
-
-            successfully_parsed: 0,
-            rejected: 0,
-            failed: 0,
-
-            non_udp: 0,
-            wrong_port: 0,
-            not_a_price_quote: 0,
-        }
-    }
-}
-
-impl Default for PacketParseStats {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl std::fmt::Display for PacketParseStats {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let duration = self.parse_time.as_millis() as f64;
-        let total = self.packet_count as f64;
-        let successfully_parsed = self.successfully_parsed as f64;
-        let rejected = self.rejected as f64;
-        let failed = self.failed as f64;
-
-        let non_udp = self.non_udp as f64;
-        let wrong_port = self.wrong_port as f64;
-        let not_a_price_quote = self.not_a_price_quote as f64;
-
-        writeln!(f, "Packet Parse Stats:")?;
-        writeln!(f, "  Parse Time: {:.2}ms", duration)?;
-        writeln!(f, "  Total Packets: {}", self.packet_count)?;
-        writeln!(
-            f,
-            "  Successfully Parsed: {} ({:.2}%)",
-            successfully_parsed,
-            successfully_parsed / total * 100.0
-        )?;
-        writeln!(
-            f,
-            "  Rejected: {} ({:.2}%)",
-            rejected,
-            rejected / total * 100.0
-        )?;
-        writeln!(f, "  Failed: {} ({:.2}%)", failed, failed / total * 100.0)?;
-        writeln!(
-            f,
-            "  Non UDP: {} ({:.2}%)",
-            non_udp,
-            non_udp / total * 100.0
-        )?;
-        writeln!(
-            f,
-            "  Wrong Port: {} ({:.2}%)",
-            wrong_port,
-            wrong_port / total * 100.0
-        )?;
-        writeln!(
-            f,
-            "  Not a Price Quote: {} ({:.2}%)",
-            not_a_price_quote,
-            not_a_price_quote / total * 100.0
-        )?;
-        Ok(())
-    }
-}
-
-pub fn parse_price_quotes_from_file(path: &str) -> (Vec<PriceQuote>, PacketParseStats) {
-    let file = File::open(path).expect("couldn't read file");
-    let mut reader = PcapReader::new(file).expect("failed to read pcap file");
-
-    let start = std::time::Instant::now();
-    let mut parse_stats = PacketParseStats::new();
-    let mut price_quotes: Vec<PriceQuote> = vec![];
-    while let Some(pcap_packet) = reader.next_packet() {
-        parse_stats.packet_count += 1;
-
-        // try to parse packet
-        let pcap_packet = pcap_packet.expect("failed to get packet");
-        let packet = pcap_packet.data;
-        let parsed_packet = match SlicedPacket::from_ethernet(&packet) {
-            Ok(packet) => packet,
-            Err(err) => {
-                eprintln!("Failed to parse packet: {:?}", err);
-                continue;
+    if *matches.get_one::<bool>("assert-sorted").unwrap() {
+        if let Some(i) = find_accept_time_violation(&price_quotes) {
+            let fmt_time = |q: &PriceQuote| fmt_accept_time(q.accept_time());
+            eprintln!(
+                "error: quotes not sorted by accept time: quote {} ({} {}) comes before quote {} ({} {})",
+                i,
+                price_quotes[i].issue_code,
+                fmt_time(&price_quotes[i]),
+                i + 1,
+                price_quotes[i + 1].issue_code,
+                fmt_time(&price_quotes[i + 1])
+            );
+            std::process::exit(1);
+        }
+    }
+
+    // clamping is about arrival order, so it has to happen before any sort
+    let clamped_times = matches
+        .get_one::<bool>("clamp-times")
+        .unwrap()
+        .then(|| clamp_accept_times(&price_quotes));
+
+    if let Some(&interval_ms) = matches.get_one::<u64>("resample-to-ticks") {
+        price_quotes = resample::resample(
+            &price_quotes,
+            Duration::from_millis(interval_ms),
+            *matches.get_one::<bool>("forward-fill").unwrap(),
+        );
+    }
+
+    let canonical = *matches.get_one::<bool>("canonical").unwrap();
+    let sort_key = match matches.get_one::<String>("sort-by") {
+        Some(name) => SortKey::from_name(name),
+        None if *matches.get_one::<bool>("sorted").unwrap() => Some(SortKey::AcceptTime),
+        None if canonical => Some(SortKey::Issue),
+        None => None,
+    };
+    if let Some(sort_key) = sort_key {
+        price_quotes.sort_by(sort_key.key_fn());
+    }
+
+    match matches.get_one::<String>("output").unwrap().as_str() {
+        "npy" => {
+            let dir = matches.get_one::<String>("output-dir").unwrap();
+            match npy_writer::write_quotes_to_npy(&price_quotes, Path::new(dir), price_format.scale)
+            {
+                Ok(written) => eprintln!("wrote {} npy files", written.len()),
+                Err(err) => {
+                    eprintln!("error: couldn't write {}: {}", dir, err);
+                    std::process::exit(1);
+                }
             }
-        };
+        }
+        _ if matches.contains_id("output-dir") => {
+            let mut splitter = issue_splitter(&matches);
+            for price_quote in &price_quotes {
+                write_split(&mut splitter, price_quote);
+            }
+            finish_split(&mut splitter);
+        }
+        _ if matches.contains_id("split-by-date") => {
+            let mut splitter = date_splitter(&matches);
+            for price_quote in &price_quotes {
+                write_date_split(&mut splitter, price_quote);
+            }
+            finish_date_split(&mut splitter);
+        }
+        "cbor" => {
+            let output_path = matches.get_one::<String>("output-path").unwrap();
+            let result = File::create(output_path).and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                for price_quote in &price_quotes {
+                    codec::write_cbor_record(&mut writer, price_quote)
+                        .map_err(codec::into_io_error)?;
+                }
+                writer.flush()
+            });
+            if let Err(err) = result {
+                eprintln!("error: couldn't write {}: {}", output_path, err);
+                std::process::exit(1);
+            }
+        }
+        "bin" => {
+            let output_path = matches.get_one::<String>("output-path").unwrap();
+            let result = File::create(output_path).and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                for price_quote in &price_quotes {
+                    record::write_record(&mut writer, price_quote)?;
+                }
+                writer.flush()
+            });
+            if let Err(err) = result {
+                eprintln!("error: couldn't write {}: {}", output_path, err);
+                std::process::exit(1);
+            }
+        }
+        "zstd-csv" => {
+            let output_path = matches.get_one::<String>("output-path").unwrap();
+            let level = *matches.get_one::<i32>("compression-level").unwrap();
+            let result = ZstdCsvWriter::create(output_path, level, price_format.scale).and_then(
+                |mut writer| {
+                    for price_quote in &price_quotes {
+                        writer.write(price_quote)?;
+                    }
+                    writer.finish()
+                },
+            );
+            if let Err(err) = result {
+                eprintln!("error: couldn't write {}: {}", output_path, err);
+                std::process::exit(1);
+            }
+        }
+        "xlsx" => {
+            let output_path = matches.get_one::<String>("output-path").unwrap();
+            if let Err(err) =
+                xlsx_writer::write_quotes_to_xlsx(&price_quotes, output_path, price_format.scale)
+            {
+                eprintln!("error: couldn't write {}: {}", output_path, err);
+                std::process::exit(1);
+            }
+        }
+        "duckdb" => write_duckdb(
+            &price_quotes,
+            matches.get_one::<String>("output-path").unwrap(),
+            price_format.scale,
+        ),
+        "quickfix" => {
+            let output_path = matches.get_one::<String>("output-path").unwrap();
+            let comp_ids = fix::CompIds::new(
+                matches.get_one::<String>("fix-sender").unwrap(),
+                matches.get_one::<String>("fix-target").unwrap(),
+            );
+            let result = fix::QuickFixStore::create(output_path, comp_ids, price_format.scale)
+                .and_then(|mut store| {
+                    for price_quote in &price_quotes {
+                        store.write(price_quote)?;
+                    }
+                    store.finish()
+                });
+            if let Err(err) = result {
+                eprintln!("error: couldn't write {}: {}", output_path, err);
+                std::process::exit(1);
+            }
+        }
+        "websocket" => {
+            if let Some(server) = websocket_server(&matches) {
+                for price_quote in &price_quotes {
+                    server.send(price_quote);
+                }
+                server.close();
+            }
+        }
+        "redis" => {
+            if let Some(mut sink) = redis_sink(&matches) {
+                for price_quote in &price_quotes {
+                    send_to_redis(&mut sink, price_quote);
+                }
+            }
+        }
+        _ => {
+            let style = if canonical {
+                DisplayStyle::Canonical
+            } else {
+                stdout_style()
+            };
+            let mut printer = quote_printer(&matches, style);
+            let with_clamped = *matches.get_one::<bool>("with-clamped-times").unwrap();
+            for (i, price_quote) in price_quotes.iter().enumerate() {
+                match &clamped_times {
+                    Some(clamped) if with_clamped => println!(
+                        "{} clamped_accept_time={}",
+                        printer.format(price_quote),
+                        fmt_accept_time(clamped.times[i])
+                    ),
+                    _ => println!("{}", printer.format(price_quote)),
+                }
+            }
+        }
+    }
+
+    if let Some(clamped) = &clamped_times {
+        println!(
+            "\nClamped Accept Times: {} of {} quotes, furthest {:.3}s",
+            clamped.clamped,
+            price_quotes.len(),
+            clamped.max_lag.num_milliseconds() as f64 / 1000.0
+        );
+    }
 
-        // skip if not udp
-        let udp = if let Some(TransportSlice::Udp(udp)) = parsed_packet.transport {
-            udp
+    if let Some(prefix_len) = matches.get_one::<usize>("prefix-histogram") {
+        let histogram = report::issue_prefix_histogram(&price_quotes, *prefix_len);
+        print!("\n{}", report::format_issue_prefix_histogram(&histogram));
+    }
+
+    if *matches.get_one::<bool>("heatmap").unwrap() {
+        let heatmap =
+            report::build_heatmap(&price_quotes, *matches.get_one::<usize>("bins").unwrap());
+        print!("\n{}", report::format_heatmap(&heatmap));
+    }
+
+    if let Some(n) = matches.get_one::<usize>("top-by-volume") {
+        let ranked = report::top_by_volume(&price_quotes, *n, price_format.scale);
+        print!("\n{}", report::format_top_by_volume(&ranked));
+    }
+
+    if *matches.get_one::<bool>("session-stats").unwrap() {
+        print!(
+            "\n{}",
+            SessionStats::from_quotes(&price_quotes, price_format.scale)
+        );
+    }
+
+    if *matches.get_one::<bool>("first-last-seen").unwrap() {
+        print!(
+            "\n{}",
+            SessionStats::from_quotes(&price_quotes, price_format.scale).format_first_last_seen()
+        );
+    }
+
+    if let Some(n) = matches.get_one::<usize>("correlation") {
+        let (issues, matrix) = analytics::correlation_matrix(&price_quotes, *n);
+        print!("\n{}", analytics::format_correlation_tsv(&issues, &matrix));
+    }
+
+    if *matches.get_one::<bool>("compute-twap").unwrap() {
+        let twap = analytics::compute_twap(&price_quotes, price_format.scale);
+        print!("\n{}", analytics::format_twap_tsv(&twap));
+    }
+
+    if let Some(mut latencies) = latency_histogram(&matches) {
+        let clock = latency_clock(&matches);
+        let max_latency_us = matches.get_one::<i64>("max-latency-us").copied();
+        for price_quote in &price_quotes {
+            if let Some(latency_us) = quote_latency_us(price_quote, clock) {
+                latencies.record(latency_us);
+                if latency_spike(price_quote, latency_us, max_latency_us) {
+                    parse_stats.latency_spikes += 1;
+                }
+            }
+        }
+        parse_stats.latency_report = latencies.report();
+    }
+
+    if let Some(mut rates) = rate_counter(&matches) {
+        for price_quote in &price_quotes {
+            rates.record(price_quote.packet_rcv_time);
+        }
+        parse_stats.rate_series = rates.series();
+    }
+
+    if let Some(mut bursts) = burst_detector(&matches) {
+        let mut by_time: Vec<&PriceQuote> = price_quotes.iter().collect();
+        by_time.sort_by_key(|q| q.packet_rcv_time);
+        for price_quote in by_time {
+            report_burst(bursts.record(price_quote.packet_rcv_time, &price_quote.issue_code));
+        }
+        report_burst(bursts.finish());
+        parse_stats.worst_burst = bursts.worst().cloned();
+    }
+
+    let top = matches.get_one::<usize>("top").copied();
+    if *matches.get_one::<bool>("per-issue-stats").unwrap() {
+        let collector = IssueStatsCollector::from_quotes(&price_quotes);
+        parse_stats.per_issue = Some(collector.finish(top));
+        parse_stats.price_format = price_format;
+    } else if let Some(n) = top {
+        let collector = IssueStatsCollector::from_quotes(&price_quotes);
+        print!(
+            "\n{}",
+            format_top_issues(&collector.finish(Some(n)), price_format)
+        );
+    }
+
+    parse_stats.violations = thresholds(&matches).check(&parse_stats);
+
+    // print the parse stats
+    if !*matches.get_one::<bool>("quiet").unwrap() {
+        if canonical {
+            println!("\n{:#}", parse_stats);
         } else {
-            parse_stats.non_udp += 1;
-            parse_stats.rejected += 1;
-            continue;
-        };
+            println!("\n{}", parse_stats);
+        }
+    }
+    exit_on_violations(&parse_stats);
+}
+
+fn fmt_accept_time(time: Option<NaiveTime>) -> String {
+    match time {
+        Some(t) => t.format("%H:%M:%S%.3f").to_string(),
+        None => "invalid".to_string(),
+    }
+}
 
-        // skip if wrong port
-        let destination_port = udp.destination_port();
-        if destination_port != 15515 && destination_port != 15516 {
-            parse_stats.wrong_port += 1;
-            parse_stats.rejected += 1;
-            continue;
+// --output duckdb
+#[cfg(feature = "duckdb")]
+fn write_duckdb(price_quotes: &[PriceQuote], output_path: &str, scale: u32) {
+    if let Err(err) = tsc_solution::db::write_quotes_to_duckdb(price_quotes, output_path, scale) {
+        eprintln!("error: couldn't write {}: {}", output_path, err);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(not(feature = "duckdb"))]
+fn write_duckdb(_: &[PriceQuote], _: &str, _: u32) {
+    eprintln!("error: --output duckdb needs a build with the duckdb feature");
+    std::process::exit(2);
+}
+
+// --output websocket, serving once a client has connected. None for every
+// other output
+#[cfg(feature = "websocket")]
+fn websocket_server(matches: &ArgMatches) -> Option<WsServer> {
+    if matches.get_one::<String>("output").unwrap() != "websocket" {
+        return None;
+    }
+    let port = *matches.get_one::<u16>("websocket-port").unwrap();
+    let server = WsServer::bind(port).unwrap_or_else(|err| {
+        eprintln!("error: couldn't listen on port {}: {}", port, err);
+        std::process::exit(1);
+    });
+    eprintln!("waiting for a client on ws://{}", server.local_addr());
+    server.wait_for_client();
+    Some(server)
+}
+
+#[cfg(not(feature = "websocket"))]
+fn websocket_server(matches: &ArgMatches) -> Option<WsServer> {
+    if matches.get_one::<String>("output").unwrap() != "websocket" {
+        return None;
+    }
+    eprintln!("error: --output websocket needs a build with the websocket feature");
+    std::process::exit(2);
+}
+
+// there's never one of these without the websocket feature,
+// websocket_server exits instead
+#[cfg(not(feature = "websocket"))]
+enum WsServer {}
+
+#[cfg(not(feature = "websocket"))]
+impl WsServer {
+    fn send(&self, _: &PriceQuote) {
+        match *self {}
+    }
+
+    fn close(self) {
+        match self {}
+    }
+}
+
+// --filter-script as a predicate on quotes. None without a script, so
+// quotes never go near lua unless asked to
+type QuoteFilter = Box<dyn Fn(&PriceQuote) -> bool>;
+
+#[cfg(feature = "lua")]
+fn quote_filter(matches: &ArgMatches) -> Option<QuoteFilter> {
+    let path = matches.get_one::<String>("filter-script")?.clone();
+    let script = ScriptFilter::load(&path, price_format(matches).scale).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
+    Some(Box::new(move |price_quote| {
+        script.keep(price_quote).unwrap_or_else(|err| {
+            eprintln!("error: {}: {}", path, err);
+            std::process::exit(1);
+        })
+    }))
+}
+
+#[cfg(not(feature = "lua"))]
+fn quote_filter(matches: &ArgMatches) -> Option<QuoteFilter> {
+    if matches.contains_id("filter-script") {
+        eprintln!("error: --filter-script needs a build with the lua feature");
+        std::process::exit(2);
+    }
+    None
+}
+
+// accept times are in hundredths of a second, so anything finer can't be
+// told apart from rounding
+fn parse_latency_threshold(text: &str) -> Result<i64, String> {
+    match text.parse::<i64>() {
+        Ok(us) if us >= 10_000 => Ok(us),
+        Ok(_) => Err(
+            "accept times are only to the hundredth of a second, so it has to be at least 10000"
+                .to_string(),
+        ),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn thresholds(matches: &ArgMatches) -> Thresholds {
+    Thresholds {
+        max_failed: matches.get_one::<u64>("max-failed").copied(),
+        max_failed_pct: matches.get_one::<f64>("max-failed-pct").copied(),
+        min_quotes: matches.get_one::<u64>("min-quotes").copied(),
+    }
+}
+
+// a capture outside --max-failed, --max-failed-pct or --min-quotes still
+// gets its output and stats, then fails the run so a script can tell
+fn exit_on_violations(parse_stats: &PacketParseStats) {
+    if parse_stats.violations.is_empty() {
+        return;
+    }
+    let reasons: Vec<String> = parse_stats
+        .violations
+        .iter()
+        .map(|violation| violation.to_string())
+        .collect();
+    eprintln!("error: {}", reasons.join("; "));
+    std::process::exit(THRESHOLD_EXIT_CODE);
+}
+
+fn parse_spread_thresholds(text: &str) -> Result<SpreadThresholds, String> {
+    SpreadThresholds::parse(text)
+        .ok_or_else(|| "expected two tick counts like 2,10, the first no bigger".to_string())
+}
+
+fn parse_stats_interval(text: &str) -> Result<StatsInterval, String> {
+    StatsInterval::parse(text)
+        .ok_or_else(|| "expected a duration like 5s or a number of packets like 2M".to_string())
+}
+
+fn parse_bucket_width(text: &str) -> Result<Duration, String> {
+    match analytics::parse_duration(text) {
+        Some(width) if !width.is_zero() => Ok(width),
+        _ => Err("expected a duration like 500ms, 1s or 1m".to_string()),
+    }
+}
+
+// --country takes either case, issue codes are upper
+fn parse_country(code: &str) -> Result<String, String> {
+    if code.len() == 2 && code.bytes().all(|b| b.is_ascii_alphabetic()) {
+        Ok(code.to_ascii_uppercase())
+    } else {
+        Err("expected a two letter country code".to_string())
+    }
+}
+
+// --timestamps-from up to --timestamps-before, as times since the epoch
+fn rcv_time_range(matches: &ArgMatches) -> std::ops::Range<Duration> {
+    let since_epoch = |id: &str| {
+        let date = *matches.get_one::<NaiveDate>(id).unwrap();
+        let secs = date.and_time(NaiveTime::MIN).and_utc().timestamp();
+        u64::try_from(secs)
+            .map(Duration::from_secs)
+            .unwrap_or_else(|_| {
+                eprintln!("error: --{} can't be before 1970", id);
+                std::process::exit(2);
+            })
+    };
+    since_epoch("timestamps-from")..since_epoch("timestamps-before")
+}
+
+fn numeric_encoding(matches: &ArgMatches) -> NumericEncoding {
+    if *matches.get_one::<bool>("binary-be").unwrap() {
+        NumericEncoding::BigEndian
+    } else if *matches.get_one::<bool>("binary-le").unwrap() {
+        NumericEncoding::LittleEndian
+    } else {
+        NumericEncoding::Ascii
+    }
+}
+
+fn price_format(matches: &ArgMatches) -> PriceFormat {
+    PriceFormat::new(
+        *matches.get_one::<u32>("price-scale").unwrap(),
+        matches.get_one::<u32>("price-precision").copied(),
+    )
+}
+
+fn latency_histogram(matches: &ArgMatches) -> Option<analytics::LatencyHistogram> {
+    (*matches.get_one::<bool>("latency-report").unwrap() || matches.contains_id("max-latency-us"))
+        .then(|| analytics::LatencyHistogram::new(*matches.get_one::<u64>("latency-max").unwrap()))
+}
+
+// warns about a quote that took longer than --max-latency-us to arrive
+fn latency_spike(price_quote: &PriceQuote, latency_us: i64, max_latency_us: Option<i64>) -> bool {
+    match max_latency_us {
+        Some(max_latency_us) if latency_us > max_latency_us => {
+            eprintln!(
+                "warning: {} seq {} arrived {}us after it was accepted, over {}us",
+                price_quote.issue_code, price_quote.issue_seq_no, latency_us, max_latency_us
+            );
+            true
         }
+        _ => false,
+    }
+}
+
+fn burst_detector(matches: &ArgMatches) -> Option<analytics::BurstDetector> {
+    let window = matches.get_one::<Duration>("burst-window")?;
+    let threshold = matches.get_one::<u64>("burst-threshold")?;
+    Some(analytics::BurstDetector::new(*window, *threshold))
+}
 
-        // skip if its not a price quote
-        let payload = parsed_packet.payload;
-        const QUOTE_PACKET_PREFIX: &[u8; 5] = b"B6034";
-        if !payload.starts_with(QUOTE_PACKET_PREFIX) {
-            parse_stats.not_a_price_quote += 1;
-            parse_stats.rejected += 1;
-            continue;
+fn report_burst(burst: Option<analytics::Burst>) {
+    if let Some(burst) = burst {
+        eprintln!("burst: {}", burst);
+    }
+}
+
+fn rate_counter(matches: &ArgMatches) -> Option<analytics::RateCounter> {
+    matches
+        .get_one::<Duration>("rate-buckets")
+        .map(|width| analytics::RateCounter::new(*width))
+}
+
+// the session date, if one was given, and timezone accept times are read in
+fn latency_clock(matches: &ArgMatches) -> (Option<NaiveDate>, Tz) {
+    (
+        matches.get_one::<NaiveDate>("session-date").copied(),
+        *matches.get_one::<Tz>("timezone").unwrap(),
+    )
+}
+
+fn quote_latency_us(
+    price_quote: &PriceQuote,
+    (session_date, tz): (Option<NaiveDate>, Tz),
+) -> Option<i64> {
+    let date = session_date.or_else(|| price_quote.capture_date(tz))?;
+    price_quote.latency(date, tz)?.num_microseconds()
+}
+
+// every file given to --merge-pcap or --diff-files, or PATH with any glob
+// expanded
+fn input_paths(matches: &ArgMatches) -> Vec<String> {
+    if let Some(paths) = matches
+        .get_many::<String>("merge-pcap")
+        .or_else(|| matches.get_many::<String>("diff-files"))
+    {
+        return paths.cloned().collect();
+    }
+    let path = matches.get_one::<String>("PATH").unwrap();
+    expand_capture_paths(path).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    })
+}
+
+// every input's size added up, None if any can't be read
+fn input_len(paths: &[String]) -> Option<u64> {
+    paths
+        .iter()
+        .map(|path| std::fs::metadata(path).ok().map(|metadata| metadata.len()))
+        .sum()
+}
+
+fn open_inputs(paths: &[String]) -> Vec<File> {
+    paths
+        .iter()
+        .map(|path| {
+            File::open(path).unwrap_or_else(|err| {
+                eprintln!("error: couldn't open {}: {}", path, err);
+                std::process::exit(1);
+            })
+        })
+        .collect()
+}
+
+fn run_streaming(paths: &[String], parse_options: &ParseOptions, matches: &ArgMatches) {
+    let count = *matches.get_one::<bool>("count").unwrap();
+    let stats_only = *matches.get_one::<bool>("stats-only").unwrap();
+    let validate = *matches.get_one::<bool>("validate-volumes").unwrap();
+
+    let files = open_inputs(paths);
+    let price_format = price_format(matches);
+    let mut printer = quote_printer(matches, stdout_style());
+    let mut splitter = matches
+        .contains_id("output-dir")
+        .then(|| issue_splitter(matches));
+    let mut date_splitter = matches
+        .contains_id("split-by-date")
+        .then(|| date_splitter(matches));
+    let ws_server = websocket_server(matches);
+    let mut trade_inferrer = matches
+        .get_one::<bool>("aggregate-to-trades")
+        .unwrap()
+        .then(TradeInferrer::new);
+    if trade_inferrer.is_some() && !count && !stats_only {
+        eprintln!("note: trades are inferred from changes to the top of book and are estimates, not executions");
+    }
+    let mut bucketer = matches
+        .get_one::<u64>("bucket")
+        .map(|&width_ms| Bucketer::new(width_ms, price_format.scale));
+    let quote_filter = quote_filter(matches);
+    let mut filtered_by_script = 0;
+    let per_issue_stats = *matches.get_one::<bool>("per-issue-stats").unwrap();
+    let top = matches.get_one::<usize>("top").copied();
+    let mut issue_stats = (per_issue_stats || top.is_some()).then(IssueStatsCollector::new);
+    let mut latencies = latency_histogram(matches);
+    let clock = latency_clock(matches);
+    let max_latency_us = matches.get_one::<i64>("max-latency-us").copied();
+    let mut latency_spikes = 0;
+    let mut rates = rate_counter(matches);
+    let mut bursts = burst_detector(matches);
+    let mut redis = redis_sink(matches);
+    let mut quote_count = 0u64;
+    let mut volume_inconsistencies = 0;
+    // payload is only there with --with-hex
+    let mut on_quote = |price_quote: &PriceQuote, payload: Option<&[u8]>| {
+        if let Some(filter) = &quote_filter {
+            if !filter(price_quote) {
+                filtered_by_script += 1;
+                return ControlFlow::Continue(());
+            }
+        }
+        if validate {
+            volume_inconsistencies += validate_volumes(std::slice::from_ref(price_quote));
+        }
+        if let Some(issue_stats) = &mut issue_stats {
+            issue_stats.observe(price_quote);
+        }
+        if let Some(latencies) = &mut latencies {
+            if let Some(latency_us) = quote_latency_us(price_quote, clock) {
+                latencies.record(latency_us);
+                if latency_spike(price_quote, latency_us, max_latency_us) {
+                    latency_spikes += 1;
+                }
+            }
+        }
+        if let Some(rates) = &mut rates {
+            rates.record(price_quote.packet_rcv_time);
+        }
+        if let Some(bursts) = &mut bursts {
+            report_burst(bursts.record(price_quote.packet_rcv_time, &price_quote.issue_code));
+        }
+        quote_count += 1;
+        if let Some(splitter) = &mut splitter {
+            write_split(splitter, price_quote);
+        } else if let Some(splitter) = &mut date_splitter {
+            write_date_split(splitter, price_quote);
+        } else if let Some(server) = &ws_server {
+            server.send(price_quote);
+        } else if let Some(bucketer) = &mut bucketer {
+            bucketer.observe(price_quote);
+        } else if let Some(trade_inferrer) = &mut trade_inferrer {
+            for trade in trade_inferrer.observe(price_quote) {
+                if !count && !stats_only {
+                    println!("{}", trade.fmt_with(price_format));
+                }
+            }
+        } else if let Some(sink) = &mut redis {
+            send_to_redis(sink, price_quote);
+        } else if !count && !stats_only {
+            match payload {
+                Some(payload) => println!(
+                    "{} hex={}",
+                    printer.format(price_quote),
+                    encode_hex(payload)
+                ),
+                None => println!("{}", printer.format(price_quote)),
+            }
+        }
+        ControlFlow::Continue(())
+    };
+    let result = if matches.contains_id("merge-pcap") {
+        merge_price_quotes_with(files, parse_options, |q| on_quote(q, None))
+    } else if let Some(&jobs) = matches.get_one::<usize>("jobs") {
+        // the files are only put back in order once they're all parsed, so
+        // nothing can be printed as it's decoded
+        concat_price_quotes_from_readers_parallel(files, parse_options, jobs).map(
+            |(price_quotes, parse_stats)| {
+                for price_quote in &price_quotes {
+                    let _ = on_quote(price_quote, None);
+                }
+                parse_stats
+            },
+        )
+    } else if *matches.get_one::<bool>("with-hex").unwrap() {
+        concat_price_quotes_with_payload(files, parse_options, |q, payload| {
+            on_quote(&q, Some(payload))
+        })
+    } else {
+        concat_price_quotes_with(files, parse_options, |q| on_quote(q, None))
+    };
+    let mut parse_stats = match result {
+        Ok(parse_stats) => parse_stats,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+    };
+    parse_stats.volume_inconsistencies = volume_inconsistencies;
+    parse_stats.filtered_by_script = filtered_by_script;
+    parse_stats.latency_spikes = latency_spikes;
+    // --top on its own gets a table of its own, with --per-issue-stats it
+    // only trims that one
+    let mut top_issues = issue_stats.map(|issue_stats| issue_stats.finish(top));
+    if per_issue_stats {
+        parse_stats.per_issue = top_issues.take();
+        parse_stats.price_format = price_format;
+    }
+    parse_stats.latency_report = latencies.and_then(|latencies| latencies.report());
+    parse_stats.rate_series = rates.and_then(|rates| rates.series());
+    if let Some(mut bursts) = bursts {
+        report_burst(bursts.finish());
+        parse_stats.worst_burst = bursts.worst().cloned();
+    }
+    if let Some(splitter) = &mut splitter {
+        finish_split(splitter);
+    }
+    if let Some(splitter) = &mut date_splitter {
+        finish_date_split(splitter);
+    }
+    if let Some(server) = ws_server {
+        server.close();
+    }
+
+    if let Some(bucketer) = bucketer {
+        if bucketer.unbucketed > 0 {
+            eprintln!(
+                "warning: {} quotes had no readable accept time and aren't in any bucket",
+                bucketer.unbucketed
+            );
+        }
+        if !count && !stats_only {
+            for bucket in bucketer.finish() {
+                println!("{}", bucket);
+            }
+        }
+    }
+    if count {
+        println!("{}", quote_count);
+    }
+    if let Some(top_issues) = &top_issues {
+        print!("\n{}", format_top_issues(top_issues, price_format));
+    }
+    parse_stats.violations = thresholds(matches).check(&parse_stats);
+    if stats_only {
+        print!("{}", parse_stats);
+    } else if !*matches.get_one::<bool>("quiet").unwrap() {
+        println!("\n{}", parse_stats);
+    }
+    exit_on_violations(&parse_stats);
+}
+
+fn run_headers_only(paths: &[String], parse_options: &ParseOptions, matches: &ArgMatches) {
+    let count = *matches.get_one::<bool>("count").unwrap();
+    let stats_only = *matches.get_one::<bool>("stats-only").unwrap();
+
+    let mut quote_count = 0u64;
+    let result = concat_quote_headers_with(open_inputs(paths), parse_options, |header| {
+        quote_count += 1;
+        if !count && !stats_only {
+            println!("{}", header);
+        }
+        ControlFlow::Continue(())
+    });
+    let mut parse_stats = match result {
+        Ok(parse_stats) => parse_stats,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    if count {
+        println!("{}", quote_count);
+    }
+    parse_stats.violations = thresholds(matches).check(&parse_stats);
+    if stats_only {
+        print!("{}", parse_stats);
+    } else if !*matches.get_one::<bool>("quiet").unwrap() {
+        println!("\n{}", parse_stats);
+    }
+    exit_on_violations(&parse_stats);
+}
+
+// counts quotes per issue off the headers alone, so nothing but the counts
+// is kept
+fn run_issue_list(paths: &[String], parse_options: &ParseOptions, matches: &ArgMatches) {
+    let mut counts: HashMap<String, u64> = HashMap::new();
+    let result = concat_quote_headers_with(open_inputs(paths), parse_options, |header| {
+        let issue_code = header
+            .issue_code
+            .trim_matches(|c: char| c.is_whitespace() || c == '\0');
+        match counts.get_mut(issue_code) {
+            Some(count) => *count += 1,
+            None => {
+                counts.insert(issue_code.to_string(), 1);
+            }
+        }
+        ControlFlow::Continue(())
+    });
+    let mut parse_stats = match result {
+        Ok(parse_stats) => parse_stats,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
         }
+    };
 
-        // try to parse price quote
-        let payload = parsed_packet.payload;
-        let packet_received_time = pcap_packet.timestamp;
-        match PriceQuote::from_bytes(packet_received_time, payload) {
-            Ok(price_quote) => {
-                price_quotes.push(price_quote);
+    let min_count = *matches.get_one::<u64>("issue-list-min-count").unwrap();
+    print!("{}", report::format_issue_list(&counts, min_count));
+    parse_stats.violations = thresholds(matches).check(&parse_stats);
+    if !*matches.get_one::<bool>("quiet").unwrap() {
+        println!("\n{}", parse_stats);
+    }
+    exit_on_violations(&parse_stats);
+}
+
+fn issue_splitter(matches: &ArgMatches) -> IssueSplitter {
+    let dir = matches.get_one::<String>("output-dir").unwrap();
+    let max_open = *matches.get_one::<usize>("max-open-files").unwrap();
+    IssueSplitter::new(dir, max_open).unwrap_or_else(|err| {
+        eprintln!("error: couldn't create {}: {}", dir, err);
+        std::process::exit(1);
+    })
+}
+
+fn write_split(splitter: &mut IssueSplitter, price_quote: &PriceQuote) {
+    if let Err(err) = splitter.write(price_quote) {
+        let path = splitter.path_for(&price_quote.issue_code);
+        eprintln!("error: couldn't write {}: {}", path.display(), err);
+        std::process::exit(1);
+    }
+}
+
+fn finish_split(splitter: &mut IssueSplitter) {
+    if let Err(err) = splitter.flush() {
+        eprintln!("error: couldn't write issue files: {}", err);
+        std::process::exit(1);
+    }
+    eprintln!("wrote {} issue files", splitter.issue_count());
+}
+
+fn date_splitter(matches: &ArgMatches) -> DateSplitter {
+    let dir = matches.get_one::<String>("split-by-date").unwrap();
+    let tz = *matches.get_one::<Tz>("timezone").unwrap();
+    DateSplitter::new(dir, tz).unwrap_or_else(|err| {
+        eprintln!("error: couldn't create {}: {}", dir, err);
+        std::process::exit(1);
+    })
+}
+
+fn write_date_split(splitter: &mut DateSplitter, price_quote: &PriceQuote) {
+    if let Err(err) = splitter.write(price_quote) {
+        eprintln!("error: couldn't write date files: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn finish_date_split(splitter: &mut DateSplitter) {
+    if let Err(err) = splitter.flush() {
+        eprintln!("error: couldn't write date files: {}", err);
+        std::process::exit(1);
+    }
+    eprintln!("wrote {} date files", splitter.dates().len());
+}
+
+fn quote_printer(matches: &ArgMatches, style: DisplayStyle) -> QuotePrinter {
+    let mut printer = QuotePrinter::new(*matches.get_one::<bool>("spread-colors").unwrap(), style);
+    printer.price_format = price_format(matches);
+    if *matches.get_one::<bool>("color-by-spread").unwrap() {
+        printer.line_colors = Some(
+            matches
+                .get_one::<SpreadThresholds>("spread-thresholds")
+                .copied()
+                .unwrap_or_default(),
+        );
+    }
+    printer
+}
+
+// color only when a person is looking at it
+fn stdout_style() -> DisplayStyle {
+    if std::io::stdout().is_terminal() {
+        DisplayStyle::Colored
+    } else {
+        DisplayStyle::Plain
+    }
+}
+
+fn run_mock_feed(matches: &ArgMatches) {
+    let num_quotes = *matches.get_one::<usize>("num-quotes").unwrap();
+    let num_issues = *matches.get_one::<usize>("num-issues").unwrap();
+    let output_path = matches.get_one::<String>("output-path").unwrap();
+    let seed = matches.get_one::<u64>("seed").copied().unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+    });
+    if num_issues == 0 {
+        eprintln!("error: --num-issues must be at least 1");
+        std::process::exit(2);
+    }
+
+    let file = match File::create(output_path) {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("error: couldn't create {}: {}", output_path, err);
+            std::process::exit(1);
+        }
+    };
+    let written = mock::write_mock_pcap(BufWriter::new(file), num_quotes, num_issues, seed)
+        .map_err(|err| err.to_string())
+        .and_then(|mut writer| writer.flush().map_err(|err| err.to_string()));
+    if let Err(err) = written {
+        eprintln!("error: couldn't write {}: {}", output_path, err);
+        std::process::exit(1);
+    }
+    eprintln!(
+        "wrote {} quotes across {} issues to {} (seed {})",
+        num_quotes, num_issues, output_path, seed
+    );
+}
+
+// both sides of a diff, exiting on the first that doesn't parse
+fn parse_diff_sides(
+    a_path: &str,
+    b_path: &str,
+    parse_options: &ParseOptions,
+) -> (Vec<PriceQuote>, Vec<PriceQuote>) {
+    let parse = |path: &str| match parse_price_quotes_from_file(path, parse_options) {
+        Ok((price_quotes, _)) => price_quotes,
+        Err(err) => {
+            eprintln!("error: {}: {}", path, err);
+            std::process::exit(1);
+        }
+    };
+    (parse(a_path), parse(b_path))
+}
+
+fn run_diff(a_path: &str, b_path: &str) {
+    const SAMPLE_SIZE: usize = 5;
+
+    let (a, b) = parse_diff_sides(a_path, b_path, &ParseOptions::default());
+
+    let diff = diff::content_diff(&a, &b);
+    println!(
+        "{}: {} quotes, {} only here",
+        a_path,
+        a.len(),
+        diff.only_in_a.len()
+    );
+    println!(
+        "{}: {} quotes, {} only here",
+        b_path,
+        b.len(),
+        diff.only_in_b.len()
+    );
+    for price_quote in diff.only_in_a.iter().take(SAMPLE_SIZE) {
+        println!("- {}", price_quote.fmt_styled(stdout_style()));
+    }
+    for price_quote in diff.only_in_b.iter().take(SAMPLE_SIZE) {
+        println!("+ {}", price_quote.fmt_styled(stdout_style()));
+    }
+
+    if !diff.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+fn run_diff_files(
+    a_path: &str,
+    b_path: &str,
+    parse_options: &ParseOptions,
+    price_format: PriceFormat,
+) {
+    let (a, b) = parse_diff_sides(a_path, b_path, parse_options);
+
+    let diff = diff::diff_quote_sets(&a, &b);
+    for change in &diff.changes {
+        match change {
+            diff::QuoteChange::Removed(price_quote) => {
+                println!("- {}", price_quote.fmt_with(stdout_style(), price_format))
+            }
+            diff::QuoteChange::Added(price_quote) => {
+                println!("+ {}", price_quote.fmt_with(stdout_style(), price_format))
             }
-            Err(_) => {
-                parse_stats.failed += 1;
+            diff::QuoteChange::Changed { a, fields, .. } => {
+                let accept_time = a.accept_time().map_or("invalid".to_string(), |t| {
+                    t.format("%H:%M:%S%.3f").to_string()
+                });
+                println!("~ {} {}", a.issue_code, accept_time);
+                for field in fields {
+                    println!("    {}: {} -> {}", field.field, field.a, field.b);
+                }
             }
         }
     }
-    parse_stats.parse_time = start.elapsed();
+    eprintln!(
+        "{} removed, {} added, {} changed, {} unchanged",
+        diff.removed(),
+        diff.added(),
+        diff.changed(),
+        diff.unchanged
+    );
+
+    if !diff.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+// --output redis, connected. None for every other output
+#[cfg(feature = "redis")]
+fn redis_sink(matches: &ArgMatches) -> Option<RedisSink> {
+    if matches.get_one::<String>("output").unwrap() != "redis" {
+        return None;
+    }
+    let url = matches.get_one::<String>("redis-url").unwrap();
+    let stream = matches.get_one::<String>("redis-stream").unwrap();
+    let maxlen = matches.get_one::<usize>("redis-maxlen").copied();
+    let sink = RedisSink::connect(url, stream, maxlen).unwrap_or_else(|err| {
+        eprintln!("error: couldn't connect to {}: {}", url, err);
+        std::process::exit(1);
+    });
+    Some(sink)
+}
+
+#[cfg(not(feature = "redis"))]
+fn redis_sink(matches: &ArgMatches) -> Option<RedisSink> {
+    if matches.get_one::<String>("output").unwrap() != "redis" {
+        return None;
+    }
+    eprintln!("error: --output redis needs a build with the redis feature");
+    std::process::exit(2);
+}
+
+#[cfg(feature = "redis")]
+fn send_to_redis(sink: &mut RedisSink, price_quote: &PriceQuote) {
+    if let Err(err) = sink.send(price_quote) {
+        eprintln!("error: couldn't add a quote to the redis stream: {}", err);
+        std::process::exit(1);
+    }
+}
 
-    parse_stats.successfully_parsed = price_quotes.len() as u64;
+// there's never one of these without the redis feature, redis_sink exits
+// instead
+#[cfg(not(feature = "redis"))]
+enum RedisSink {}
 
-    (price_quotes, parse_stats)
+#[cfg(not(feature = "redis"))]
+fn send_to_redis(sink: &mut RedisSink, _: &PriceQuote) {
+    match *sink {}
 }
@@ -0,0 +1,226 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// which krx market a quote belongs to. the wire carries this as a single
+// ascii digit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MarketType {
+    Kospi,
+    Kosdaq,
+    Derivatives,
+    Unknown(u16),
+}
+
+impl MarketType {
+    pub fn from_code(code: u16) -> Self {
+        match code {
+            1 => MarketType::Kospi,
+            2 => MarketType::Kosdaq,
+            4 => MarketType::Derivatives,
+            other => MarketType::Unknown(other),
+        }
+    }
+
+    pub fn code(&self) -> u16 {
+        match self {
+            MarketType::Kospi => 1,
+            MarketType::Kosdaq => 2,
+            MarketType::Derivatives => 4,
+            MarketType::Unknown(code) => *code,
+        }
+    }
+
+    pub fn is_known(&self) -> bool {
+        !matches!(self, MarketType::Unknown(_))
+    }
+}
+
+impl fmt::Display for MarketType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarketType::Kospi => write!(f, "KOSPI"),
+            MarketType::Kosdaq => write!(f, "KOSDAQ"),
+            MarketType::Derivatives => write!(f, "Derivatives"),
+            MarketType::Unknown(code) => write!(f, "Unknown ({})", code),
+        }
+    }
+}
+
+// the trading session state, sent as two ascii digits. the codes are the
+// ones in the exchange's feed specification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MarketStatus {
+    // 00, quotes before the open are indicative only
+    PreOpen,
+    // 10
+    OpeningAuction,
+    // 11
+    OpeningAuctionExtended,
+    // 20
+    IntradayAuction,
+    // 21
+    IntradayAuctionExtended,
+    // 30
+    ClosingAuction,
+    // 40
+    Continuous,
+    // 80, after hours single price trading
+    UnitTrading,
+    // 90
+    Halted,
+    // 99
+    Closed,
+    Unknown(u16),
+}
+
+impl MarketStatus {
+    pub fn from_code(code: u16) -> Self {
+        match code {
+            0 => MarketStatus::PreOpen,
+            10 => MarketStatus::OpeningAuction,
+            11 => MarketStatus::OpeningAuctionExtended,
+            20 => MarketStatus::IntradayAuction,
+            21 => MarketStatus::IntradayAuctionExtended,
+            30 => MarketStatus::ClosingAuction,
+            40 => MarketStatus::Continuous,
+            80 => MarketStatus::UnitTrading,
+            90 => MarketStatus::Halted,
+            99 => MarketStatus::Closed,
+            other => MarketStatus::Unknown(other),
+        }
+    }
+
+    pub fn code(&self) -> u16 {
+        match self {
+            MarketStatus::PreOpen => 0,
+            MarketStatus::OpeningAuction => 10,
+            MarketStatus::OpeningAuctionExtended => 11,
+            MarketStatus::IntradayAuction => 20,
+            MarketStatus::IntradayAuctionExtended => 21,
+            MarketStatus::ClosingAuction => 30,
+            MarketStatus::Continuous => 40,
+            MarketStatus::UnitTrading => 80,
+            MarketStatus::Halted => 90,
+            MarketStatus::Closed => 99,
+            MarketStatus::Unknown(code) => *code,
+        }
+    }
+
+    pub fn is_known(&self) -> bool {
+        !matches!(self, MarketStatus::Unknown(_))
+    }
+
+    pub const NAMES: [&'static str; 10] = [
+        "pre-open",
+        "opening-auction",
+        "opening-auction-extended",
+        "intraday-auction",
+        "intraday-auction-extended",
+        "closing-auction",
+        "continuous",
+        "unit-trading",
+        "halted",
+        "closed",
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pre-open" => Some(MarketStatus::PreOpen),
+            "opening-auction" => Some(MarketStatus::OpeningAuction),
+            "opening-auction-extended" => Some(MarketStatus::OpeningAuctionExtended),
+            "intraday-auction" => Some(MarketStatus::IntradayAuction),
+            "intraday-auction-extended" => Some(MarketStatus::IntradayAuctionExtended),
+            "closing-auction" => Some(MarketStatus::ClosingAuction),
+            "continuous" => Some(MarketStatus::Continuous),
+            "unit-trading" => Some(MarketStatus::UnitTrading),
+            "halted" => Some(MarketStatus::Halted),
+            "closed" => Some(MarketStatus::Closed),
+            _ => None,
+        }
+    }
+
+    pub fn is_continuous_session(&self) -> bool {
+        matches!(self, MarketStatus::Continuous)
+    }
+
+    // single price auctions, where quotes are indicative rather than live
+    pub fn is_auction(&self) -> bool {
+        matches!(
+            self,
+            MarketStatus::OpeningAuction
+                | MarketStatus::OpeningAuctionExtended
+                | MarketStatus::IntradayAuction
+                | MarketStatus::IntradayAuctionExtended
+                | MarketStatus::ClosingAuction
+        )
+    }
+
+    pub fn is_trading(&self) -> bool {
+        self.is_continuous_session() || self.is_auction() || *self == MarketStatus::UnitTrading
+    }
+}
+
+impl fmt::Display for MarketStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarketStatus::PreOpen => write!(f, "Pre-Open"),
+            MarketStatus::OpeningAuction => write!(f, "Opening Auction"),
+            MarketStatus::OpeningAuctionExtended => write!(f, "Opening Auction (Extended)"),
+            MarketStatus::IntradayAuction => write!(f, "Intraday Auction"),
+            MarketStatus::IntradayAuctionExtended => write!(f, "Intraday Auction (Extended)"),
+            MarketStatus::ClosingAuction => write!(f, "Closing Auction"),
+            MarketStatus::Continuous => write!(f, "Continuous"),
+            MarketStatus::UnitTrading => write!(f, "Unit Trading"),
+            MarketStatus::Halted => write!(f, "Halted"),
+            MarketStatus::Closed => write!(f, "Closed"),
+            MarketStatus::Unknown(code) => write!(f, "Unknown ({})", code),
+        }
+    }
+}
+
+// either the numeric code or one of NAMES
+impl FromStr for MarketStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(code) = s.parse::<u16>() {
+            return Ok(MarketStatus::from_code(code));
+        }
+        MarketStatus::from_name(s).ok_or_else(|| {
+            format!(
+                "expected a status code or one of {}",
+                MarketStatus::NAMES.join(", ")
+            )
+        })
+    }
+}
+
+// turns raw little endian ascii digits back into the number they spell.
+// None if any byte isn't a digit
+pub fn decode_ascii_code(raw: u64, len: usize) -> Option<u16> {
+    let bytes = raw.to_le_bytes();
+    let digits = &bytes[..len];
+    if !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    Some(
+        digits
+            .iter()
+            .fold(0u16, |acc, d| acc * 10 + (d - b'0') as u16),
+    )
+}
+
+// the other direction: code as len ascii digits packed little endian, the
+// way the decoder stores them
+pub fn encode_ascii_code(code: u64, len: usize) -> u64 {
+    let digits = format!("{:0width$}", code, width = len);
+    let digits = &digits.as_bytes()[digits.len() - len..];
+    let mut bytes = [0u8; 8];
+    bytes[..len].copy_from_slice(digits);
+    u64::from_le_bytes(bytes)
+}
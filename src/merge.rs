@@ -0,0 +1,127 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::{BufReader, Read};
+use std::ops::ControlFlow;
+
+use pcap_file::pcap::{PcapPacket, PcapReader};
+
+use crate::error::ParseFileError;
+use crate::parsing::{quote_parser, screen_quote, PacketLoop, Stopwatch, PCAP_RECORD_HEADER_LEN};
+use crate::{PacketParseStats, ParseOptions, PriceQuote};
+
+// position of a capture in the list being merged
+type FileIndex = usize;
+
+// merges several pcap captures into one, like mergecap: always takes the
+// packet with the earliest timestamp out of all the files, so quotes come out
+// in global capture time order. for captures split across machines with
+// synced clocks. ties go to the capture listed first
+pub fn merge_price_quotes_from_readers<R: Read>(
+    readers: Vec<R>,
+    options: &ParseOptions,
+) -> Result<(Vec<PriceQuote>, PacketParseStats), ParseFileError> {
+    let mut price_quotes = vec![];
+    let parse_stats = merge_price_quotes_into(readers, options, |price_quote| {
+        price_quotes.push(price_quote);
+        ControlFlow::Continue(())
+    })?;
+    Ok((price_quotes, parse_stats))
+}
+
+// the merged quotes one at a time, see parse_price_quotes_with
+pub fn merge_price_quotes_with<R, F>(
+    readers: Vec<R>,
+    options: &ParseOptions,
+    mut f: F,
+) -> Result<PacketParseStats, ParseFileError>
+where
+    R: Read,
+    F: FnMut(&PriceQuote) -> ControlFlow<()>,
+{
+    merge_price_quotes_into(readers, options, |price_quote| f(&price_quote))
+}
+
+fn merge_price_quotes_into<R, F>(
+    readers: Vec<R>,
+    options: &ParseOptions,
+    mut f: F,
+) -> Result<PacketParseStats, ParseFileError>
+where
+    R: Read,
+    F: FnMut(PriceQuote) -> ControlFlow<()>,
+{
+    let start = Stopwatch::start();
+    let mut packet_loop = PacketLoop::new(quote_parser(options), options.clone());
+    let mut captures = readers
+        .into_iter()
+        .map(|reader| {
+            PcapReader::new(BufReader::new(packet_loop.counting(reader)))
+                .map_err(ParseFileError::Header)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    packet_loop.open_event_log()?;
+
+    // the next packet of every capture that has one left, waiting its turn
+    let mut pending: Vec<Option<PcapPacket<'static>>> = vec![];
+    let mut queue = BinaryHeap::new();
+    for (index, capture) in captures.iter_mut().enumerate() {
+        let packet = next_packet(capture, &mut packet_loop.parse_stats)?;
+        if let Some(packet) = &packet {
+            queue.push((Reverse(packet.timestamp), Reverse(index as FileIndex)));
+        }
+        pending.push(packet);
+    }
+
+    while let Some((_, Reverse(index))) = queue.pop() {
+        let packet = pending[index]
+            .take()
+            .expect("queued captures have a packet");
+        let message = packet_loop.handle_frame(
+            packet.timestamp,
+            &packet.data,
+            packet.orig_len,
+            PCAP_RECORD_HEADER_LEN + packet.data.len() as u64,
+        )?;
+
+        pending[index] = next_packet(&mut captures[index], &mut packet_loop.parse_stats)?;
+        if let Some(next) = &pending[index] {
+            queue.push((Reverse(next.timestamp), Reverse(index)));
+        }
+
+        let Some(message) = message else {
+            continue;
+        };
+        let price_quote = *message
+            .downcast::<PriceQuote>()
+            .expect("B6034Parser only produces price quotes");
+        if !screen_quote(options, &mut packet_loop.parse_stats, &price_quote) {
+            continue;
+        }
+        if f(price_quote).is_break() {
+            break;
+        }
+    }
+
+    packet_loop.close_event_log()?;
+    packet_loop.note_io_time();
+    let mut parse_stats = packet_loop.parse_stats;
+    parse_stats.parse_time = start.elapsed();
+    Ok(parse_stats)
+}
+
+fn next_packet<R: Read>(
+    capture: &mut PcapReader<R>,
+    parse_stats: &mut PacketParseStats,
+) -> Result<Option<PcapPacket<'static>>, ParseFileError> {
+    match capture
+        .next_packet()
+        .map(|packet| packet.map(PcapPacket::into_owned))
+    {
+        Some(Ok(packet)) => Ok(Some(packet)),
+        Some(Err(source)) => Err(ParseFileError::Record {
+            packet_index: parse_stats.packet_count + 1,
+            source,
+        }),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::HashSet;
+use std::io::Write;
+use std::time::Duration;
+
+use chrono::NaiveTime;
+use pcap_file::pcap::{PcapPacket, PcapWriter};
+use pcap_file::PcapError;
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+
+use crate::builder::PriceQuoteBuilder;
+use crate::parser::B6034Parser;
+use crate::price_quote::{PriceQuote, LEVELS};
+use crate::testdata::udp_frame;
+
+// 2011-02-16 09:00:00 KST, the session the builder defaults to
+const SESSION_START: Duration = Duration::from_secs(1297814400);
+const TICK: u64 = 5;
+
+struct MockIssue {
+    issue_code: String,
+    seq: u32,
+    // wanders a tick or two per quote
+    mid: u64,
+}
+
+// random but well formed B6034 quotes, in rising capture time. the same seed
+// always gives the same feed
+pub struct MockFeed {
+    rng: StdRng,
+    issues: Vec<MockIssue>,
+    quotes: usize,
+    elapsed: Duration,
+}
+
+impl MockFeed {
+    pub fn new(num_issues: usize, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut issue_codes = HashSet::new();
+        while issue_codes.len() < num_issues.max(1) {
+            issue_codes.insert(random_issue_code(&mut rng));
+        }
+        let mut issue_codes: Vec<_> = issue_codes.into_iter().collect();
+        issue_codes.sort();
+
+        let issues = issue_codes
+            .into_iter()
+            .enumerate()
+            .map(|(i, issue_code)| MockIssue {
+                issue_code,
+                seq: (i as u32 % 999) + 1,
+                mid: rng.random_range(20..600) * TICK,
+            })
+            .collect();
+        MockFeed {
+            rng,
+            issues,
+            quotes: 0,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    // every issue gets a quote before any repeats, after that it's random
+    pub fn next_quote(&mut self) -> PriceQuote {
+        let index = if self.quotes < self.issues.len() {
+            self.quotes
+        } else {
+            self.rng.random_range(0..self.issues.len())
+        };
+        self.quotes += 1;
+        self.elapsed += Duration::from_micros(self.rng.random_range(500..20_000));
+
+        let rng = &mut self.rng;
+        let issue = &mut self.issues[index];
+        let step = rng.random_range(0..=4) * TICK;
+        issue.mid = (issue.mid + step)
+            .saturating_sub(2 * TICK)
+            .max(LEVELS as u64 * TICK * 2);
+
+        let bids: Vec<_> = (1..=LEVELS as u64)
+            .map(|level| (issue.mid - level * TICK, rng.random_range(1..50)))
+            .collect();
+        let asks: Vec<_> = (1..=LEVELS as u64)
+            .map(|level| (issue.mid + level * TICK, rng.random_range(1..50)))
+            .collect();
+        let mut builder = PriceQuoteBuilder::new()
+            .issue_code(&issue.issue_code)
+            .seq(issue.seq)
+            .rcv_time(SESSION_START + self.elapsed)
+            .accept_time(session_time(self.elapsed))
+            .bids(&bids)
+            .asks(&asks);
+        for level in 0..LEVELS {
+            builder = builder
+                .bid_orders(level, rng.random_range(1..10))
+                .ask_orders(level, rng.random_range(1..10));
+        }
+        builder.build()
+    }
+}
+
+// a pcap of num_quotes mock quotes as ethernet/ipv4/udp frames with valid
+// checksums, alternating between the two feed ports
+pub fn write_mock_pcap<W: Write>(
+    writer: W,
+    num_quotes: usize,
+    num_issues: usize,
+    seed: u64,
+) -> Result<W, PcapError> {
+    let mut feed = MockFeed::new(num_issues, seed);
+    let mut pcap = PcapWriter::new(writer)?;
+    for i in 0..num_quotes {
+        let quote = feed.next_quote();
+        let port = B6034Parser::PORTS[i % B6034Parser::PORTS.len()];
+        let frame = udp_frame(port, &quote.encode_b6034());
+        pcap.write_packet(&PcapPacket::new(
+            quote.packet_rcv_time,
+            frame.len() as u32,
+            &frame,
+        ))?;
+    }
+    Ok(pcap.into_writer())
+}
+
+// kospi200 futures and options style isins, like KR4301F42629
+fn random_issue_code(rng: &mut StdRng) -> String {
+    format!(
+        "KR4{}01F{:05}",
+        rng.random_range(1..=3),
+        rng.random_range(0..100_000)
+    )
+}
+
+// accept times are only good to the hundredth
+fn session_time(elapsed: Duration) -> NaiveTime {
+    let open = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    let hundredths = elapsed.as_millis() as i64 / 10;
+    open + chrono::Duration::milliseconds(hundredths * 10)
+}
@@ -0,0 +1,103 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::Timelike;
+
+use crate::price_quote::{
+    PriceQuote, ASK_COUNT_FIELDS, ASK_PRICE_FIELDS, ASK_QTY_FIELDS, BID_COUNT_FIELDS,
+    BID_PRICE_FIELDS, BID_QTY_FIELDS, LEVELS,
+};
+
+// one <field>.npy per numeric field under dir, each a one dimensional array
+// with an entry per quote, so numpy.load lines them up by index. prices are
+// f64, scaled by scale, and quantities u64. the times are i64 nanoseconds: packet_rcv_time
+// since the unix epoch, quote_accept_time since midnight. seq and the order
+// counts are i64, and like the accept time -1 when they're unreadable. the identifying codes (data type,
+// market, issue code) aren't numbers and are left out. returns the files
+// written
+pub fn write_quotes_to_npy(
+    quotes: &[PriceQuote],
+    dir: &Path,
+    scale: u32,
+) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    let mut written = vec![];
+    let mut column = |name: &str| {
+        let path = dir.join(format!("{}.npy", name));
+        written.push(path.clone());
+        path
+    };
+
+    npy::to_file(
+        column("packet_rcv_time"),
+        quotes.iter().map(|q| q.packet_rcv_time.as_nanos() as i64),
+    )?;
+    npy::to_file(
+        column("quote_accept_time"),
+        quotes.iter().map(|q| {
+            q.accept_time().map_or(-1, |t| {
+                t.num_seconds_from_midnight() as i64 * 1_000_000_000 + t.nanosecond() as i64
+            })
+        }),
+    )?;
+    npy::to_file(
+        column("issue_seq_no"),
+        quotes.iter().map(|q| q.seq().map_or(-1, i64::from)),
+    )?;
+    npy::to_file(
+        column("market_status_type"),
+        quotes.iter().map(|q| q.market_status().code()),
+    )?;
+    npy::to_file(
+        column("total_bid_quote_volume"),
+        quotes.iter().map(|q| q.total_bid_quote_volume.raw()),
+    )?;
+    npy::to_file(
+        column("total_ask_quote_volume"),
+        quotes.iter().map(|q| q.total_ask_quote_volume.raw()),
+    )?;
+    for i in 0..LEVELS {
+        npy::to_file(
+            column(BID_PRICE_FIELDS[i]),
+            quotes.iter().map(|q| q.bids[i].price.to_f64(scale)),
+        )?;
+        npy::to_file(
+            column(BID_QTY_FIELDS[i]),
+            quotes.iter().map(|q| q.bids[i].qty.raw()),
+        )?;
+        npy::to_file(
+            column(BID_COUNT_FIELDS[i]),
+            quotes
+                .iter()
+                .map(|q| q.bids[i].orders().map_or(-1, |n| n as i64)),
+        )?;
+        npy::to_file(
+            column(ASK_PRICE_FIELDS[i]),
+            quotes.iter().map(|q| q.asks[i].price.to_f64(scale)),
+        )?;
+        npy::to_file(
+            column(ASK_QTY_FIELDS[i]),
+            quotes.iter().map(|q| q.asks[i].qty.raw()),
+        )?;
+        npy::to_file(
+            column(ASK_COUNT_FIELDS[i]),
+            quotes
+                .iter()
+                .map(|q| q.asks[i].orders().map_or(-1, |n| n as i64)),
+        )?;
+    }
+    npy::to_file(
+        column("no_of_best_bid_valid_quote_total"),
+        quotes
+            .iter()
+            .map(|q| q.bid_order_total().map_or(-1, |n| n as i64)),
+    )?;
+    npy::to_file(
+        column("no_of_best_ask_valid_quote_total"),
+        quotes
+            .iter()
+            .map(|q| q.ask_order_total().map_or(-1, |n| n as i64)),
+    )?;
+    Ok(written)
+}
@@ -0,0 +1,152 @@
+use std::any::Any;
+use std::time::Duration;
+
+use crate::error::QuoteDecodeError;
+use crate::price_quote::{message_len, NumericEncoding, PriceQuote, ISSUE_CODE_LEN};
+
+// decides which udp packets a message type cares about and decodes them.
+// parse hands back a boxed value so callers downcast to the parser's type
+pub trait PacketParser {
+    fn accepts_port(&self, port: u16) -> bool;
+    fn accepts_payload_prefix(&self, payload: &[u8]) -> bool;
+    // size of one message, used to split raw input with no packet framing
+    fn message_len(&self) -> usize;
+    fn parse(&self, rcv_time: Duration, payload: &[u8]) -> Result<Box<dyn Any>, QuoteDecodeError>;
+}
+
+impl<P: PacketParser + ?Sized> PacketParser for &P {
+    fn accepts_port(&self, port: u16) -> bool {
+        (**self).accepts_port(port)
+    }
+
+    fn accepts_payload_prefix(&self, payload: &[u8]) -> bool {
+        (**self).accepts_payload_prefix(payload)
+    }
+
+    fn message_len(&self) -> usize {
+        (**self).message_len()
+    }
+
+    fn parse(&self, rcv_time: Duration, payload: &[u8]) -> Result<Box<dyn Any>, QuoteDecodeError> {
+        (**self).parse(rcv_time, payload)
+    }
+}
+
+// the kospi200 price quote feed this tool was written for. the ports,
+// prefixes and issue code width default to the ones the feed uses, other
+// message types sharing the layout differ in those
+#[derive(Debug, Clone)]
+pub struct B6034Parser {
+    pub ports: Vec<u16>,
+    pub prefixes: Vec<Vec<u8>>,
+    pub issue_code_len: usize,
+    pub numbers: NumericEncoding,
+}
+
+impl B6034Parser {
+    pub const PORTS: [u16; 2] = [15515, 15516];
+    pub const PREFIX: &'static [u8; 5] = b"B6034";
+}
+
+impl Default for B6034Parser {
+    fn default() -> Self {
+        B6034Parser {
+            ports: Self::PORTS.to_vec(),
+            prefixes: vec![Self::PREFIX.to_vec()],
+            issue_code_len: ISSUE_CODE_LEN,
+            numbers: NumericEncoding::Ascii,
+        }
+    }
+}
+
+impl PacketParser for B6034Parser {
+    fn accepts_port(&self, port: u16) -> bool {
+        self.ports.contains(&port)
+    }
+
+    fn accepts_payload_prefix(&self, payload: &[u8]) -> bool {
+        self.prefixes
+            .iter()
+            .any(|prefix| payload.starts_with(prefix))
+    }
+
+    fn message_len(&self) -> usize {
+        message_len(self.issue_code_len)
+    }
+
+    fn parse(&self, rcv_time: Duration, payload: &[u8]) -> Result<Box<dyn Any>, QuoteDecodeError> {
+        Ok(Box::new(PriceQuote::from_bytes_with(
+            rcv_time,
+            payload,
+            self.issue_code_len,
+            self.numbers,
+        )?))
+    }
+}
+
+// picks out the same packets as the B6034Parser it wraps, but only decodes
+// each quote's header. parse hands back a QuoteHeader
+#[derive(Debug, Clone, Default)]
+pub struct B6034HeaderParser(pub B6034Parser);
+
+impl PacketParser for B6034HeaderParser {
+    fn accepts_port(&self, port: u16) -> bool {
+        self.0.accepts_port(port)
+    }
+
+    fn accepts_payload_prefix(&self, payload: &[u8]) -> bool {
+        self.0.accepts_payload_prefix(payload)
+    }
+
+    fn message_len(&self) -> usize {
+        self.0.message_len()
+    }
+
+    fn parse(&self, rcv_time: Duration, payload: &[u8]) -> Result<Box<dyn Any>, QuoteDecodeError> {
+        Ok(Box::new(PriceQuote::header_from_bytes_with_issue_code_len(
+            rcv_time,
+            payload,
+            self.0.issue_code_len,
+        )?))
+    }
+}
+
+// whatever the wrapped parser produced, plus a copy of the payload it was
+// decoded from
+pub struct WithPayload {
+    pub message: Box<dyn Any>,
+    pub payload: Vec<u8>,
+}
+
+// keeps the source bytes of every message the wrapped parser decodes, for
+// when the original payload has to be shown. parse hands back a WithPayload
+#[derive(Debug, Clone, Default)]
+pub struct KeepPayload<P>(pub P);
+
+impl<P: PacketParser> PacketParser for KeepPayload<P> {
+    fn accepts_port(&self, port: u16) -> bool {
+        self.0.accepts_port(port)
+    }
+
+    fn accepts_payload_prefix(&self, payload: &[u8]) -> bool {
+        self.0.accepts_payload_prefix(payload)
+    }
+
+    fn message_len(&self) -> usize {
+        self.0.message_len()
+    }
+
+    fn parse(&self, rcv_time: Duration, payload: &[u8]) -> Result<Box<dyn Any>, QuoteDecodeError> {
+        Ok(Box::new(WithPayload {
+            message: self.0.parse(rcv_time, payload)?,
+            payload: payload.to_vec(),
+        }))
+    }
+}
+
+// a decoded message and the udp destination port it arrived on, see
+// concat_price_quotes_with_port. None for raw input, which has no udp header
+pub struct WithPort {
+    pub message: Box<dyn Any>,
+    pub port: Option<u16>,
+}
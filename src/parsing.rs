@@ -0,0 +1,2046 @@
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap, HashSet};
+#[cfg(feature = "cli")]
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read};
+use std::ops::{ControlFlow, Range};
+#[cfg(feature = "cli")]
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::NaiveTime;
+use etherparse::{InternetSlice, SerializedSize, SlicedPacket, TransportSlice, UdpHeader};
+use pcap_file::pcap::PcapReader;
+use pcap_file::pcapng::{Block, PcapNgReader};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::analytics;
+use crate::decap;
+use crate::error::{PacketClassifyError, ParseFileError};
+#[cfg(feature = "serde")]
+use crate::event_log::EventLogger;
+use crate::issue_stats::{format_issue_stats, IssueStats};
+use crate::market::{MarketStatus, MarketType};
+use crate::parser::{
+    B6034HeaderParser, B6034Parser, KeepPayload, PacketParser, WithPayload, WithPort,
+};
+use crate::price::PriceFormat;
+use crate::price_quote::{
+    accept_hundredths, fmt_accept_time, fmt_rcv_time, fmt_rcv_time_iso, NumericEncoding,
+    PriceQuote, QuoteHeader, LEVELS,
+};
+use crate::thresholds::Violation;
+
+// what arrived on one udp destination port
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PortCounts {
+    pub packets: u64,
+    pub quotes: u64,
+    pub failed: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PacketParseStats {
+    pub parse_time: std::time::Duration,
+    // the parts of parse_time spent reading packets off the capture, and
+    // classifying and decoding them. whatever's left went to whoever was
+    // handed the quotes. io_time is every read of the input. past the first
+    // CLOCK_EVERY packets only a sample is decoded against the clock, so
+    // decode_time is an estimate
+    pub io_time: Duration,
+    pub decode_time: Duration,
+    pub packet_count: u64,
+    // every captured byte of every packet, frame headers and all
+    pub total_bytes: u64,
+    // total_bytes plus the capture's own record headers, what was read for
+    // the packets. pcapng options aren't counted
+    pub record_bytes: u64,
+    // payloads of every udp packet, whatever the port. raw input's messages
+    // count as payloads too
+    pub udp_payload_bytes: u64,
+    // payloads that decoded as quotes, and the whole records they came in
+    pub quote_bytes: u64,
+    pub quote_record_bytes: u64,
+
+    // earliest and latest capture timestamps of any packet, epoch nanos in
+    // json. raw input has none
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_epoch_nanos"))]
+    pub first_rcv_time: Option<Duration>,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_epoch_nanos"))]
+    pub last_rcv_time: Option<Duration>,
+    // earliest and latest readable quote_accept_time of the decoded quotes,
+    // raw like the field itself and HH:MM:SS.mmm in json
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_accept_time"))]
+    pub first_accept_time: Option<u64>,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_accept_time"))]
+    pub last_accept_time: Option<u64>,
+
+    pub successfully_parsed: u64,
+    pub rejected: u64,
+    pub failed: u64,
+
+    pub non_udp: u64,
+    pub wrong_port: u64,
+    pub not_a_price_quote: u64,
+    // right prefix but the payload isn't exactly one message long, so it
+    // never reaches the decoder and isn't counted in failed
+    pub malformed_quote: u64,
+    // a quote whose ip or udp checksum doesn't match. only checked with
+    // verify_checksums, plenty of captures leave them zeroed
+    pub checksum_failures: u64,
+
+    // why quotes failed to decode, keyed by field and kind of problem
+    pub parse_failures: HashMap<String, u64>,
+
+    // frames that arrived wrapped in gre or erspan
+    pub decapsulated: u64,
+    // packets to a quote port the capture's snaplen cut short, so they show
+    // up as malformed
+    pub snaplen_truncated: u64,
+
+    // udp packets per destination port the parser listens on, every one of
+    // them even if nothing came in. other_ports lumps together the rest
+    pub ports: BTreeMap<u16, PortCounts>,
+    pub other_ports: PortCounts,
+
+    pub volume_inconsistencies: u64,
+    // quotes validate_book found anything wrong with, see --validate-book
+    pub book_violations: u64,
+    // quotes dropped by --filter-no-bid or --filter-no-ask
+    pub one_sided_market: u64,
+    // quotes dropped by --drop-bad-timestamps
+    pub bad_timestamps: u64,
+    // quotes dropped by --country
+    pub other_countries: u64,
+    // quotes dropped by --valid-issues
+    pub unknown_issue: u64,
+    // quotes --filter-script's filter turned down
+    pub filtered_by_script: u64,
+    // quotes that arrived more than --max-latency-us after they were
+    // accepted
+    pub latency_spikes: u64,
+    // quotes that got through the filters, by how many levels of each side
+    // have a price, 0 to 5
+    pub bid_depth: [u64; LEVELS + 1],
+    pub ask_depth: [u64; LEVELS + 1],
+
+    // market codes we don't have a name for, keyed by raw code
+    pub unknown_market_types: BTreeMap<u16, u64>,
+    pub unknown_market_statuses: BTreeMap<u16, u64>,
+    // quotes dropped by --exclude-market-status, keyed by raw code
+    pub excluded_market_statuses: BTreeMap<u16, u64>,
+
+    pub latency_report: Option<analytics::LatencyReport>,
+    // --rate-buckets
+    pub rate_series: Option<analytics::RateSeries>,
+    // --burst-window, the busiest window of them all
+    pub worst_burst: Option<analytics::Burst>,
+    // --per-issue-stats, most active first
+    pub per_issue: Option<Vec<IssueStats>>,
+    // how per_issue's spreads are printed, --price-scale and
+    // --price-precision
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub price_format: PriceFormat,
+    // the --max-failed, --max-failed-pct and --min-quotes limits the run
+    // broke, by name in json
+    pub violations: Vec<Violation>,
+}
+
+impl PacketParseStats {
+    pub fn new() -> Self {
+        PacketParseStats {
+            parse_time: std::time::Duration::new(0, 0),
+            io_time: Duration::ZERO,
+            decode_time: Duration::ZERO,
+            packet_count: 0,
+            total_bytes: 0,
+            record_bytes: 0,
+            udp_payload_bytes: 0,
+            quote_bytes: 0,
+            quote_record_bytes: 0,
+
+            first_rcv_time: None,
+            last_rcv_time: None,
+            first_accept_time: None,
+            last_accept_time: None,
+
+            successfully_parsed: 0,
+            rejected: 0,
+            failed: 0,
+
+            non_udp: 0,
+            wrong_port: 0,
+            not_a_price_quote: 0,
+            malformed_quote: 0,
+            checksum_failures: 0,
+
+            parse_failures: HashMap::new(),
+
+            decapsulated: 0,
+            snaplen_truncated: 0,
+
+            ports: BTreeMap::new(),
+            other_ports: PortCounts::default(),
+
+            volume_inconsistencies: 0,
+            book_violations: 0,
+            one_sided_market: 0,
+            bad_timestamps: 0,
+            other_countries: 0,
+            unknown_issue: 0,
+            filtered_by_script: 0,
+            latency_spikes: 0,
+            bid_depth: [0; LEVELS + 1],
+            ask_depth: [0; LEVELS + 1],
+
+            unknown_market_types: BTreeMap::new(),
+            unknown_market_statuses: BTreeMap::new(),
+            excluded_market_statuses: BTreeMap::new(),
+
+            latency_report: None,
+            rate_series: None,
+            worst_burst: None,
+            per_issue: None,
+            price_format: PriceFormat::default(),
+            violations: vec![],
+        }
+    }
+
+    // folds in the stats of another run, over another file or another part
+    // of the same one. counts add up and time ranges widen. parse_time is
+    // the longer of the two, since runs being merged usually ran side by
+    // side. io_time and decode_time do add up, so across threads they can
+    // come to more than parse_time. the latency report, rate series, worst
+    // burst, per_issue, price_format and violations are left alone, they don't add up
+    pub fn merge(&mut self, other: &PacketParseStats) {
+        self.parse_time = self.parse_time.max(other.parse_time);
+        self.io_time += other.io_time;
+        self.decode_time += other.decode_time;
+        self.packet_count += other.packet_count;
+        self.total_bytes += other.total_bytes;
+        self.record_bytes += other.record_bytes;
+        self.udp_payload_bytes += other.udp_payload_bytes;
+        self.quote_bytes += other.quote_bytes;
+        self.quote_record_bytes += other.quote_record_bytes;
+
+        for rcv_time in [other.first_rcv_time, other.last_rcv_time]
+            .into_iter()
+            .flatten()
+        {
+            self.record_rcv_time(rcv_time);
+        }
+        for accept_time in [other.first_accept_time, other.last_accept_time]
+            .into_iter()
+            .flatten()
+        {
+            self.record_accept_time(accept_time);
+        }
+
+        self.successfully_parsed += other.successfully_parsed;
+        self.rejected += other.rejected;
+        self.failed += other.failed;
+
+        self.non_udp += other.non_udp;
+        self.wrong_port += other.wrong_port;
+        self.not_a_price_quote += other.not_a_price_quote;
+        self.malformed_quote += other.malformed_quote;
+        self.checksum_failures += other.checksum_failures;
+
+        for (reason, count) in &other.parse_failures {
+            *self.parse_failures.entry(reason.clone()).or_insert(0) += count;
+        }
+
+        self.decapsulated += other.decapsulated;
+        self.snaplen_truncated += other.snaplen_truncated;
+
+        let add_ports = |into: &mut PortCounts, from: &PortCounts| {
+            into.packets += from.packets;
+            into.quotes += from.quotes;
+            into.failed += from.failed;
+        };
+        for (port, counts) in &other.ports {
+            add_ports(self.ports.entry(*port).or_default(), counts);
+        }
+        add_ports(&mut self.other_ports, &other.other_ports);
+
+        self.volume_inconsistencies += other.volume_inconsistencies;
+        self.book_violations += other.book_violations;
+        self.one_sided_market += other.one_sided_market;
+        self.bad_timestamps += other.bad_timestamps;
+        self.other_countries += other.other_countries;
+        self.unknown_issue += other.unknown_issue;
+        self.filtered_by_script += other.filtered_by_script;
+        self.latency_spikes += other.latency_spikes;
+        for (depth, other_depth) in [
+            (&mut self.bid_depth, &other.bid_depth),
+            (&mut self.ask_depth, &other.ask_depth),
+        ] {
+            for (count, other_count) in depth.iter_mut().zip(other_depth) {
+                *count += other_count;
+            }
+        }
+
+        let add_codes = |into: &mut BTreeMap<u16, u64>, from: &BTreeMap<u16, u64>| {
+            for (code, count) in from {
+                *into.entry(*code).or_insert(0) += count;
+            }
+        };
+        add_codes(&mut self.unknown_market_types, &other.unknown_market_types);
+        add_codes(
+            &mut self.unknown_market_statuses,
+            &other.unknown_market_statuses,
+        );
+        add_codes(
+            &mut self.excluded_market_statuses,
+            &other.excluded_market_statuses,
+        );
+    }
+}
+
+// generates a getter for each plain counter
+macro_rules! counter_getters {
+    ($($counter:ident),* $(,)?) => {
+        $(
+            pub fn $counter(&self) -> u64 {
+                self.$counter
+            }
+        )*
+    };
+}
+
+impl PacketParseStats {
+    counter_getters!(
+        packet_count,
+        total_bytes,
+        record_bytes,
+        udp_payload_bytes,
+        quote_bytes,
+        quote_record_bytes,
+        successfully_parsed,
+        rejected,
+        failed,
+        non_udp,
+        wrong_port,
+        not_a_price_quote,
+        malformed_quote,
+        checksum_failures,
+        decapsulated,
+        snaplen_truncated,
+        volume_inconsistencies,
+        book_violations,
+        one_sided_market,
+        bad_timestamps,
+        other_countries,
+        unknown_issue,
+        filtered_by_script,
+        latency_spikes,
+    );
+
+    pub fn parse_time(&self) -> Duration {
+        self.parse_time
+    }
+
+    pub fn io_time(&self) -> Duration {
+        self.io_time
+    }
+
+    pub fn decode_time(&self) -> Duration {
+        self.decode_time
+    }
+
+    pub fn parse_failures(&self) -> &HashMap<String, u64> {
+        &self.parse_failures
+    }
+
+    pub fn ports(&self) -> &BTreeMap<u16, PortCounts> {
+        &self.ports
+    }
+
+    pub fn other_ports(&self) -> PortCounts {
+        self.other_ports
+    }
+
+    pub fn unknown_market_types(&self) -> &BTreeMap<u16, u64> {
+        &self.unknown_market_types
+    }
+
+    pub fn unknown_market_statuses(&self) -> &BTreeMap<u16, u64> {
+        &self.unknown_market_statuses
+    }
+
+    pub fn excluded_market_statuses(&self) -> &BTreeMap<u16, u64> {
+        &self.excluded_market_statuses
+    }
+
+    fn record_rcv_time(&mut self, rcv_time: Duration) {
+        self.first_rcv_time = Some(self.first_rcv_time.map_or(rcv_time, |t| t.min(rcv_time)));
+        self.last_rcv_time = Some(self.last_rcv_time.map_or(rcv_time, |t| t.max(rcv_time)));
+    }
+
+    fn record_depth(&mut self, price_quote: &PriceQuote) {
+        self.bid_depth[price_quote.populated_bid_levels()] += 1;
+        self.ask_depth[price_quote.populated_ask_levels()] += 1;
+    }
+
+    // unreadable accept times are left out
+    fn record_accept_time(&mut self, accept_time: u64) {
+        let Some(hundredths) = accept_hundredths(accept_time) else {
+            return;
+        };
+        let earlier = |t: u64| accept_hundredths(t).is_none_or(|h| hundredths < h);
+        if self.first_accept_time.is_none_or(earlier) {
+            self.first_accept_time = Some(accept_time);
+        }
+        let later = |t: u64| accept_hundredths(t).is_none_or(|h| hundredths > h);
+        if self.last_accept_time.is_none_or(later) {
+            self.last_accept_time = Some(accept_time);
+        }
+    }
+
+    // from the first packet captured to the last
+    pub fn capture_duration(&self) -> Option<Duration> {
+        Some(self.last_rcv_time? - self.first_rcv_time?)
+    }
+
+    // decoded quotes over the capture duration, unlike the parse throughput
+    // this is about the feed. None if the capture spans no time at all
+    pub fn quotes_per_capture_second(&self) -> Option<f64> {
+        let seconds = self.capture_duration()?.as_secs_f64();
+        (seconds > 0.0).then(|| self.successfully_parsed as f64 / seconds)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn serialize_epoch_nanos<S: serde::Serializer>(
+    time: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    time.map(|t| t.as_nanos() as u64).serialize(serializer)
+}
+
+#[cfg(feature = "serde")]
+fn serialize_accept_time<S: serde::Serializer>(
+    time: &Option<u64>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    time.map(fmt_accept_time).serialize(serializer)
+}
+
+impl Default for PacketParseStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 1023 B, 1.00 KiB, 1.00 MiB, 1.00 GiB
+fn fmt_byte_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", size, UNITS[unit])
+}
+
+// {:#} leaves out the parse timings, the one thing that differs between
+// runs over the same capture, and shows capture times in full
+impl std::fmt::Display for PacketParseStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let duration = self.parse_time.as_millis() as f64;
+        let total = self.packet_count as f64;
+        // every count is shown against the total packet count
+        let counter = |f: &mut std::fmt::Formatter<'_>, indent, label, count: u64| {
+            writeln!(
+                f,
+                "{:indent$}{}: {} ({:.2}%)",
+                "",
+                label,
+                count,
+                count as f64 / total * 100.0,
+                indent = indent
+            )
+        };
+
+        writeln!(f, "Packet Parse Stats:")?;
+        if !f.alternate() {
+            writeln!(f, "  Parse Time: {:.2}ms", duration)?;
+            writeln!(f, "    I/O: {:.2}ms", self.io_time.as_secs_f64() * 1000.0)?;
+            writeln!(
+                f,
+                "    Decode: {:.2}ms",
+                self.decode_time.as_secs_f64() * 1000.0
+            )?;
+        }
+        writeln!(f, "  Total Packets: {}", self.packet_count)?;
+        writeln!(f, "  Total Bytes: {}", self.total_bytes)?;
+        writeln!(
+            f,
+            "  Record Bytes: {} ({}), {:.1} per packet",
+            self.record_bytes,
+            fmt_byte_size(self.record_bytes),
+            self.record_bytes as f64 / self.packet_count.max(1) as f64
+        )?;
+        writeln!(
+            f,
+            "  UDP Payload Bytes: {} ({})",
+            self.udp_payload_bytes,
+            fmt_byte_size(self.udp_payload_bytes)
+        )?;
+        writeln!(
+            f,
+            "  Quote Bytes: {} ({})",
+            self.quote_bytes,
+            fmt_byte_size(self.quote_bytes)
+        )?;
+        // what it took to carry each quote: capture record, frame and udp
+        // headers against the message itself
+        let quotes = self.successfully_parsed.max(1) as f64;
+        let overhead = self.quote_record_bytes.saturating_sub(self.quote_bytes);
+        writeln!(
+            f,
+            "  Quote Overhead: {:.1} header bytes per {:.1} byte quote ({:.2}%)",
+            overhead as f64 / quotes,
+            self.quote_bytes as f64 / quotes,
+            overhead as f64 / self.quote_record_bytes.max(1) as f64 * 100.0
+        )?;
+        let or_na = |value: Option<String>| value.unwrap_or_else(|| "n/a".to_string());
+        let fmt_rcv_time = if f.alternate() {
+            fmt_rcv_time_iso
+        } else {
+            fmt_rcv_time
+        };
+        writeln!(
+            f,
+            "  Capture Start: {}",
+            or_na(self.first_rcv_time.map(fmt_rcv_time))
+        )?;
+        writeln!(
+            f,
+            "  Capture End: {}",
+            or_na(self.last_rcv_time.map(fmt_rcv_time))
+        )?;
+        writeln!(
+            f,
+            "  Capture Duration: {}",
+            or_na(
+                self.capture_duration()
+                    .map(|d| format!("{:.3}s", d.as_secs_f64()))
+            )
+        )?;
+        writeln!(
+            f,
+            "  First Accept Time: {}",
+            or_na(self.first_accept_time.map(fmt_accept_time))
+        )?;
+        writeln!(
+            f,
+            "  Last Accept Time: {}",
+            or_na(self.last_accept_time.map(fmt_accept_time))
+        )?;
+        writeln!(
+            f,
+            "  Quotes Per Capture Second: {}",
+            or_na(
+                self.quotes_per_capture_second()
+                    .map(|r| format!("{:.2}", r))
+            )
+        )?;
+        let port_counts = |f: &mut std::fmt::Formatter<'_>, label: &str, counts: &PortCounts| {
+            writeln!(
+                f,
+                "    {}: {} packets, {} quotes, {} failed",
+                label, counts.packets, counts.quotes, counts.failed
+            )
+        };
+        for (port, counts) in &self.ports {
+            port_counts(f, &format!("Port {}", port), counts)?;
+        }
+        if !self.ports.is_empty() || self.other_ports.packets > 0 {
+            port_counts(f, "Other Ports", &self.other_ports)?;
+        }
+        counter(f, 2, "Successfully Parsed", self.successfully_parsed)?;
+
+        // the reasons always add up to the rejected count
+        counter(f, 2, "Rejected", self.rejected)?;
+        counter(f, 4, "Non UDP", self.non_udp)?;
+        counter(f, 4, "Wrong Port", self.wrong_port)?;
+        counter(f, 4, "Not a Price Quote", self.not_a_price_quote)?;
+        counter(f, 4, "Malformed Quote", self.malformed_quote)?;
+        counter(f, 4, "Checksum Failures", self.checksum_failures)?;
+
+        counter(f, 2, "Failed", self.failed)?;
+        let mut parse_failures: Vec<(&String, &u64)> = self.parse_failures.iter().collect();
+        parse_failures.sort();
+        for (reason, count) in parse_failures {
+            counter(f, 4, reason, *count)?;
+        }
+
+        writeln!(f, "  Decapsulated: {}", self.decapsulated)?;
+        writeln!(f, "  Snaplen Truncated: {}", self.snaplen_truncated)?;
+        writeln!(
+            f,
+            "  Volume Inconsistencies: {}",
+            self.volume_inconsistencies
+        )?;
+        writeln!(f, "  Book Violations: {}", self.book_violations)?;
+        writeln!(f, "  One Sided Market: {}", self.one_sided_market)?;
+        writeln!(f, "  Bad Timestamps: {}", self.bad_timestamps)?;
+        writeln!(f, "  Other Countries: {}", self.other_countries)?;
+        writeln!(f, "  Unknown Issue: {}", self.unknown_issue)?;
+        writeln!(f, "  Filtered By Script: {}", self.filtered_by_script)?;
+        writeln!(f, "  Latency Spikes: {}", self.latency_spikes)?;
+        let fmt_depth = |depth: &[u64; LEVELS + 1]| {
+            let counts: Vec<String> = depth
+                .iter()
+                .enumerate()
+                .map(|(levels, count)| format!("{}:{}", levels, count))
+                .collect();
+            counts.join(" ")
+        };
+        writeln!(f, "  Bid Levels Populated: {}", fmt_depth(&self.bid_depth))?;
+        writeln!(f, "  Ask Levels Populated: {}", fmt_depth(&self.ask_depth))?;
+        for (code, count) in &self.unknown_market_types {
+            writeln!(f, "  Unknown Market Type {}: {}", code, count)?;
+        }
+        for (code, count) in &self.unknown_market_statuses {
+            writeln!(f, "  Unknown Market Status {}: {}", code, count)?;
+        }
+        for (code, count) in &self.excluded_market_statuses {
+            writeln!(
+                f,
+                "  Excluded Market Status {} ({}): {}",
+                code,
+                MarketStatus::from_code(*code),
+                count
+            )?;
+        }
+        if let Some(latency_report) = &self.latency_report {
+            write!(f, "{}", latency_report)?;
+        }
+        if let Some(rate_series) = &self.rate_series {
+            write!(f, "{}", rate_series)?;
+        }
+        if let Some(burst) = &self.worst_burst {
+            writeln!(f, "  Worst Burst: {}", burst)?;
+        }
+        if let Some(per_issue) = &self.per_issue {
+            write!(f, "\n{}", format_issue_stats(per_issue, self.price_format))?;
+        }
+        for violation in &self.violations {
+            writeln!(f, "  Threshold Violated: {}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+// works out whether a sliced packet is something the parser wants, handing
+// back the payload if so
+pub fn classify_packet<'a, P: PacketParser>(
+    parser: &P,
+    packet: &SlicedPacket<'a>,
+) -> Result<&'a [u8], PacketClassifyError> {
+    // skip if not udp
+    let udp = if let Some(TransportSlice::Udp(udp)) = &packet.transport {
+        udp
+    } else {
+        return Err(PacketClassifyError::NotUdp);
+    };
+
+    // skip if wrong port
+    let port = udp.destination_port();
+    if !parser.accepts_port(port) {
+        return Err(PacketClassifyError::WrongPort { port });
+    }
+
+    // skip if its not a price quote
+    if !parser.accepts_payload_prefix(packet.payload) {
+        return Err(PacketClassifyError::NotAPriceQuote { port });
+    }
+
+    // the captured payload can run past the udp length (ethernet padding)
+    // or stop short of it (snaplen)
+    let udp_len = (udp.length() as usize).saturating_sub(UdpHeader::SERIALIZED_SIZE);
+    let payload = &packet.payload[..packet.payload.len().min(udp_len)];
+    if payload.len() != parser.message_len() {
+        return Err(PacketClassifyError::MalformedQuote {
+            port,
+            len: payload.len(),
+            expected: parser.message_len(),
+        });
+    }
+
+    Ok(payload)
+}
+
+// ipv4 header checksum, and the udp checksum when the sender filled it in.
+// payload is what classify_packet handed back
+fn checksums_match(packet: &SlicedPacket, payload: &[u8]) -> bool {
+    let Some(TransportSlice::Udp(udp)) = &packet.transport else {
+        return true;
+    };
+    let udp_header = udp.to_header();
+    let expected = match &packet.ip {
+        Some(InternetSlice::Ipv4(header, _)) => {
+            let header = header.to_header();
+            if header.calc_header_checksum().ok() != Some(header.header_checksum) {
+                return false;
+            }
+            // zero means no checksum was computed, only allowed over ipv4
+            if udp_header.checksum == 0 {
+                return true;
+            }
+            udp_header.calc_checksum_ipv4(&header, payload)
+        }
+        Some(InternetSlice::Ipv6(header, _)) => {
+            udp_header.calc_checksum_ipv6(&header.to_header(), payload)
+        }
+        None => return true,
+    };
+    expected.ok() == Some(udp_header.checksum)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    Pcap,
+    PcapNg,
+    // back to back message payloads with no framing at all
+    Raw,
+}
+
+impl InputFormat {
+    pub const NAMES: [&'static str; 3] = ["pcap", "pcapng", "raw"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pcap" => Some(InputFormat::Pcap),
+            "pcapng" => Some(InputFormat::PcapNg),
+            "raw" => Some(InputFormat::Raw),
+            _ => None,
+        }
+    }
+
+    // guess from the first few bytes of the input. anything unrecognised is
+    // treated as pcap so the reader reports what's wrong with the header
+    pub fn detect<P: PacketParser>(parser: &P, head: &[u8]) -> Self {
+        const PCAPNG_MAGIC: [u8; 4] = [0x0a, 0x0d, 0x0d, 0x0a];
+        if head.starts_with(&PCAPNG_MAGIC) {
+            InputFormat::PcapNg
+        } else if parser.accepts_payload_prefix(head) {
+            InputFormat::Raw
+        } else {
+            InputFormat::Pcap
+        }
+    }
+}
+
+// how often --stats-interval prints an interim line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsInterval {
+    Every(Duration),
+    Packets(u64),
+}
+
+impl StatsInterval {
+    // a duration like parse_duration takes, or a number of millions of
+    // packets like 2M
+    pub fn parse(text: &str) -> Option<Self> {
+        let interval = match text.trim().strip_suffix('M') {
+            Some(millions) => {
+                StatsInterval::Packets(millions.parse::<u64>().ok()?.checked_mul(1_000_000)?)
+            }
+            None => StatsInterval::Every(analytics::parse_duration(text)?),
+        };
+        match interval {
+            StatsInterval::Every(every) if every.is_zero() => None,
+            StatsInterval::Packets(0) => None,
+            interval => Some(interval),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    // udp destination ports and payload prefixes quotes are looked for on
+    pub ports: Vec<u16>,
+    pub prefixes: Vec<Vec<u8>>,
+    // width of the issue code field, 12 for B6034
+    pub issue_code_len: usize,
+    // ascii digits for B6034, see NumericEncoding
+    pub numbers: NumericEncoding,
+    // drop quotes with an empty bid or ask side, counted as one_sided_market
+    pub filter_no_bid: bool,
+    pub filter_no_ask: bool,
+    // run validate_book on every quote, counting the ones that fail in
+    // book_violations. drop_bad_books also drops them
+    pub validate_book: bool,
+    pub drop_bad_books: bool,
+    // drop quotes captured outside this range, since epoch, counted in
+    // bad_timestamps. corrupt captures turn up times like 0 or 2106
+    pub rcv_time_range: Option<Range<Duration>>,
+    // drop quotes sent during these session states, counted per status in
+    // excluded_market_statuses
+    pub exclude_market_statuses: Vec<MarketStatus>,
+    // keep only quotes whose issue code is an isin from one of these
+    // countries, counted in other_countries. empty keeps everything
+    pub countries: Vec<String>,
+    // keep only quotes whose issue code is in this set, counted in
+    // unknown_issue. see read_issue_list
+    pub valid_issues: Option<HashSet<String>>,
+    // the first quote that fails to parse aborts the whole file instead of
+    // just being counted
+    pub strict_fail: bool,
+    // log every rejected packet to stderr
+    pub print_rejected: bool,
+    // reject quotes whose ip or udp checksum doesn't match, counted in
+    // checksum_failures
+    pub verify_checksums: bool,
+    // report the prefix actually seen on this many packets that arrived on
+    // the right port but weren't quotes
+    pub prefix_report_limit: usize,
+    // skips detection when set
+    pub input_format: Option<InputFormat>,
+    // write a jsonl line here for every quote, rejection, decode failure and
+    // anomalous market. see EventLogger
+    pub event_log: Option<String>,
+    // bytes dropped from the front of every captured frame before it's read
+    // as ethernet. an escape hatch for encapsulations etherparse doesn't
+    // know, like gre or erspan mirrored traffic
+    pub skip_bytes: usize,
+    // print a line of progress to stderr this often
+    pub stats_interval: Option<StatsInterval>,
+    // size of all the input together, if it's known, so stats_interval can
+    // say how far through it is
+    pub input_len: Option<u64>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        let parser = B6034Parser::default();
+        ParseOptions {
+            ports: parser.ports,
+            prefixes: parser.prefixes,
+            issue_code_len: parser.issue_code_len,
+            numbers: parser.numbers,
+            filter_no_bid: false,
+            filter_no_ask: false,
+            validate_book: false,
+            drop_bad_books: false,
+            rcv_time_range: None,
+            exclude_market_statuses: vec![],
+            countries: vec![],
+            valid_issues: None,
+            strict_fail: false,
+            print_rejected: false,
+            verify_checksums: false,
+            prefix_report_limit: 0,
+            input_format: None,
+            event_log: None,
+            skip_bytes: 0,
+            stats_interval: None,
+            input_len: None,
+        }
+    }
+}
+
+// issue codes one to a line, for ParseOptions::valid_issues. whitespace
+// around them is dropped and so are blank lines
+pub fn read_issue_list<R: BufRead>(reader: R) -> std::io::Result<HashSet<String>> {
+    let mut issues = HashSet::new();
+    for line in reader.lines() {
+        let line = line?;
+        let issue = line.trim();
+        if !issue.is_empty() {
+            issues.insert(issue.to_string());
+        }
+    }
+    Ok(issues)
+}
+
+#[cfg(feature = "cli")]
+pub fn parse_price_quotes_from_file(
+    path: &str,
+    options: &ParseOptions,
+) -> Result<(Vec<PriceQuote>, PacketParseStats), ParseFileError> {
+    parse_price_quotes_from_reader(open_capture(path)?, options)
+}
+
+// any pcap, pcapng or raw input, from a file, a socket or memory
+pub fn parse_price_quotes_from_reader<R: Read>(
+    reader: R,
+    options: &ParseOptions,
+) -> Result<(Vec<PriceQuote>, PacketParseStats), ParseFileError> {
+    let mut price_quotes = vec![];
+    let parse_stats = parse_price_quotes_into([reader], options, |price_quote| {
+        price_quotes.push(price_quote);
+        ControlFlow::Continue(())
+    })?;
+    Ok((price_quotes, parse_stats))
+}
+
+// a capture that's already in memory
+pub fn parse_price_quotes_from_bytes(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Result<(Vec<PriceQuote>, PacketParseStats), ParseFileError> {
+    parse_price_quotes_from_reader(Cursor::new(data), options)
+}
+
+// several captures read back to back as one, like a rotated capture set.
+// each may be in a different format
+pub fn concat_price_quotes_from_readers<R: Read>(
+    readers: Vec<R>,
+    options: &ParseOptions,
+) -> Result<(Vec<PriceQuote>, PacketParseStats), ParseFileError> {
+    let mut price_quotes = vec![];
+    let parse_stats = parse_price_quotes_into(readers, options, |price_quote| {
+        price_quotes.push(price_quote);
+        ControlFlow::Continue(())
+    })?;
+    Ok((price_quotes, parse_stats))
+}
+
+// hands each quote to f as soon as it's decoded instead of collecting them,
+// for when only running totals are needed. stops reading as soon as f
+// breaks, the stats then only cover what was read
+pub fn parse_price_quotes_with<R, F>(
+    reader: R,
+    options: &ParseOptions,
+    mut f: F,
+) -> Result<PacketParseStats, ParseFileError>
+where
+    R: Read,
+    F: FnMut(&PriceQuote) -> ControlFlow<()>,
+{
+    parse_price_quotes_into([reader], options, |price_quote| f(&price_quote))
+}
+
+pub fn concat_price_quotes_with<R, F>(
+    readers: Vec<R>,
+    options: &ParseOptions,
+    mut f: F,
+) -> Result<PacketParseStats, ParseFileError>
+where
+    R: Read,
+    F: FnMut(&PriceQuote) -> ControlFlow<()>,
+{
+    parse_price_quotes_into(readers, options, |price_quote| f(&price_quote))
+}
+
+// like concat_price_quotes_with, but f also gets the payload each quote was
+// decoded from, byte for byte
+pub fn concat_price_quotes_with_payload<R, F>(
+    readers: Vec<R>,
+    options: &ParseOptions,
+    mut f: F,
+) -> Result<PacketParseStats, ParseFileError>
+where
+    R: Read,
+    F: FnMut(PriceQuote, &[u8]) -> ControlFlow<()>,
+{
+    let parser = KeepPayload(quote_parser(options));
+    parse_packets(&parser, readers, options, |message, parse_stats| {
+        let kept = *message
+            .downcast::<WithPayload>()
+            .expect("KeepPayload only produces messages with payloads");
+        let price_quote = *kept
+            .message
+            .downcast::<PriceQuote>()
+            .expect("B6034Parser only produces price quotes");
+        if !screen_quote(options, parse_stats, &price_quote) {
+            return ControlFlow::Continue(());
+        }
+        f(price_quote, &kept.payload)
+    })
+}
+
+// like concat_price_quotes_with, but each quote comes with the udp
+// destination port it arrived on, None for raw input
+pub fn concat_price_quotes_with_port<R, F>(
+    readers: Vec<R>,
+    options: &ParseOptions,
+    mut f: F,
+) -> Result<PacketParseStats, ParseFileError>
+where
+    R: Read,
+    F: FnMut(PriceQuote, Option<u16>) -> ControlFlow<()>,
+{
+    let mut packet_loop = PacketLoop::new(quote_parser(options), options.clone());
+    packet_loop.keep_ports = true;
+    run_packet_loop(packet_loop, readers, |message, parse_stats| {
+        let tagged = *message
+            .downcast::<WithPort>()
+            .expect("keep_ports wraps every message");
+        let price_quote = *tagged
+            .message
+            .downcast::<PriceQuote>()
+            .expect("B6034Parser only produces price quotes");
+        if !screen_quote(options, parse_stats, &price_quote) {
+            return ControlFlow::Continue(());
+        }
+        f(price_quote, tagged.port)
+    })
+}
+
+// a capture path, or a glob for a rotated set like feed-*.pcap. matches are
+// sorted by name so zero padded sequence numbers come out in order, whatever
+// the shell would have done. a path that exists is taken as is, even if it
+// has glob characters in it
+#[cfg(feature = "cli")]
+pub fn expand_capture_paths(path: &str) -> Result<Vec<String>, ParseFileError> {
+    let is_glob = path.contains(['*', '?', '[']);
+    if !is_glob || Path::new(path).exists() {
+        return Ok(vec![path.to_string()]);
+    }
+
+    let matches = glob::glob(path).map_err(|err| ParseFileError::Glob {
+        pattern: path.to_string(),
+        reason: err.msg.to_string(),
+    })?;
+    let mut paths = vec![];
+    for entry in matches {
+        let entry = entry.map_err(|err| ParseFileError::Open {
+            path: err.path().display().to_string(),
+            source: err.into(),
+        })?;
+        paths.push(entry.display().to_string());
+    }
+    if paths.is_empty() {
+        return Err(ParseFileError::NoMatches {
+            pattern: path.to_string(),
+        });
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn parse_price_quotes_into<R, F>(
+    readers: impl IntoIterator<Item = R>,
+    options: &ParseOptions,
+    mut f: F,
+) -> Result<PacketParseStats, ParseFileError>
+where
+    R: Read,
+    F: FnMut(PriceQuote) -> ControlFlow<()>,
+{
+    let parser = quote_parser(options);
+    parse_packets(&parser, readers, options, |message, parse_stats| {
+        let price_quote = *message
+            .downcast::<PriceQuote>()
+            .expect("B6034Parser only produces price quotes");
+        if !screen_quote(options, parse_stats, &price_quote) {
+            return ControlFlow::Continue(());
+        }
+        f(price_quote)
+    })
+}
+
+// the built-in parser, set up the way options asks
+pub(crate) fn quote_parser(options: &ParseOptions) -> B6034Parser {
+    B6034Parser {
+        ports: options.ports.clone(),
+        prefixes: options.prefixes.clone(),
+        issue_code_len: options.issue_code_len,
+        numbers: options.numbers,
+    }
+}
+
+// tallies what's notable about a decoded quote and applies the quote level
+// filters. false if the quote should be dropped
+pub(crate) fn screen_quote(
+    options: &ParseOptions,
+    parse_stats: &mut PacketParseStats,
+    price_quote: &PriceQuote,
+) -> bool {
+    if !screen_market(
+        options,
+        parse_stats,
+        price_quote.packet_rcv_time,
+        price_quote.market(),
+        price_quote.market_status(),
+        &price_quote.issue_code,
+        price_quote.country_code(),
+    ) {
+        return false;
+    }
+
+    if options.validate_book && !price_quote.validate_book().is_empty() {
+        parse_stats.book_violations += 1;
+        if options.drop_bad_books {
+            return false;
+        }
+    }
+
+    if (options.filter_no_bid && !price_quote.has_bid())
+        || (options.filter_no_ask && !price_quote.has_ask())
+    {
+        parse_stats.one_sided_market += 1;
+        return false;
+    }
+
+    parse_stats.record_depth(price_quote);
+    true
+}
+
+// the part of screen_quote that only needs the header
+fn screen_market(
+    options: &ParseOptions,
+    parse_stats: &mut PacketParseStats,
+    rcv_time: Duration,
+    market: MarketType,
+    status: MarketStatus,
+    issue_code: &str,
+    country_code: Option<&str>,
+) -> bool {
+    if let Some(range) = &options.rcv_time_range {
+        if !range.contains(&rcv_time) {
+            parse_stats.bad_timestamps += 1;
+            return false;
+        }
+    }
+
+    if !market.is_known() {
+        *parse_stats
+            .unknown_market_types
+            .entry(market.code())
+            .or_insert(0) += 1;
+    }
+    if !status.is_known() {
+        *parse_stats
+            .unknown_market_statuses
+            .entry(status.code())
+            .or_insert(0) += 1;
+    }
+
+    if options.exclude_market_statuses.contains(&status) {
+        *parse_stats
+            .excluded_market_statuses
+            .entry(status.code())
+            .or_insert(0) += 1;
+        return false;
+    }
+
+    if !options.countries.is_empty()
+        && !country_code.is_some_and(|country| options.countries.iter().any(|c| c == country))
+    {
+        parse_stats.other_countries += 1;
+        return false;
+    }
+
+    if let Some(valid_issues) = &options.valid_issues {
+        if !valid_issues.contains(issue_code.trim()) {
+            parse_stats.unknown_issue += 1;
+            return false;
+        }
+    }
+
+    true
+}
+
+// like concat_price_quotes_with, but only each quote's header is decoded,
+// which is much cheaper when the book isn't needed. market status
+// exclusions, --country, --valid-issues and the capture time range still
+// apply, the one sided filters can't and are ignored.
+// nothing is written to the event log for quotes
+pub fn concat_quote_headers_with<R, F>(
+    readers: Vec<R>,
+    options: &ParseOptions,
+    mut f: F,
+) -> Result<PacketParseStats, ParseFileError>
+where
+    R: Read,
+    F: FnMut(QuoteHeader) -> ControlFlow<()>,
+{
+    let parser = B6034HeaderParser(quote_parser(options));
+    parse_packets(&parser, readers, options, |message, parse_stats| {
+        let header = *message
+            .downcast::<QuoteHeader>()
+            .expect("B6034HeaderParser only produces quote headers");
+        if !screen_market(
+            options,
+            parse_stats,
+            header.packet_rcv_time,
+            header.market(),
+            header.market_status(),
+            &header.issue_code,
+            header.country_code(),
+        ) {
+            return ControlFlow::Continue(());
+        }
+        f(header)
+    })
+}
+
+#[cfg(feature = "cli")]
+pub fn parse_packets_from_file<P: PacketParser>(
+    parser: &P,
+    path: &str,
+    options: &ParseOptions,
+) -> Result<(Vec<Box<dyn Any>>, PacketParseStats), ParseFileError> {
+    let file = open_capture(path)?;
+    let mut parsed = vec![];
+    let parse_stats = parse_packets(parser, [file], options, |message, _| {
+        parsed.push(message);
+        ControlFlow::Continue(())
+    })?;
+    Ok((parsed, parse_stats))
+}
+
+#[cfg(feature = "cli")]
+fn open_capture(path: &str) -> Result<File, ParseFileError> {
+    File::open(path).map_err(|source| ParseFileError::Open {
+        path: path.to_string(),
+        source,
+    })
+}
+
+// the packet loop, generic over what kind of message is being pulled out and
+// what's done with each one. the parser decides which packets it wants, the
+// ports and prefixes in options only configure the built-in B6034 parser.
+// several readers are read back to back as one capture
+fn parse_packets<P, R, H>(
+    parser: &P,
+    readers: impl IntoIterator<Item = R>,
+    options: &ParseOptions,
+    handle: H,
+) -> Result<PacketParseStats, ParseFileError>
+where
+    P: PacketParser,
+    R: Read,
+    H: FnMut(Box<dyn Any>, &mut PacketParseStats) -> ControlFlow<()>,
+{
+    run_packet_loop(PacketLoop::new(parser, options.clone()), readers, handle)
+}
+
+// parse_packets for a loop that's already set up
+fn run_packet_loop<P, R, H>(
+    mut packet_loop: PacketLoop<P>,
+    readers: impl IntoIterator<Item = R>,
+    mut handle: H,
+) -> Result<PacketParseStats, ParseFileError>
+where
+    P: PacketParser,
+    R: Read,
+    H: FnMut(Box<dyn Any>, &mut PacketParseStats) -> ControlFlow<()>,
+{
+    let start = Stopwatch::start();
+    packet_loop.open_event_log()?;
+    for reader in readers {
+        if read_capture(&mut packet_loop, reader, &mut handle)?.is_break() {
+            break;
+        }
+    }
+    packet_loop.close_event_log()?;
+
+    let mut parse_stats = packet_loop.parse_stats;
+    parse_stats.parse_time = start.elapsed();
+    Ok(parse_stats)
+}
+
+// what each kind of capture record adds to the packet it holds. pcapng
+// blocks also pad the packet to 4 bytes
+pub(crate) const PCAP_RECORD_HEADER_LEN: u64 = 16;
+const EPB_HEADER_LEN: u64 = 32;
+const SPB_HEADER_LEN: u64 = 16;
+
+// one capture through the loop. breaks as soon as handle does
+pub(crate) fn read_capture<P, R, H>(
+    packet_loop: &mut PacketLoop<P>,
+    reader: R,
+    handle: &mut H,
+) -> Result<ControlFlow<()>, ParseFileError>
+where
+    P: PacketParser,
+    R: Read,
+    H: FnMut(Box<dyn Any>, &mut PacketParseStats) -> ControlFlow<()>,
+{
+    let flow = read_packets(packet_loop, reader, handle);
+    packet_loop.note_io_time();
+    flow
+}
+
+fn read_packets<P, R, H>(
+    packet_loop: &mut PacketLoop<P>,
+    reader: R,
+    handle: &mut H,
+) -> Result<ControlFlow<()>, ParseFileError>
+where
+    P: PacketParser,
+    R: Read,
+    H: FnMut(Box<dyn Any>, &mut PacketParseStats) -> ControlFlow<()>,
+{
+    let mut input = BufReader::new(packet_loop.counting(reader));
+    let read_error = |source| ParseFileError::Read { source };
+    let format = match packet_loop.options.input_format {
+        Some(format) => format,
+        None => {
+            let head = input.fill_buf().map_err(read_error)?;
+            InputFormat::detect(&packet_loop.parser, head)
+        }
+    };
+
+    let mut deliver = |packet_loop: &mut PacketLoop<P>, message: Option<Box<dyn Any>>| match message
+    {
+        Some(message) => handle(message, &mut packet_loop.parse_stats),
+        None => ControlFlow::Continue(()),
+    };
+    match format {
+        InputFormat::Pcap => {
+            let mut reader = PcapReader::new(input).map_err(ParseFileError::Header)?;
+            while let Some(pcap_packet) = reader.next_packet() {
+                let pcap_packet = pcap_packet.map_err(|source| ParseFileError::Record {
+                    packet_index: packet_loop.parse_stats.packet_count + 1,
+                    source,
+                })?;
+                let message = packet_loop.handle_frame(
+                    pcap_packet.timestamp,
+                    &pcap_packet.data,
+                    pcap_packet.orig_len,
+                    PCAP_RECORD_HEADER_LEN + pcap_packet.data.len() as u64,
+                )?;
+                if deliver(packet_loop, message).is_break() {
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+        }
+        InputFormat::PcapNg => {
+            let mut reader = PcapNgReader::new(input).map_err(ParseFileError::Header)?;
+            while let Some(block) = reader.next_block() {
+                let block = match block {
+                    Ok(block) => block,
+                    Err(source) => {
+                        return Err(ParseFileError::Record {
+                            packet_index: packet_loop.parse_stats.packet_count + 1,
+                            source,
+                        })
+                    }
+                };
+                // only packet blocks count, the rest is interface and section
+                // bookkeeping
+                let message = match block {
+                    Block::EnhancedPacket(packet) => packet_loop.handle_frame(
+                        packet.timestamp,
+                        &packet.data,
+                        packet.original_len,
+                        EPB_HEADER_LEN + (packet.data.len() as u64).next_multiple_of(4),
+                    )?,
+                    // simple packets carry no timestamp
+                    Block::SimplePacket(packet) => packet_loop.handle_frame(
+                        Duration::ZERO,
+                        &packet.data,
+                        packet.original_len,
+                        SPB_HEADER_LEN + (packet.data.len() as u64).next_multiple_of(4),
+                    )?,
+                    _ => continue,
+                };
+                if deliver(packet_loop, message).is_break() {
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+        }
+        InputFormat::Raw => {
+            let mut data = vec![];
+            input.read_to_end(&mut data).map_err(read_error)?;
+            for payload in data.chunks(packet_loop.parser.message_len()) {
+                let message = packet_loop.handle_raw(payload)?;
+                if deliver(packet_loop, message).is_break() {
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+        }
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+// adds every byte read to count, for --stats-interval's idea of how far
+// through the input the loop is, and how long the reads took to nanos, for
+// io_time. reads come a whole buffer at a time, so that's a clock read
+// either side of a buffer rather than of every packet
+pub(crate) struct CountingReader<R> {
+    inner: R,
+    count: Arc<AtomicU64>,
+    nanos: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let stopwatch = Stopwatch::start();
+        let read = self.inner.read(buf);
+        let nanos = stopwatch.elapsed().as_nanos() as u64;
+        self.nanos.fetch_add(nanos, Ordering::Relaxed);
+        let n = read?;
+        self.count.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+// --stats-interval, and where things stood at the last line
+struct Heartbeat {
+    interval: StatsInterval,
+    started: Stopwatch,
+    last_elapsed: Duration,
+    last_packets: u64,
+}
+
+impl Heartbeat {
+    fn new(interval: StatsInterval) -> Self {
+        Heartbeat {
+            interval,
+            started: Stopwatch::start(),
+            last_elapsed: Duration::ZERO,
+            last_packets: 0,
+        }
+    }
+
+    // the line to print after this many packets, if one is due
+    fn tick(
+        &mut self,
+        parse_stats: &PacketParseStats,
+        bytes_read: u64,
+        input_len: Option<u64>,
+    ) -> Option<String> {
+        let packets = parse_stats.packet_count;
+        let due = match self.interval {
+            StatsInterval::Packets(n) => packets.is_multiple_of(n),
+            StatsInterval::Every(every) => {
+                packets.is_multiple_of(CLOCK_EVERY)
+                    && self.started.elapsed() - self.last_elapsed >= every
+            }
+        };
+        if !due {
+            return None;
+        }
+
+        let elapsed = self.started.elapsed();
+        let since = (elapsed - self.last_elapsed).as_secs_f64();
+        let rate = if since > 0.0 {
+            (packets - self.last_packets) as f64 / since
+        } else {
+            0.0
+        };
+        let mut line = format!(
+            "interim: {:.1}s, {} packets, {} quotes, {:.0} packets/s",
+            elapsed.as_secs_f64(),
+            packets,
+            parse_stats.successfully_parsed,
+            rate
+        );
+        if let Some(input_len) = input_len.filter(|&len| len > 0 && bytes_read > 0) {
+            // read ahead can put the offset a little past the end
+            let done = (bytes_read as f64 / input_len as f64).min(1.0);
+            let left = elapsed.as_secs_f64() * (1.0 - done) / done;
+            line.push_str(&format!(
+                ", {:.1}% read, about {:.0}s left",
+                done * 100.0,
+                left
+            ));
+        }
+        self.last_elapsed = elapsed;
+        self.last_packets = packets;
+        Some(line)
+    }
+}
+
+// times parse_time. std's Instant panics on wasm32-unknown-unknown, there
+// parse_time just stays zero
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Stopwatch {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    start: std::time::Instant,
+}
+
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Stopwatch {
+            #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        return self.start.elapsed();
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        return Duration::ZERO;
+    }
+}
+
+// packets between clock reads, for the heartbeat and decode_time. reading it
+// every packet would cost more than the numbers are worth
+const CLOCK_EVERY: u64 = 1024;
+
+// the packets PacketLoop did decode against the clock, for the ones it
+// didn't
+#[derive(Debug, Default)]
+struct ClockSample {
+    total: Duration,
+    packets: u32,
+}
+
+impl ClockSample {
+    // passes elapsed back, for adding to the real total
+    fn add(&mut self, elapsed: Duration) -> Duration {
+        self.total += elapsed;
+        self.packets += 1;
+        elapsed
+    }
+
+    fn average(&self) -> Duration {
+        self.total / self.packets.max(1)
+    }
+}
+
+// classifies and decodes one packet at a time, whatever the input format,
+// keeping count as it goes. every entry point shares this so their stats
+// agree
+pub(crate) struct PacketLoop<P> {
+    pub(crate) parser: P,
+    pub(crate) options: ParseOptions,
+    pub(crate) parse_stats: PacketParseStats,
+    // off for a quick pass that only classifies. accepted payloads are then
+    // counted in candidates and never decoded
+    pub(crate) decode: bool,
+    pub(crate) candidates: u64,
+    // hand back every decoded message wrapped in a WithPort
+    pub(crate) keep_ports: bool,
+    event_log: Option<EventLogger>,
+    prefixes_reported: usize,
+    heartbeat: Option<Heartbeat>,
+    // what decoding the timed packets took. the packets in between are
+    // charged the average
+    decode_sample: ClockSample,
+    // bytes read off the input, over every capture so far, and how long
+    // reading them took
+    bytes_read: Arc<AtomicU64>,
+    read_nanos: Arc<AtomicU64>,
+}
+
+// stands in for EventLogger without the serde feature. it can't be built,
+// open_event_log refuses without the cli feature anyway
+#[cfg(not(feature = "serde"))]
+enum EventLogger {}
+
+#[cfg(not(feature = "serde"))]
+impl EventLogger {
+    fn quote(&mut self, _: Duration, _: u64, _: &PriceQuote) -> std::io::Result<()> {
+        match *self {}
+    }
+
+    fn rejected(&mut self, _: Duration, _: u64, _: &PacketClassifyError) -> std::io::Result<()> {
+        match *self {}
+    }
+
+    fn decode_failed(
+        &mut self,
+        _: Duration,
+        _: u64,
+        _: &crate::error::QuoteDecodeError,
+    ) -> std::io::Result<()> {
+        match *self {}
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match *self {}
+    }
+}
+
+impl<P: PacketParser> PacketLoop<P> {
+    pub(crate) fn new(parser: P, options: ParseOptions) -> Self {
+        let mut parse_stats = PacketParseStats::new();
+        // listed up front so a port nothing arrives on still shows
+        for port in &options.ports {
+            if parser.accepts_port(*port) {
+                parse_stats.ports.insert(*port, PortCounts::default());
+            }
+        }
+        PacketLoop {
+            parser,
+            parse_stats,
+            decode: true,
+            candidates: 0,
+            keep_ports: false,
+            event_log: None,
+            prefixes_reported: 0,
+            heartbeat: options.stats_interval.map(Heartbeat::new),
+            decode_sample: ClockSample::default(),
+            bytes_read: Arc::new(AtomicU64::new(0)),
+            read_nanos: Arc::new(AtomicU64::new(0)),
+            options,
+        }
+    }
+
+    // starts the event log options asks for, if any
+    pub(crate) fn open_event_log(&mut self) -> Result<(), ParseFileError> {
+        if let Some(path) = &self.options.event_log {
+            #[cfg(feature = "cli")]
+            let event_log = EventLogger::create(path);
+            #[cfg(not(feature = "cli"))]
+            let event_log = Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "event logs are files, which need the cli feature",
+            ));
+            let event_log = event_log.map_err(|source| ParseFileError::Open {
+                path: path.clone(),
+                source,
+            })?;
+            self.event_log = Some(event_log);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn close_event_log(&mut self) -> Result<(), ParseFileError> {
+        match &mut self.event_log {
+            Some(event_log) => event_log
+                .flush()
+                .map_err(|source| ParseFileError::EventLog { source }),
+            None => Ok(()),
+        }
+    }
+
+    fn port_counts(&mut self, port: u16) -> &mut PortCounts {
+        if self.parser.accepts_port(port) {
+            self.parse_stats.ports.entry(port).or_default()
+        } else {
+            &mut self.parse_stats.other_ports
+        }
+    }
+
+    fn log_rejected(
+        &mut self,
+        timestamp: Duration,
+        reason: &PacketClassifyError,
+    ) -> Result<(), ParseFileError> {
+        match &mut self.event_log {
+            Some(event_log) => event_log
+                .rejected(timestamp, self.parse_stats.packet_count, reason)
+                .map_err(|source| ParseFileError::EventLog { source }),
+            None => Ok(()),
+        }
+    }
+
+    // reader, counted into bytes_read and read_nanos
+    pub(crate) fn counting<R>(&self, reader: R) -> CountingReader<R> {
+        CountingReader {
+            inner: reader,
+            count: self.bytes_read.clone(),
+            nanos: self.read_nanos.clone(),
+        }
+    }
+
+    // brings io_time up to every read so far
+    pub(crate) fn note_io_time(&mut self) {
+        self.parse_stats.io_time = Duration::from_nanos(self.read_nanos.load(Ordering::Relaxed));
+    }
+
+    // the decode for the next packet, with its time added to decode_time.
+    // the first CLOCK_EVERY are all timed, so small captures come out exact,
+    // then every CLOCK_EVERY-th
+    fn decoding<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let packets = self.parse_stats.packet_count;
+        if packets >= CLOCK_EVERY && !packets.is_multiple_of(CLOCK_EVERY) {
+            self.parse_stats.decode_time += self.decode_sample.average();
+            return f(self);
+        }
+        let stopwatch = Stopwatch::start();
+        let result = f(self);
+        self.parse_stats.decode_time += self.decode_sample.add(stopwatch.elapsed());
+        result
+    }
+
+    // an ethernet frame from a capture, orig_len long before the snaplen got
+    // to it. None if it was rejected or failed to decode, the stats say which
+    pub(crate) fn handle_frame(
+        &mut self,
+        timestamp: Duration,
+        frame: &[u8],
+        orig_len: u32,
+        record_len: u64,
+    ) -> Result<Option<Box<dyn Any>>, ParseFileError> {
+        let message = self.decoding(|packet_loop| {
+            packet_loop.decode_frame(timestamp, frame, orig_len, record_len)
+        });
+        self.heartbeat();
+        message
+    }
+
+    fn heartbeat(&mut self) {
+        if let Some(heartbeat) = &mut self.heartbeat {
+            let bytes_read = self.bytes_read.load(Ordering::Relaxed);
+            if let Some(line) =
+                heartbeat.tick(&self.parse_stats, bytes_read, self.options.input_len)
+            {
+                eprintln!("{}", line);
+            }
+        }
+    }
+
+    fn decode_frame(
+        &mut self,
+        timestamp: Duration,
+        frame: &[u8],
+        orig_len: u32,
+        record_len: u64,
+    ) -> Result<Option<Box<dyn Any>>, ParseFileError> {
+        let captured_len = frame.len();
+        self.parse_stats.packet_count += 1;
+        self.parse_stats.total_bytes += frame.len() as u64;
+        self.parse_stats.record_bytes += record_len;
+        self.parse_stats.record_rcv_time(timestamp);
+        let skip_bytes = self.options.skip_bytes;
+        let Some(frame) = frame.get(skip_bytes..) else {
+            let err = PacketClassifyError::Malformed(format!(
+                "{} bytes is shorter than --skip-bytes {}",
+                frame.len(),
+                skip_bytes
+            ));
+            eprintln!("packet {}: {}", self.parse_stats.packet_count, err);
+            self.log_rejected(timestamp, &err)?;
+            return Ok(None);
+        };
+        let frame = match decap::decapsulate(frame) {
+            Some(inner) => {
+                self.parse_stats.decapsulated += 1;
+                inner
+            }
+            None => frame,
+        };
+        let parsed_packet = match SlicedPacket::from_ethernet(frame) {
+            Ok(packet) => packet,
+            Err(err) => {
+                let err = PacketClassifyError::Malformed(format!("{:?}", err));
+                eprintln!("packet {}: {}", self.parse_stats.packet_count, err);
+                self.log_rejected(timestamp, &err)?;
+                return Ok(None);
+            }
+        };
+
+        let port = match &parsed_packet.transport {
+            Some(TransportSlice::Udp(udp)) => Some(udp.destination_port()),
+            _ => None,
+        };
+        if let Some(port) = port {
+            self.port_counts(port).packets += 1;
+            self.parse_stats.udp_payload_bytes += parsed_packet.payload.len() as u64;
+            if (captured_len as u64) < orig_len as u64 {
+                self.note_truncated(port, captured_len, orig_len);
+            }
+        }
+
+        let classified = match classify_packet(&self.parser, &parsed_packet) {
+            // only udp gets this far, so there's always a port
+            Ok(payload)
+                if self.options.verify_checksums && !checksums_match(&parsed_packet, payload) =>
+            {
+                Err(PacketClassifyError::BadChecksum {
+                    port: port.unwrap_or_default(),
+                })
+            }
+            classified => classified,
+        };
+        let payload = match classified {
+            Ok(payload) => payload,
+            Err(err) => {
+                match err {
+                    PacketClassifyError::NotUdp => self.parse_stats.non_udp += 1,
+                    PacketClassifyError::WrongPort { .. } => self.parse_stats.wrong_port += 1,
+                    PacketClassifyError::NotAPriceQuote { .. } => {
+                        self.parse_stats.not_a_price_quote += 1
+                    }
+                    PacketClassifyError::MalformedQuote { .. } => {
+                        self.parse_stats.malformed_quote += 1
+                    }
+                    PacketClassifyError::BadChecksum { .. } => {
+                        self.parse_stats.checksum_failures += 1
+                    }
+                    PacketClassifyError::Malformed(_) => {}
+                }
+                self.parse_stats.rejected += 1;
+                self.log_rejected(timestamp, &err)?;
+                if self.options.print_rejected {
+                    print_rejected_packet(timestamp, &err, &parsed_packet);
+                }
+                if let PacketClassifyError::NotAPriceQuote { port } = err {
+                    if self.prefixes_reported < self.options.prefix_report_limit {
+                        self.prefixes_reported += 1;
+                        let (ascii, hex) = describe_prefix(parsed_packet.payload, 8);
+                        eprintln!(
+                            "packet {}: port {} payload doesn't start with the expected prefix, saw \"{}\" ({})",
+                            self.parse_stats.packet_count, port, ascii, hex
+                        );
+                    }
+                }
+                return Ok(None);
+            }
+        };
+
+        self.handle_payload(timestamp, payload, port, record_len)
+    }
+
+    // a frame shorter than it was on the wire. if it was headed for a quote
+    // port, whatever goes wrong with it next is the snaplen's fault
+    fn note_truncated(&mut self, port: u16, captured_len: usize, orig_len: u32) {
+        if !self.parser.accepts_port(port) {
+            return;
+        }
+        self.parse_stats.snaplen_truncated += 1;
+        if self.parse_stats.snaplen_truncated == 1 {
+            eprintln!(
+                "packet {}: only {} of its {} bytes were captured. the capture's snaplen is too small for quotes, the rest cut short are only counted",
+                self.parse_stats.packet_count, captured_len, orig_len
+            );
+        }
+    }
+
+    // one message cut from raw input. there's no capture, so no receive time
+    pub(crate) fn handle_raw(
+        &mut self,
+        payload: &[u8],
+    ) -> Result<Option<Box<dyn Any>>, ParseFileError> {
+        let message = self.decoding(|packet_loop| packet_loop.decode_raw(payload));
+        self.heartbeat();
+        message
+    }
+
+    fn decode_raw(&mut self, payload: &[u8]) -> Result<Option<Box<dyn Any>>, ParseFileError> {
+        self.parse_stats.packet_count += 1;
+        self.parse_stats.total_bytes += payload.len() as u64;
+        self.parse_stats.record_bytes += payload.len() as u64;
+        self.parse_stats.udp_payload_bytes += payload.len() as u64;
+        // raw input has no udp header, so no port either
+        if !self.parser.accepts_payload_prefix(payload) {
+            self.parse_stats.not_a_price_quote += 1;
+            self.parse_stats.rejected += 1;
+            self.log_rejected(
+                Duration::ZERO,
+                &PacketClassifyError::NotAPriceQuote { port: 0 },
+            )?;
+            return Ok(None);
+        }
+        if payload.len() != self.parser.message_len() {
+            self.parse_stats.malformed_quote += 1;
+            self.parse_stats.rejected += 1;
+            let reason = PacketClassifyError::MalformedQuote {
+                port: 0,
+                len: payload.len(),
+                expected: self.parser.message_len(),
+            };
+            self.log_rejected(Duration::ZERO, &reason)?;
+            return Ok(None);
+        }
+        self.handle_payload(Duration::ZERO, payload, None, payload.len() as u64)
+    }
+
+    // a message payload, already known to be one the parser wants. port is
+    // None for raw input. record_len is the whole capture record it came in
+    fn handle_payload(
+        &mut self,
+        timestamp: Duration,
+        payload: &[u8],
+        port: Option<u16>,
+        record_len: u64,
+    ) -> Result<Option<Box<dyn Any>>, ParseFileError> {
+        if !self.decode {
+            self.candidates += 1;
+            return Ok(None);
+        }
+        match self.parser.parse(timestamp, payload) {
+            Ok(message) => {
+                self.parse_stats.successfully_parsed += 1;
+                self.parse_stats.quote_bytes += payload.len() as u64;
+                self.parse_stats.quote_record_bytes += record_len;
+                if let Some(port) = port {
+                    self.port_counts(port).quotes += 1;
+                }
+                let packet_index = self.parse_stats.packet_count;
+                let quote = message.downcast_ref::<PriceQuote>().or_else(|| {
+                    message
+                        .downcast_ref::<WithPayload>()
+                        .and_then(|kept| kept.message.downcast_ref::<PriceQuote>())
+                });
+                let accept_time = quote.map(|q| q.quote_accept_time).or_else(|| {
+                    message
+                        .downcast_ref::<QuoteHeader>()
+                        .map(|h| h.quote_accept_time)
+                });
+                if let Some(accept_time) = accept_time {
+                    self.parse_stats.record_accept_time(accept_time);
+                }
+                if let (Some(event_log), Some(quote)) = (&mut self.event_log, quote) {
+                    event_log
+                        .quote(timestamp, packet_index, quote)
+                        .map_err(|source| ParseFileError::EventLog { source })?;
+                }
+                if self.keep_ports {
+                    return Ok(Some(Box::new(WithPort { message, port })));
+                }
+                Ok(Some(message))
+            }
+            Err(source) => {
+                if let Some(event_log) = &mut self.event_log {
+                    event_log
+                        .decode_failed(timestamp, self.parse_stats.packet_count, &source)
+                        .map_err(|source| ParseFileError::EventLog { source })?;
+                }
+                if self.options.strict_fail {
+                    return Err(ParseFileError::Decode {
+                        packet_index: self.parse_stats.packet_count,
+                        source,
+                    });
+                }
+                self.parse_stats.failed += 1;
+                if let Some(port) = port {
+                    self.port_counts(port).failed += 1;
+                }
+                let reason = format!("{} ({})", source.field(), source.kind());
+                *self.parse_stats.parse_failures.entry(reason).or_insert(0) += 1;
+                Ok(None)
+            }
+        }
+    }
+}
+
+// warns on stderr for every quote whose totals disagree with its levels,
+// returns how many did
+pub fn validate_volumes(price_quotes: &[PriceQuote]) -> u64 {
+    let mut inconsistencies = 0;
+    for price_quote in price_quotes {
+        if price_quote.volumes_consistent() {
+            continue;
+        }
+        inconsistencies += 1;
+        eprintln!(
+            "volume mismatch for {} seq {}: bid total {} vs levels {}, ask total {} vs levels {}",
+            price_quote.issue_code,
+            price_quote.issue_seq_no,
+            price_quote.total_bid_quote_volume,
+            price_quote.bid_level_volume(),
+            price_quote.total_ask_quote_volume,
+            price_quote.ask_level_volume()
+        );
+    }
+    inconsistencies
+}
+
+// drops quotes with an empty bid and/or ask side, returns how many went
+pub fn filter_one_sided(price_quotes: &mut Vec<PriceQuote>, no_bid: bool, no_ask: bool) -> u64 {
+    let before = price_quotes.len();
+    price_quotes.retain(|q| (q.has_bid() || !no_bid) && (q.has_ask() || !no_ask));
+    (before - price_quotes.len()) as u64
+}
+
+// the first len bytes of a payload as printable ascii (anything else shown
+// as '.') and as hex
+pub fn describe_prefix(payload: &[u8], len: usize) -> (String, String) {
+    let prefix = &payload[..payload.len().min(len)];
+    let ascii = prefix
+        .iter()
+        .map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        })
+        .collect();
+    let hex = prefix.iter().map(|b| format!("{:02x}", b)).collect();
+    (ascii, hex)
+}
+
+// index of the first quote whose successor has an earlier accept time
+#[cfg(feature = "chrono")]
+pub fn find_accept_time_violation(price_quotes: &[PriceQuote]) -> Option<usize> {
+    price_quotes
+        .windows(2)
+        .position(|pair| pair[0].accept_time() > pair[1].accept_time())
+}
+
+// accept times forced to never go backwards, in arrival order. a quote that
+// arrives with an earlier time than one already seen is pulled forward to
+// that time. quotes with an unreadable time get None and don't move the clock
+#[cfg(feature = "chrono")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClampedTimes {
+    pub times: Vec<Option<NaiveTime>>,
+    // quotes that had to be pulled forward
+    pub clamped: u64,
+    // the furthest any one quote was pulled forward
+    pub max_lag: chrono::Duration,
+}
+
+#[cfg(feature = "chrono")]
+pub fn clamp_accept_times(price_quotes: &[PriceQuote]) -> ClampedTimes {
+    let mut latest: Option<NaiveTime> = None;
+    let mut clamped = 0;
+    let mut max_lag = chrono::Duration::zero();
+    let times = price_quotes
+        .iter()
+        .map(|q| {
+            let time = q.accept_time()?;
+            match latest {
+                Some(latest) if time < latest => {
+                    clamped += 1;
+                    max_lag = max_lag.max(latest - time);
+                    Some(latest)
+                }
+                _ => {
+                    latest = Some(time);
+                    Some(time)
+                }
+            }
+        })
+        .collect();
+    ClampedTimes {
+        times,
+        clamped,
+        max_lag,
+    }
+}
+
+// one line per rejected packet: time, reason, addresses, and a peek at the
+// start of the payload
+fn print_rejected_packet(timestamp: Duration, reason: &PacketClassifyError, packet: &SlicedPacket) {
+    let (source, destination) = match &packet.ip {
+        Some(InternetSlice::Ipv4(header, _)) => (
+            header.source_addr().to_string(),
+            header.destination_addr().to_string(),
+        ),
+        Some(InternetSlice::Ipv6(header, _)) => (
+            header.source_addr().to_string(),
+            header.destination_addr().to_string(),
+        ),
+        None => ("-".to_string(), "-".to_string()),
+    };
+    let port = match reason {
+        PacketClassifyError::WrongPort { port }
+        | PacketClassifyError::NotAPriceQuote { port }
+        | PacketClassifyError::MalformedQuote { port, .. }
+        | PacketClassifyError::BadChecksum { port } => port.to_string(),
+        _ => "-".to_string(),
+    };
+    let preview: String = packet
+        .payload
+        .iter()
+        .take(16)
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    eprintln!(
+        "rejected {}.{:06} {}: {} -> {}:{} {}",
+        timestamp.as_secs(),
+        timestamp.subsec_micros(),
+        reason,
+        source,
+        destination,
+        port,
+        preview
+    );
+}
@@ -0,0 +1,196 @@
+use std::fmt;
+
+use rust_decimal::Decimal;
+#[cfg(feature = "json-schema")]
+use schemars::JsonSchema;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// how prices are turned into numbers and text. the feed sends prices as
+// plain integers, scale is how many of their digits are decimal places
+// (--price-scale). precision is how many decimal places get printed,
+// rounding half up when it's fewer than the scale and padding with zeros
+// when it's more. None follows the scale (--price-precision)
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceFormat {
+    pub scale: u32,
+    pub precision: Option<u32>,
+}
+
+impl PriceFormat {
+    pub fn new(scale: u32, precision: Option<u32>) -> Self {
+        PriceFormat { scale, precision }
+    }
+
+    pub fn precision(&self) -> u32 {
+        self.precision.unwrap_or(self.scale)
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+pub struct Price(pub u64);
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+pub struct Qty(pub u64);
+
+impl Price {
+    pub fn raw(self) -> u64 {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    // distance between two prices, regardless of which is larger
+    pub fn spread(self, other: Price) -> Price {
+        Price(self.0.abs_diff(other.0))
+    }
+
+    // midpoint of two prices, rounded down. never overflows
+    pub fn mid(self, other: Price) -> Price {
+        Price(self.0 / 2 + other.0 / 2 + (self.0 % 2 + other.0 % 2) / 2)
+    }
+
+    pub fn checked_sub(self, other: Price) -> Option<Price> {
+        self.0.checked_sub(other.0).map(Price)
+    }
+
+    pub fn to_f64(self, scale: u32) -> f64 {
+        self.0 as f64 / 10f64.powi(scale as i32)
+    }
+
+    pub fn to_decimal(self, scale: u32) -> Decimal {
+        Decimal::from_i128_with_scale(self.0 as i128, scale)
+    }
+
+    // the price as format says to print it. Display is the raw integer
+    pub fn display(self, format: PriceFormat) -> ScaledPrice {
+        ScaledPrice {
+            price: self,
+            format,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScaledPrice {
+    price: Price,
+    format: PriceFormat,
+}
+
+impl Qty {
+    pub fn raw(self) -> u64 {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn saturating_add(self, other: Qty) -> Qty {
+        Qty(self.0.saturating_add(other.0))
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64
+    }
+
+    pub fn to_decimal(self) -> Decimal {
+        Decimal::from(self.0)
+    }
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Display for ScaledPrice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scale = self.format.scale;
+        let precision = self.format.precision();
+        // u128 so rounding and padding can't overflow
+        let mut value = self.price.0 as u128;
+        if precision < scale {
+            let dropped = 10u128.pow(scale - precision);
+            value = (value + dropped / 2) / dropped;
+        } else {
+            value *= 10u128.pow(precision - scale);
+        }
+        if precision == 0 {
+            return write!(f, "{}", value);
+        }
+        let divisor = 10u128.pow(precision);
+        write!(
+            f,
+            "{}.{:0width$}",
+            value / divisor,
+            value % divisor,
+            width = precision as usize
+        )
+    }
+}
+
+impl fmt::Display for Qty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<u64> for Price {
+    fn from(value: u64) -> Self {
+        Price(value)
+    }
+}
+
+impl From<Price> for u64 {
+    fn from(value: Price) -> Self {
+        value.0
+    }
+}
+
+// the unscaled wire integer as a float, 535 for 5.35 with two implied
+// decimals. to_f64 and to_decimal take a scale
+impl From<Price> for f64 {
+    fn from(value: Price) -> Self {
+        value.to_f64(0)
+    }
+}
+
+impl From<Price> for Decimal {
+    fn from(value: Price) -> Self {
+        value.to_decimal(0)
+    }
+}
+
+impl From<u64> for Qty {
+    fn from(value: u64) -> Self {
+        Qty(value)
+    }
+}
+
+impl From<Qty> for u64 {
+    fn from(value: Qty) -> Self {
+        value.0
+    }
+}
+
+impl From<Qty> for f64 {
+    fn from(value: Qty) -> Self {
+        value.to_f64()
+    }
+}
+
+impl From<Qty> for Decimal {
+    fn from(value: Qty) -> Self {
+        value.to_decimal()
+    }
+}
@@ -1,199 +1,1329 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
 use std::fmt;
-use std::io::{Cursor, Read};
-use std::io::{Error as IOError, ErrorKind};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 use std::time::Duration;
 
-use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
-use chrono::{TimeZone, Utc};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+#[cfg(feature = "chrono")]
+use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+#[cfg(feature = "chrono")]
+use chrono_tz::Tz;
+#[cfg(feature = "cli")]
 use colored::Colorize;
+#[cfg(feature = "json-schema")]
+use schemars::JsonSchema;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::builder::PriceQuoteBuilder;
+use crate::error::{HexQuoteError, QuoteDecodeError};
+use crate::market::{decode_ascii_code, MarketStatus, MarketType};
+use crate::price::{Price, PriceFormat, Qty};
+use crate::quote_index::{IssueInterner, QuoteKey};
+
+pub const LEVELS: usize = 5;
+
+// full length of a B6034 message on the wire, end of message byte included
+pub const B6034_LEN: usize = 215;
+// B6034 identifies issues by 12 byte isin. other message types in the same
+// layout use other widths
+pub const ISSUE_CODE_LEN: usize = 12;
+
+// message length for the B6034 layout with a different issue code width
+pub fn message_len(issue_code_len: usize) -> usize {
+    B6034_LEN - ISSUE_CODE_LEN + issue_code_len
+}
+pub const END_OF_MESSAGE: u8 = 0xff;
+
+// the most a field of each width can hold, for the json schema's maximums.
+// ascii digits never get that far, --binary-be and --binary-le can
+#[cfg(feature = "json-schema")]
+const MAX_3_BYTES: u64 = (1 << 24) - 1;
+#[cfg(feature = "json-schema")]
+const MAX_5_BYTES: u64 = (1 << 40) - 1;
+#[cfg(feature = "json-schema")]
+const MAX_7_BYTES: u64 = (1 << 56) - 1;
+
+// how the volume, price and quantity fields are written. B6034 spells them
+// in ascii digits, the default. related binary feeds put the same widths
+// down as unsigned integers in either byte order. the order counts and
+// accept time are kept as raw bytes whichever it is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumericEncoding {
+    #[default]
+    Ascii,
+    BigEndian,
+    LittleEndian,
+}
+
+// one rung of the book. order_count comes from the no_of_best_*_quote
+// fields which sit further down the wire than the price and qty
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+pub struct Level {
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(range(min = 0, max = MAX_5_BYTES), description = "Price, 0 for an empty level")
+    )]
+    pub price: Price, // 5 bytes
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(range(min = 0, max = MAX_7_BYTES), description = "Quantity at the price")
+    )]
+    pub qty: Qty, // 7 bytes
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(
+            range(min = 0, max = u32::MAX),
+            description = "Number of orders at the price, as its four raw ascii digits read as one little endian number",
+            example = u32::from_le_bytes(*b"0012")
+        )
+    )]
+    pub order_count: u32,
+}
+
+impl Level {
+    // order_count keeps the four raw ascii digits, this is the number they
+    // spell. None if they aren't digits
+    pub fn orders(&self) -> Option<u64> {
+        decode_digits(self.order_count as u64, 4)
+    }
+}
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
 pub struct PriceQuote {
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(description = "Capture time of the packet, since the unix epoch")
+    )]
     pub packet_rcv_time: Duration,
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(range(min = 0, max = u16::MAX), description = "Raw bytes of the data type, \"B6\" for a quote")
+    )]
     pub data_type: u16,
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(range(min = 0, max = u16::MAX), description = "Raw bytes of the information type, \"03\" for a quote")
+    )]
     pub information_type: u16,
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(range(min = 0, max = u8::MAX), description = "Raw byte of the market type")
+    )]
     pub market_type: u8,
+    #[cfg_attr(feature = "json-schema", schemars(description = "ISIN of the issue"))]
     pub issue_code: String,
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(range(min = 0, max = MAX_3_BYTES), description = "Raw bytes of the issue's sequence number")
+    )]
     pub issue_seq_no: u32, // only 3 bytes
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(range(min = 0, max = u16::MAX), description = "Raw bytes of the market status")
+    )]
     pub market_status_type: u16,
-    pub total_bid_quote_volume: u64, // only 7 bytes
-
-    pub best_bid_price_1st: u64,     // 5 bytes
-    pub best_bid_quantity_1st: u64,  // 7 bytes
-    pub best_bid_price_2nd: u64,     // 5 bytes
-    pub best_bid_quantity_2nd: u64,  // 7 bytes
-    pub best_bid_price_3rd: u64,     // 5 bytes
-    pub best_bid_quantity_3rd: u64,  // 7 bytes
-    pub best_bid_price_4th: u64,     // 5 bytes
-    pub best_bid_quantity_4th: u64,  // 7 bytes
-    pub best_bid_price_5th: u64,     // 5 bytes
-    pub best_bid_quantity_5th: u64,  // 7 bytes
-    pub total_ask_quote_volume: u64, // 7 bytes
-    pub best_ask_price_1st: u64,     // 5 bytes
-    pub best_ask_quantity_1st: u64,  // 7 bytes
-    pub best_ask_price_2nd: u64,     // 5 bytes
-    pub best_ask_quantity_2nd: u64,  // 7 bytes
-    pub best_ask_price_3rd: u64,     // 5 bytes
-    pub best_ask_quantity_3rd: u64,  // 7 bytes
-    pub best_ask_price_4th: u64,     // 5 bytes
-    pub best_ask_quantity_4th: u64,  // 7 bytes
-    pub best_ask_price_5th: u64,     // 5 bytes
-    pub best_ask_quantity_5th: u64,  // 7 bytes
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(range(min = 0, max = MAX_7_BYTES), description = "Total quantity bid")
+    )]
+    pub total_bid_quote_volume: Qty, // 7 bytes
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(range(min = 0, max = MAX_7_BYTES), description = "Total quantity offered")
+    )]
+    pub total_ask_quote_volume: Qty, // 7 bytes
+
+    // both sides are best first, the same order they arrive in
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(description = "Best five bids, best first")
+    )]
+    pub bids: [Level; LEVELS],
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(description = "Best five asks, best first")
+    )]
+    pub asks: [Level; LEVELS],
 
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(range(min = 0, max = MAX_5_BYTES), description = "Raw bytes of the total number of valid bid orders")
+    )]
     pub no_of_best_bid_valid_quote_total: u64, // 5 bytes
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(range(min = 0, max = MAX_5_BYTES), description = "Raw bytes of the total number of valid ask orders")
+    )]
+    pub no_of_best_ask_valid_quote_total: u64, // 5 bytes
 
-    pub no_of_best_bid_quote_1st: u32,
-    pub no_of_best_bid_quote_2nd: u32,
-    pub no_of_best_bid_quote_3rd: u32,
-    pub no_of_best_bid_quote_4th: u32,
-    pub no_of_best_bid_quote_5th: u32,
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(
+            range(min = 0, max = u64::MAX),
+            description = "Exchange accept time as its raw HHMMSSuu ascii bytes read as one little endian number",
+            example = u64::from_le_bytes(*b"09000001")
+        )
+    )]
+    pub quote_accept_time: u64,
+}
 
-    pub no_of_best_ask_valid_quote_total: u64, // 5 bytes
+// wire names of the per-level fields, best level first
+pub const BID_PRICE_FIELDS: [&str; LEVELS] = [
+    "best_bid_price_1st",
+    "best_bid_price_2nd",
+    "best_bid_price_3rd",
+    "best_bid_price_4th",
+    "best_bid_price_5th",
+];
+pub const BID_QTY_FIELDS: [&str; LEVELS] = [
+    "best_bid_quantity_1st",
+    "best_bid_quantity_2nd",
+    "best_bid_quantity_3rd",
+    "best_bid_quantity_4th",
+    "best_bid_quantity_5th",
+];
+pub const ASK_PRICE_FIELDS: [&str; LEVELS] = [
+    "best_ask_price_1st",
+    "best_ask_price_2nd",
+    "best_ask_price_3rd",
+    "best_ask_price_4th",
+    "best_ask_price_5th",
+];
+pub const ASK_QTY_FIELDS: [&str; LEVELS] = [
+    "best_ask_quantity_1st",
+    "best_ask_quantity_2nd",
+    "best_ask_quantity_3rd",
+    "best_ask_quantity_4th",
+    "best_ask_quantity_5th",
+];
+pub const BID_COUNT_FIELDS: [&str; LEVELS] = [
+    "no_of_best_bid_quote_1st",
+    "no_of_best_bid_quote_2nd",
+    "no_of_best_bid_quote_3rd",
+    "no_of_best_bid_quote_4th",
+    "no_of_best_bid_quote_5th",
+];
+pub const ASK_COUNT_FIELDS: [&str; LEVELS] = [
+    "no_of_best_ask_quote_1st",
+    "no_of_best_ask_quote_2nd",
+    "no_of_best_ask_quote_3rd",
+    "no_of_best_ask_quote_4th",
+    "no_of_best_ask_quote_5th",
+];
 
-    pub no_of_best_ask_quote_1st: u32,
-    pub no_of_best_ask_quote_2nd: u32,
-    pub no_of_best_ask_quote_3rd: u32,
-    pub no_of_best_ask_quote_4th: u32,
-    pub no_of_best_ask_quote_5th: u32,
+// generates the old per-level field names as accessors
+macro_rules! level_accessors {
+    ($($side:ident[$index:expr] => $price:ident, $qty:ident, $count:ident;)*) => {
+        $(
+            pub fn $price(&self) -> Price {
+                self.$side[$index].price
+            }
 
-    pub quote_accept_time: u64,
+            pub fn $qty(&self) -> Qty {
+                self.$side[$index].qty
+            }
+
+            pub fn $count(&self) -> u32 {
+                self.$side[$index].order_count
+            }
+        )*
+    };
 }
 
 impl PriceQuote {
-    pub fn from_bytes(rcv_time: Duration, bytes: &[u8]) -> Result<Self, IOError> {
-        let mut rdr = Cursor::new(bytes);
+    pub fn from_bytes(rcv_time: Duration, bytes: &[u8]) -> Result<Self, QuoteDecodeError> {
+        Self::from_bytes_with_issue_code_len(rcv_time, bytes, ISSUE_CODE_LEN)
+    }
+
+    pub fn from_bytes_with_issue_code_len(
+        rcv_time: Duration,
+        bytes: &[u8],
+        issue_code_len: usize,
+    ) -> Result<Self, QuoteDecodeError> {
+        Self::from_bytes_with(rcv_time, bytes, issue_code_len, NumericEncoding::Ascii)
+    }
 
-        Ok(PriceQuote {
+    pub fn from_bytes_with(
+        rcv_time: Duration,
+        bytes: &[u8],
+        issue_code_len: usize,
+        numbers: NumericEncoding,
+    ) -> Result<Self, QuoteDecodeError> {
+        let mut rdr = FieldReader::new(bytes);
+        let mut quote = PriceQuote {
             packet_rcv_time: rcv_time,
-            data_type: rdr.read_u16::<LittleEndian>()?,
-            information_type: rdr.read_u16::<LittleEndian>()?,
-            market_type: rdr.read_u8()?,
-            issue_code: {
-                let mut buf = [0; 12]; // Adjust size as per your data
-                rdr.read_exact(&mut buf)?;
-                String::from_utf8_lossy(&buf).into_owned()
-            },
-            issue_seq_no: {
-                let mut buf = [0; 3];
-                rdr.read_exact(&mut buf)?;
-                (buf[0] as u32) << 16 | (buf[1] as u32) << 8 | buf[2] as u32
-            },
-            market_status_type: rdr.read_u16::<LittleEndian>()?,
-            total_bid_quote_volume: rdr.read_uint::<LittleEndian>(7)?,
-
-            best_bid_price_1st: Self::read_ascii_decimal(&mut rdr, 5)?,
-            best_bid_quantity_1st: Self::read_ascii_decimal(&mut rdr, 7)?,
-            best_bid_price_2nd: Self::read_ascii_decimal(&mut rdr, 5)?,
-            best_bid_quantity_2nd: Self::read_ascii_decimal(&mut rdr, 7)?,
-            best_bid_price_3rd: Self::read_ascii_decimal(&mut rdr, 5)?,
-            best_bid_quantity_3rd: Self::read_ascii_decimal(&mut rdr, 7)?,
-            best_bid_price_4th: Self::read_ascii_decimal(&mut rdr, 5)?,
-            best_bid_quantity_4th: Self::read_ascii_decimal(&mut rdr, 7)?,
-            best_bid_price_5th: Self::read_ascii_decimal(&mut rdr, 5)?,
-            best_bid_quantity_5th: Self::read_ascii_decimal(&mut rdr, 7)?,
-
-            total_ask_quote_volume: rdr.read_uint::<LittleEndian>(7)?,
-
-            best_ask_price_1st: Self::read_ascii_decimal(&mut rdr, 5)?,
-            best_ask_quantity_1st: Self::read_ascii_decimal(&mut rdr, 7)?,
-            best_ask_price_2nd: Self::read_ascii_decimal(&mut rdr, 5)?,
-            best_ask_quantity_2nd: Self::read_ascii_decimal(&mut rdr, 7)?,
-            best_ask_price_3rd: Self::read_ascii_decimal(&mut rdr, 5)?,
-            best_ask_quantity_3rd: Self::read_ascii_decimal(&mut rdr, 7)?,
-            best_ask_price_4th: Self::read_ascii_decimal(&mut rdr, 5)?,
-            best_ask_quantity_4th: Self::read_ascii_decimal(&mut rdr, 7)?,
-            best_ask_price_5th: Self::read_ascii_decimal(&mut rdr, 5)?,
-            best_ask_quantity_5th: Self::read_ascii_decimal(&mut rdr, 7)?,
-
-            no_of_best_bid_valid_quote_total: rdr.read_uint::<LittleEndian>(5)?,
-            no_of_best_bid_quote_1st: rdr.read_u32::<LittleEndian>()?,
-            no_of_best_bid_quote_2nd: rdr.read_u32::<LittleEndian>()?,
-            no_of_best_bid_quote_3rd: rdr.read_u32::<LittleEndian>()?,
-            no_of_best_bid_quote_4th: rdr.read_u32::<LittleEndian>()?,
-            no_of_best_bid_quote_5th: rdr.read_u32::<LittleEndian>()?,
-            no_of_best_ask_valid_quote_total: rdr.read_uint::<LittleEndian>(5)?,
-            no_of_best_ask_quote_1st: rdr.read_u32::<LittleEndian>()?,
-            no_of_best_ask_quote_2nd: rdr.read_u32::<LittleEndian>()?,
-            no_of_best_ask_quote_3rd: rdr.read_u32::<LittleEndian>()?,
-            no_of_best_ask_quote_4th: rdr.read_u32::<LittleEndian>()?,
-            no_of_best_ask_quote_5th: rdr.read_u32::<LittleEndian>()?,
-            quote_accept_time: rdr.read_u64::<LittleEndian>()?,
+            ..Default::default()
+        };
+
+        quote.data_type = rdr.read_uint("data_type", 2)? as u16;
+        quote.information_type = rdr.read_uint("information_type", 2)? as u16;
+        quote.market_type = rdr.read_uint("market_type", 1)? as u8;
+        quote.issue_code =
+            String::from_utf8_lossy(rdr.take("issue_code", issue_code_len)?).into_owned();
+        quote.issue_seq_no = BigEndian::read_uint(rdr.take("issue_seq_no", 3)?, 3) as u32;
+        quote.market_status_type = rdr.read_uint("market_status_type", 2)? as u16;
+
+        quote.total_bid_quote_volume =
+            Qty(rdr.read_number("total_bid_quote_volume", 7, numbers)?);
+        for (i, level) in quote.bids.iter_mut().enumerate() {
+            level.price = Price(rdr.read_number(BID_PRICE_FIELDS[i], 5, numbers)?);
+            level.qty = Qty(rdr.read_number(BID_QTY_FIELDS[i], 7, numbers)?);
+        }
+
+        quote.total_ask_quote_volume =
+            Qty(rdr.read_number("total_ask_quote_volume", 7, numbers)?);
+        for (i, level) in quote.asks.iter_mut().enumerate() {
+            level.price = Price(rdr.read_number(ASK_PRICE_FIELDS[i], 5, numbers)?);
+            level.qty = Qty(rdr.read_number(ASK_QTY_FIELDS[i], 7, numbers)?);
+        }
+
+        quote.no_of_best_bid_valid_quote_total =
+            rdr.read_uint("no_of_best_bid_valid_quote_total", 5)?;
+        for (i, level) in quote.bids.iter_mut().enumerate() {
+            level.order_count = rdr.read_uint(BID_COUNT_FIELDS[i], 4)? as u32;
+        }
+        quote.no_of_best_ask_valid_quote_total =
+            rdr.read_uint("no_of_best_ask_valid_quote_total", 5)?;
+        for (i, level) in quote.asks.iter_mut().enumerate() {
+            level.order_count = rdr.read_uint(ASK_COUNT_FIELDS[i], 4)? as u32;
+        }
+
+        quote.quote_accept_time = rdr.read_uint("quote_accept_time", 8)?;
+
+        Ok(quote)
+    }
+
+    // just the header fields and the accept time, skipping over the book
+    // without looking at it. errors only if the payload is too short, the
+    // levels aren't checked for digits the way from_bytes checks them
+    pub fn header_from_bytes(
+        rcv_time: Duration,
+        bytes: &[u8],
+    ) -> Result<QuoteHeader, QuoteDecodeError> {
+        Self::header_from_bytes_with_issue_code_len(rcv_time, bytes, ISSUE_CODE_LEN)
+    }
+
+    pub fn header_from_bytes_with_issue_code_len(
+        rcv_time: Duration,
+        bytes: &[u8],
+        issue_code_len: usize,
+    ) -> Result<QuoteHeader, QuoteDecodeError> {
+        let mut rdr = FieldReader::new(bytes);
+        let mut header = QuoteHeader {
+            packet_rcv_time: rcv_time,
+            ..Default::default()
+        };
+
+        header.data_type = rdr.read_uint("data_type", 2)? as u16;
+        header.information_type = rdr.read_uint("information_type", 2)? as u16;
+        header.market_type = rdr.read_uint("market_type", 1)? as u8;
+        header.issue_code =
+            String::from_utf8_lossy(rdr.take("issue_code", issue_code_len)?).into_owned();
+        header.issue_seq_no = BigEndian::read_uint(rdr.take("issue_seq_no", 3)?, 3) as u32;
+        header.market_status_type = rdr.read_uint("market_status_type", 2)? as u16;
+
+        // accept time is the last field before the end of message byte
+        let accept_time_offset = message_len(issue_code_len) - 1 - 8;
+        rdr.take("book", accept_time_offset - rdr.offset)?;
+        header.quote_accept_time = rdr.read_uint("quote_accept_time", 8)?;
+
+        Ok(header)
+    }
+
+    // the exact wire bytes from_bytes would have decoded this quote from,
+    // including the trailing end of message byte. numbers wider than their
+    // ascii field keep only their low digits, and the issue code is space
+    // padded or cut to 12 bytes
+    pub fn encode_b6034(&self) -> Vec<u8> {
+        self.encode_with_issue_code_len(ISSUE_CODE_LEN)
+    }
+
+    pub fn encode_with_issue_code_len(&self, issue_code_len: usize) -> Vec<u8> {
+        self.encode_with(issue_code_len, NumericEncoding::Ascii)
+    }
+
+    // numbers too wide for a binary field keep only their low bytes
+    pub fn encode_with(&self, issue_code_len: usize, numbers: NumericEncoding) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(message_len(issue_code_len));
+        let push_uint = |buf: &mut Vec<u8>, value: u64, len: usize| {
+            buf.extend_from_slice(&value.to_le_bytes()[..len]);
+        };
+        let push_ascii = |buf: &mut Vec<u8>, value: u64, len: usize| match numbers {
+            NumericEncoding::Ascii => {
+                let digits = format!("{:0width$}", value, width = len);
+                buf.extend_from_slice(&digits.as_bytes()[digits.len() - len..]);
+            }
+            NumericEncoding::BigEndian => buf.extend_from_slice(&value.to_be_bytes()[8 - len..]),
+            NumericEncoding::LittleEndian => buf.extend_from_slice(&value.to_le_bytes()[..len]),
+        };
+
+        push_uint(&mut buf, self.data_type as u64, 2);
+        push_uint(&mut buf, self.information_type as u64, 2);
+        push_uint(&mut buf, self.market_type as u64, 1);
+        let mut issue_code = self.issue_code.as_bytes().to_vec();
+        issue_code.resize(issue_code_len, b' ');
+        buf.extend_from_slice(&issue_code);
+        buf.extend_from_slice(&self.issue_seq_no.to_be_bytes()[1..]);
+        push_uint(&mut buf, self.market_status_type as u64, 2);
+
+        push_ascii(&mut buf, self.total_bid_quote_volume.raw(), 7);
+        for level in &self.bids {
+            push_ascii(&mut buf, level.price.raw(), 5);
+            push_ascii(&mut buf, level.qty.raw(), 7);
+        }
+        push_ascii(&mut buf, self.total_ask_quote_volume.raw(), 7);
+        for level in &self.asks {
+            push_ascii(&mut buf, level.price.raw(), 5);
+            push_ascii(&mut buf, level.qty.raw(), 7);
+        }
+
+        push_uint(&mut buf, self.no_of_best_bid_valid_quote_total, 5);
+        for level in &self.bids {
+            push_uint(&mut buf, level.order_count as u64, 4);
+        }
+        push_uint(&mut buf, self.no_of_best_ask_valid_quote_total, 5);
+        for level in &self.asks {
+            push_uint(&mut buf, level.order_count as u64, 4);
+        }
+
+        push_uint(&mut buf, self.quote_accept_time, 8);
+        buf.push(END_OF_MESSAGE);
+        buf
+    }
+
+    // a realistic quote for docs and tests
+    pub fn example() -> Self {
+        PriceQuoteBuilder::new().build()
+    }
+
+    level_accessors! {
+        bids[0] => best_bid_price_1st, best_bid_quantity_1st, no_of_best_bid_quote_1st;
+        bids[1] => best_bid_price_2nd, best_bid_quantity_2nd, no_of_best_bid_quote_2nd;
+        bids[2] => best_bid_price_3rd, best_bid_quantity_3rd, no_of_best_bid_quote_3rd;
+        bids[3] => best_bid_price_4th, best_bid_quantity_4th, no_of_best_bid_quote_4th;
+        bids[4] => best_bid_price_5th, best_bid_quantity_5th, no_of_best_bid_quote_5th;
+        asks[0] => best_ask_price_1st, best_ask_quantity_1st, no_of_best_ask_quote_1st;
+        asks[1] => best_ask_price_2nd, best_ask_quantity_2nd, no_of_best_ask_quote_2nd;
+        asks[2] => best_ask_price_3rd, best_ask_quantity_3rd, no_of_best_ask_quote_3rd;
+        asks[3] => best_ask_price_4th, best_ask_quantity_4th, no_of_best_ask_quote_4th;
+        asks[4] => best_ask_price_5th, best_ask_quantity_5th, no_of_best_ask_quote_5th;
+    }
+
+    // sum of the quantities across the five visible levels
+    pub fn bid_level_volume(&self) -> Qty {
+        self.depth(LEVELS).0
+    }
+
+    pub fn ask_level_volume(&self) -> Qty {
+        self.depth(LEVELS).1
+    }
+
+    // a side with nothing at the top is empty, whatever the deeper levels say
+    pub fn has_bid(&self) -> bool {
+        !self.bids[0].price.is_zero()
+    }
+
+    pub fn has_ask(&self) -> bool {
+        !self.asks[0].price.is_zero()
+    }
+
+    // levels with a price, wherever they are. gaps count for nothing
+    pub fn populated_bid_levels(&self) -> usize {
+        self.bids
+            .iter()
+            .filter(|level| !level.price.is_zero())
+            .count()
+    }
+
+    pub fn populated_ask_levels(&self) -> usize {
+        self.asks
+            .iter()
+            .filter(|level| !level.price.is_zero())
+            .count()
+    }
+
+    // at least one side of the book is empty
+    pub fn is_one_sided(&self) -> bool {
+        !self.has_bid() || !self.has_ask()
+    }
+
+    // best ask minus best bid. None when either side of the book is empty
+    // or the book is crossed
+    pub fn spread(&self) -> Option<Price> {
+        let best_bid = self.bids[0].price;
+        let best_ask = self.asks[0].price;
+        if best_bid.is_zero() || best_ask.is_zero() {
+            return None;
+        }
+        best_ask.checked_sub(best_bid)
+    }
+
+    // average of the best bid and ask, with scale decimal places. done in
+    // floating point so an odd sum lands on the half instead of being
+    // rounded toward the bid
+    pub fn mid_price(&self, scale: u32) -> Option<f64> {
+        let best_bid = self.bids[0].price;
+        let best_ask = self.asks[0].price;
+        if best_bid.is_zero() || best_ask.is_zero() {
+            return None;
+        }
+        Some((best_bid.to_f64(scale) + best_ask.to_f64(scale)) / 2.0)
+    }
+
+    // share of the top level volume sitting on the bid, 0.0 to 1.0
+    pub fn imbalance(&self) -> Option<f64> {
+        let bid_qty = self.bids[0].qty.to_f64();
+        let ask_qty = self.asks[0].qty.to_f64();
+        if bid_qty + ask_qty == 0.0 {
+            return None;
+        }
+        Some(bid_qty / (bid_qty + ask_qty))
+    }
+
+    // share of the total quoted volume sitting at the best level. above
+    // about 0.8 the book is thin past the top. 0.0 when nothing is quoted
+    pub fn bid_depth_concentration(&self) -> f64 {
+        concentration(self.bids[0].qty, self.total_bid_quote_volume)
+    }
+
+    pub fn ask_depth_concentration(&self) -> f64 {
+        concentration(self.asks[0].qty, self.total_ask_quote_volume)
+    }
+
+    // herfindahl index of the quantity shares across the levels, from
+    // 1 / LEVELS when spread evenly up to 1.0 when it's all on one level.
+    // 0.0 when the side is empty
+    pub fn herfindahl_bid(&self) -> f64 {
+        herfindahl(&self.bids)
+    }
+
+    pub fn herfindahl_ask(&self) -> f64 {
+        herfindahl(&self.asks)
+    }
+
+    // total (bid, ask) quantity over the first n levels, n capped at LEVELS
+    pub fn depth(&self, n: usize) -> (Qty, Qty) {
+        let n = n.min(LEVELS);
+        let sum = |levels: &[Level]| {
+            levels
+                .iter()
+                .fold(Qty(0), |acc, level| acc.saturating_add(level.qty))
+        };
+        (sum(&self.bids[..n]), sum(&self.asks[..n]))
+    }
+
+    // market_type keeps the raw wire byte for round tripping, this is the
+    // decoded view. codes that aren't digits come back as Unknown(raw)
+    pub fn market(&self) -> MarketType {
+        decode_market(self.market_type)
+    }
+
+    pub fn market_status(&self) -> MarketStatus {
+        decode_market_status(self.market_status_type)
+    }
+
+    // issue_seq_no keeps the three raw ascii digits, this is the number they
+    // spell. None if they aren't digits
+    pub fn seq(&self) -> Option<u32> {
+        decode_seq(self.issue_seq_no)
+    }
+
+    // the no_of_best_*_valid_quote_total fields decoded the same way
+    pub fn bid_order_total(&self) -> Option<u64> {
+        decode_digits(self.no_of_best_bid_valid_quote_total, 5)
+    }
+
+    pub fn ask_order_total(&self) -> Option<u64> {
+        decode_digits(self.no_of_best_ask_valid_quote_total, 5)
+    }
+
+    // the issue code read as an isin: two letter country, nine character
+    // national id and a check digit. None if it isn't shaped like one
+    pub fn country_code(&self) -> Option<&str> {
+        isin_parts(&self.issue_code).map(|(country, _, _)| country)
+    }
+
+    pub fn nsin(&self) -> Option<&str> {
+        isin_parts(&self.issue_code).map(|(_, nsin, _)| nsin)
+    }
+
+    pub fn check_digit(&self) -> Option<u8> {
+        isin_parts(&self.issue_code).map(|(_, _, digit)| digit)
+    }
+
+    pub fn is_korean(&self) -> bool {
+        self.country_code() == Some("KR")
+    }
+
+    // quote_accept_time holds the raw ascii HHMMSScc (hundredths of a second)
+    // read as a little endian integer. None if those bytes aren't a real time
+    #[cfg(feature = "chrono")]
+    pub fn accept_time(&self) -> Option<NaiveTime> {
+        decode_accept_time(self.quote_accept_time)
+    }
+
+    // the fields header_from_bytes would have read
+    pub fn header(&self) -> QuoteHeader {
+        QuoteHeader {
+            packet_rcv_time: self.packet_rcv_time,
+            data_type: self.data_type,
+            information_type: self.information_type,
+            market_type: self.market_type,
+            issue_code: self.issue_code.clone(),
+            issue_seq_no: self.issue_seq_no,
+            market_status_type: self.market_status_type,
+            quote_accept_time: self.quote_accept_time,
+        }
+    }
+
+    // the capture date as seen in tz, handy as a default session date
+    #[cfg(feature = "chrono")]
+    pub fn capture_date(&self, tz: Tz) -> Option<NaiveDate> {
+        Utc.timestamp_opt(
+            self.packet_rcv_time.as_secs() as i64,
+            self.packet_rcv_time.subsec_nanos(),
+        )
+        .single()
+        .map(|dt| dt.with_timezone(&tz).date_naive())
+    }
+
+    // how long after the exchange accepted the quote it reached the capture
+    // point. the accept time only carries a time of day, so the session date
+    // and exchange timezone fill in the rest. negative means the clocks disagree
+    #[cfg(feature = "chrono")]
+    pub fn latency(&self, session_date: NaiveDate, tz: Tz) -> Option<chrono::Duration> {
+        let accepted = tz
+            .from_local_datetime(&session_date.and_time(self.accept_time()?))
+            .single()?;
+        let received = Utc
+            .timestamp_opt(
+                self.packet_rcv_time.as_secs() as i64,
+                self.packet_rcv_time.subsec_nanos(),
+            )
+            .single()?;
+        Some(received.signed_duration_since(accepted))
+    }
+
+    // hash of everything that came off the wire, leaving out the capture
+    // timestamp so the same quote seen on two nics hashes the same
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.data_type.hash(&mut hasher);
+        self.information_type.hash(&mut hasher);
+        self.market_type.hash(&mut hasher);
+        self.issue_code.hash(&mut hasher);
+        self.issue_seq_no.hash(&mut hasher);
+        self.market_status_type.hash(&mut hasher);
+        self.total_bid_quote_volume.hash(&mut hasher);
+        self.total_ask_quote_volume.hash(&mut hasher);
+        self.bids.hash(&mut hasher);
+        self.asks.hash(&mut hasher);
+        self.no_of_best_bid_valid_quote_total.hash(&mut hasher);
+        self.no_of_best_ask_valid_quote_total.hash(&mut hasher);
+        self.quote_accept_time.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // true when both total volumes match the sum of their levels
+    pub fn volumes_consistent(&self) -> bool {
+        self.total_bid_quote_volume == self.bid_level_volume()
+            && self.total_ask_quote_volume == self.ask_level_volume()
+    }
+
+    // the quote's identity for keyed collections. None if the seq isn't
+    // digits
+    pub fn key(&self, interner: &mut IssueInterner) -> Option<QuoteKey> {
+        let seq = self.seq()?;
+        Some(QuoteKey {
+            issue: interner.intern(&self.issue_code),
+            seq,
         })
     }
 
-    fn read_ascii_decimal(rdr: &mut Cursor<&[u8]>, len: usize) -> Result<u64, IOError> {
-        let mut buf = vec![0u8; len];
-        rdr.read_exact(&mut buf)?;
+    // quantity across every visible level on both sides
+    pub fn total_depth(&self) -> Qty {
+        let (bid, ask) = self.depth(LEVELS);
+        bid.saturating_add(ask)
+    }
+
+    // everything wrong with the shape of the book, empty when it's sound.
+    // a healthy feed never sends any of these, so they point at a decode bug
+    // or a corrupted packet
+    pub fn validate_book(&self) -> Vec<BookViolation> {
+        let mut violations = vec![];
+        for side in [Side::Bid, Side::Ask] {
+            let (levels, valid_quote_total) = match side {
+                Side::Bid => (&self.bids, self.no_of_best_bid_valid_quote_total),
+                Side::Ask => (&self.asks, self.no_of_best_ask_valid_quote_total),
+            };
+
+            for level in 1..LEVELS {
+                let (better, price) = (levels[level - 1].price, levels[level].price);
+                let out_of_order = match side {
+                    Side::Bid => price > better,
+                    Side::Ask => price < better,
+                };
+                if !better.is_zero() && !price.is_zero() && out_of_order {
+                    violations.push(BookViolation::OutOfOrder { side, level });
+                }
+            }
 
-        let s = std::str::from_utf8(&buf).map_err(|e| IOError::new(ErrorKind::InvalidData, e))?;
+            let last_populated = levels.iter().rposition(|l| !l.price.is_zero());
+            let gaps = levels[..last_populated.unwrap_or(0)]
+                .iter()
+                .enumerate()
+                .filter(|(_, l)| l.price.is_zero());
+            for (level, _) in gaps {
+                violations.push(BookViolation::Gap { side, level });
+            }
 
-        s.parse::<u64>()
-            .map_err(|e| IOError::new(ErrorKind::InvalidData, e))
+            // the total covers the whole book, the counts only the visible
+            // levels, so they can fall short of it but never pass it
+            let total = decode_digits(valid_quote_total, 5);
+            let sum = levels.iter().try_fold(0, |sum, l| Some(sum + l.orders()?));
+            let consistent = matches!((total, sum), (Some(total), Some(sum)) if sum <= total);
+            if !consistent {
+                violations.push(BookViolation::OrderCounts { side, total, sum });
+            }
+        }
+        violations
     }
 }
 
-impl fmt::Display for PriceQuote {
+impl PriceQuote {
+    // a payload written out as hex, like "42 36 30 ..." or "0x423630...".
+    // whitespace and 0x prefixes are ignored
+    pub fn from_hex(rcv_time: Duration, hex: &str) -> Result<Self, HexQuoteError> {
+        let bytes = decode_hex(hex)?;
+        Ok(Self::from_bytes(rcv_time, &bytes)?)
+    }
+}
+
+// hex with no receive time, mostly for pasting payloads while debugging
+impl FromStr for PriceQuote {
+    type Err = HexQuoteError;
+
+    fn from_str(hex: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(Duration::ZERO, hex)
+    }
+}
+
+// the other direction, lowercase and unbroken so it pastes as one word
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, HexQuoteError> {
+    let mut bytes = Vec::with_capacity(hex.len() / 2);
+    let mut high_nibble = None;
+    let mut digits = 0;
+    let mut chars = hex.char_indices().peekable();
+    let mut token_start = true;
+    while let Some((offset, c)) = chars.next() {
+        if c.is_whitespace() {
+            token_start = true;
+            continue;
+        }
+        // 0x only counts as a prefix at the start of a token
+        if token_start && c == '0' && matches!(chars.peek(), Some((_, 'x' | 'X'))) {
+            chars.next();
+            token_start = false;
+            continue;
+        }
+        token_start = false;
+
+        let nibble = c
+            .to_digit(16)
+            .ok_or(HexQuoteError::InvalidChar { offset, found: c })? as u8;
+        digits += 1;
+        match high_nibble.take() {
+            Some(high) => bytes.push(high << 4 | nibble),
+            None => high_nibble = Some(nibble),
+        }
+    }
+    if high_nibble.is_some() {
+        return Err(HexQuoteError::OddLength { digits });
+    }
+    Ok(bytes)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+impl fmt::Display for Side {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // packet time
-        let packet_time_result = Utc.timestamp_opt(
-            self.packet_rcv_time.as_secs() as i64,
-            self.packet_rcv_time.subsec_nanos(),
-        );
-        let packet_time_fmt = match packet_time_result {
-            chrono::LocalResult::Single(dt) => dt.format("%Y-%m-%d %H:%M:%S").to_string(),
-            _ => "Invalid time".to_string(),
+        match self {
+            Side::Bid => write!(f, "bid"),
+            Side::Ask => write!(f, "ask"),
+        }
+    }
+}
+
+// one thing validate_book found. levels count from 0, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum BookViolation {
+    // priced through the level before it, a bid above or an ask below
+    OutOfOrder {
+        side: Side,
+        level: usize,
+    },
+    // an empty level with a populated one further down
+    Gap {
+        side: Side,
+        level: usize,
+    },
+    // the per level order counts add up to more than the side's valid quote
+    // total. None where the field isn't digits
+    OrderCounts {
+        side: Side,
+        total: Option<u64>,
+        sum: Option<u64>,
+    },
+}
+
+impl fmt::Display for BookViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fmt_count = |count: Option<u64>| match count {
+            Some(count) => count.to_string(),
+            None => "invalid".to_string(),
         };
+        match self {
+            BookViolation::OutOfOrder { side, level } => {
+                write!(f, "{} level {} is out of order", side, level)
+            }
+            BookViolation::Gap { side, level } => {
+                write!(
+                    f,
+                    "{} level {} is empty but later levels aren't",
+                    side, level
+                )
+            }
+            BookViolation::OrderCounts { side, total, sum } => write!(
+                f,
+                "{} order counts add up to {} against a total of {}",
+                side,
+                fmt_count(*sum),
+                fmt_count(*total)
+            ),
+        }
+    }
+}
+
+fn concentration(best: Qty, total: Qty) -> f64 {
+    if total.is_zero() {
+        return 0.0;
+    }
+    best.to_f64() / total.to_f64()
+}
+
+fn herfindahl(levels: &[Level]) -> f64 {
+    let total: f64 = levels.iter().map(|level| level.qty.to_f64()).sum();
+    if total == 0.0 {
+        return 0.0;
+    }
+    levels
+        .iter()
+        .map(|level| (level.qty.to_f64() / total).powi(2))
+        .sum()
+}
+
+// like decode_ascii_code, for fields too wide to fit a u16
+fn decode_digits(raw: u64, len: usize) -> Option<u64> {
+    let digits = &raw.to_le_bytes()[..len];
+    if !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    Some(
+        digits
+            .iter()
+            .fold(0u64, |acc, d| acc * 10 + (d - b'0') as u64),
+    )
+}
+
+fn decode_market(market_type: u8) -> MarketType {
+    match decode_ascii_code(market_type as u64, 1) {
+        Some(code) => MarketType::from_code(code),
+        None => MarketType::Unknown(market_type as u16),
+    }
+}
+
+fn decode_market_status(market_status_type: u16) -> MarketStatus {
+    match decode_ascii_code(market_status_type as u64, 2) {
+        Some(code) => MarketStatus::from_code(code),
+        None => MarketStatus::Unknown(market_status_type),
+    }
+}
+
+// country, nsin and check digit. the check digit is read, not verified
+fn isin_parts(issue_code: &str) -> Option<(&str, &str, u8)> {
+    let bytes = issue_code.as_bytes();
+    if bytes.len() != 12
+        || !bytes[..2].iter().all(u8::is_ascii_uppercase)
+        || !bytes[2..11]
+            .iter()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+        || !bytes[11].is_ascii_digit()
+    {
+        return None;
+    }
+    Some((&issue_code[..2], &issue_code[2..11], bytes[11] - b'0'))
+}
+
+fn decode_seq(issue_seq_no: u32) -> Option<u32> {
+    let [_, d0, d1, d2] = issue_seq_no.to_be_bytes();
+    let raw = u32::from_le_bytes([d0, d1, d2, 0]) as u64;
+    decode_ascii_code(raw, 3).map(u32::from)
+}
+
+// hundredths of a second since midnight. orders the same as the NaiveTime
+// would, without needing chrono
+pub(crate) fn accept_hundredths(quote_accept_time: u64) -> Option<u32> {
+    let bytes = quote_accept_time.to_le_bytes();
+    if !bytes.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let field = |i: usize| ((bytes[i] - b'0') * 10 + (bytes[i + 1] - b'0')) as u32;
+    let (hour, minute, second) = (field(0), field(2), field(4));
+    if hour >= 24 || minute >= 60 || second >= 60 {
+        return None;
+    }
+    Some(((hour * 60 + minute) * 60 + second) * 100 + field(6))
+}
 
-        // quote accept time is ascii for some reason
-        let bytes = self.quote_accept_time.to_le_bytes();
-        let quote_time_str = String::from_utf8_lossy(&bytes);
+#[cfg(feature = "chrono")]
+fn decode_accept_time(quote_accept_time: u64) -> Option<NaiveTime> {
+    let hundredths = accept_hundredths(quote_accept_time)?;
+    NaiveTime::from_num_seconds_from_midnight_opt(hundredths / 100, hundredths % 100 * 10_000_000)
+}
+
+// HH:MM:SS.mmm, or invalid
+pub(crate) fn fmt_accept_time(quote_accept_time: u64) -> String {
+    accept_hundredths(quote_accept_time).map_or("invalid".to_string(), |t| {
+        format!(
+            "{:02}:{:02}:{:02}.{:03}",
+            t / 360_000,
+            t / 6_000 % 60,
+            t / 100 % 60,
+            t % 100 * 10
+        )
+    })
+}
+
+// YYYY-MM-DD HH:MM:SS in utc. without chrono it's plain seconds since the
+// epoch instead
+#[cfg(feature = "chrono")]
+pub(crate) fn fmt_rcv_time(packet_rcv_time: Duration) -> String {
+    Utc.timestamp_opt(
+        packet_rcv_time.as_secs() as i64,
+        packet_rcv_time.subsec_nanos(),
+    )
+    .single()
+    .map_or("Invalid time".to_string(), |dt| {
+        dt.format("%Y-%m-%d %H:%M:%S").to_string()
+    })
+}
+
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn fmt_rcv_time(packet_rcv_time: Duration) -> String {
+    packet_rcv_time.as_secs().to_string()
+}
+
+// YYYY-MM-DDTHH:MM:SS.nnnnnnnnnZ, or seconds and nanos since the epoch
+// without chrono
+#[cfg(feature = "chrono")]
+pub(crate) fn fmt_rcv_time_iso(packet_rcv_time: Duration) -> String {
+    Utc.timestamp_opt(
+        packet_rcv_time.as_secs() as i64,
+        packet_rcv_time.subsec_nanos(),
+    )
+    .single()
+    .map_or("invalid".to_string(), |dt| {
+        dt.format("%Y-%m-%dT%H:%M:%S%.9fZ").to_string()
+    })
+}
+
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn fmt_rcv_time_iso(packet_rcv_time: Duration) -> String {
+    format!(
+        "{}.{:09}",
+        packet_rcv_time.as_secs(),
+        packet_rcv_time.subsec_nanos()
+    )
+}
+
+// everything in a quote but the book: who, when and in what market state.
+// enough to count or filter quotes without paying for the levels
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QuoteHeader {
+    pub packet_rcv_time: Duration,
+    pub data_type: u16,
+    pub information_type: u16,
+    pub market_type: u8,
+    pub issue_code: String,
+    pub issue_seq_no: u32,
+    pub market_status_type: u16,
+    pub quote_accept_time: u64,
+}
+
+impl QuoteHeader {
+    pub fn market(&self) -> MarketType {
+        decode_market(self.market_type)
+    }
+
+    pub fn market_status(&self) -> MarketStatus {
+        decode_market_status(self.market_status_type)
+    }
+
+    pub fn seq(&self) -> Option<u32> {
+        decode_seq(self.issue_seq_no)
+    }
+
+    // the issue code read as an isin: two letter country, nine character
+    // national id and a check digit. None if it isn't shaped like one
+    pub fn country_code(&self) -> Option<&str> {
+        isin_parts(&self.issue_code).map(|(country, _, _)| country)
+    }
+
+    pub fn nsin(&self) -> Option<&str> {
+        isin_parts(&self.issue_code).map(|(_, nsin, _)| nsin)
+    }
+
+    pub fn check_digit(&self) -> Option<u8> {
+        isin_parts(&self.issue_code).map(|(_, _, digit)| digit)
+    }
+
+    pub fn is_korean(&self) -> bool {
+        self.country_code() == Some("KR")
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn accept_time(&self) -> Option<NaiveTime> {
+        decode_accept_time(self.quote_accept_time)
+    }
+}
+
+impl fmt::Display for QuoteHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let packet_time = fmt_rcv_time(self.packet_rcv_time);
+        let accept_time = fmt_accept_time(self.quote_accept_time);
+        let seq = self.seq().map_or("-".to_string(), |seq| seq.to_string());
+        write!(
+            f,
+            "{} {} {} {} {}",
+            packet_time,
+            accept_time,
+            self.issue_code,
+            seq,
+            self.market_status()
+        )
+    }
+}
+
+// walks the payload front to back, remembering where it is so errors can
+// say which field and offset went wrong
+struct FieldReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> FieldReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        FieldReader { bytes, offset: 0 }
+    }
+
+    fn take(&mut self, field: &'static str, len: usize) -> Result<&'a [u8], QuoteDecodeError> {
+        let available = self.bytes.len() - self.offset;
+        if available < len {
+            return Err(QuoteDecodeError::Truncated {
+                field,
+                offset: self.offset,
+                needed: len,
+                available,
+            });
+        }
+        let buf = &self.bytes[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(buf)
+    }
+
+    fn read_uint(&mut self, field: &'static str, len: usize) -> Result<u64, QuoteDecodeError> {
+        Ok(LittleEndian::read_uint(self.take(field, len)?, len))
+    }
+
+    fn read_number(
+        &mut self,
+        field: &'static str,
+        len: usize,
+        numbers: NumericEncoding,
+    ) -> Result<u64, QuoteDecodeError> {
+        match numbers {
+            NumericEncoding::Ascii => self.read_ascii_decimal(field, len),
+            NumericEncoding::BigEndian => Ok(BigEndian::read_uint(self.take(field, len)?, len)),
+            NumericEncoding::LittleEndian => {
+                Ok(LittleEndian::read_uint(self.take(field, len)?, len))
+            }
+        }
+    }
+
+    fn read_ascii_decimal(
+        &mut self,
+        field: &'static str,
+        len: usize,
+    ) -> Result<u64, QuoteDecodeError> {
+        let offset = self.offset;
+        let buf = self.take(field, len)?;
+        std::str::from_utf8(buf)
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .ok_or_else(|| QuoteDecodeError::InvalidDigits {
+                field,
+                offset,
+                found: String::from_utf8_lossy(buf).into_owned(),
+            })
+    }
+}
+
+// quotes order by when the exchange accepted them, then by when we captured
+// them, then by issue sequence number. quotes with an unreadable accept time
+// sort first. the remaining fields only break ties, which keeps Ord in line
+// with Eq
+impl Ord for PriceQuote {
+    fn cmp(&self, other: &Self) -> Ordering {
+        accept_hundredths(self.quote_accept_time)
+            .cmp(&accept_hundredths(other.quote_accept_time))
+            .then_with(|| self.packet_rcv_time.cmp(&other.packet_rcv_time))
+            .then_with(|| self.issue_seq_no.cmp(&other.issue_seq_no))
+            .then_with(|| self.issue_code.cmp(&other.issue_code))
+            .then_with(|| self.quote_accept_time.cmp(&other.quote_accept_time))
+            .then_with(|| self.data_type.cmp(&other.data_type))
+            .then_with(|| self.information_type.cmp(&other.information_type))
+            .then_with(|| self.market_type.cmp(&other.market_type))
+            .then_with(|| self.market_status_type.cmp(&other.market_status_type))
+            .then_with(|| {
+                self.total_bid_quote_volume
+                    .cmp(&other.total_bid_quote_volume)
+            })
+            .then_with(|| {
+                self.total_ask_quote_volume
+                    .cmp(&other.total_ask_quote_volume)
+            })
+            .then_with(|| self.bids.cmp(&other.bids))
+            .then_with(|| self.asks.cmp(&other.asks))
+            .then_with(|| {
+                self.no_of_best_bid_valid_quote_total
+                    .cmp(&other.no_of_best_bid_valid_quote_total)
+            })
+            .then_with(|| {
+                self.no_of_best_ask_valid_quote_total
+                    .cmp(&other.no_of_best_ask_valid_quote_total)
+            })
+    }
+}
+
+impl PartialOrd for PriceQuote {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// the orderings the cli and library users can sort by. every comparator
+// falls back to the full Ord on ties so each one is a total order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    AcceptTime,
+    Issue,
+    RcvTime,
+    Spread,
+}
+
+impl SortKey {
+    pub const NAMES: [&'static str; 4] = ["accept-time", "issue", "rcv-time", "spread"];
+
+    pub fn from_name(name: &str) -> Option<SortKey> {
+        match name {
+            "accept-time" => Some(SortKey::AcceptTime),
+            "issue" => Some(SortKey::Issue),
+            "rcv-time" => Some(SortKey::RcvTime),
+            "spread" => Some(SortKey::Spread),
+            _ => None,
+        }
+    }
+
+    pub fn key_fn(&self) -> fn(&PriceQuote, &PriceQuote) -> Ordering {
+        match self {
+            SortKey::AcceptTime => |a, b| a.cmp(b),
+            SortKey::Issue => |a, b| a.issue_code.cmp(&b.issue_code).then_with(|| a.cmp(b)),
+            SortKey::RcvTime => |a, b| {
+                a.packet_rcv_time
+                    .cmp(&b.packet_rcv_time)
+                    .then_with(|| a.cmp(b))
+            },
+            // one sided books have no spread and go last
+            SortKey::Spread => |a, b| match (a.spread(), b.spread()) {
+                (Some(x), Some(y)) => x.cmp(&y).then_with(|| a.cmp(b)),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => a.cmp(b),
+            },
+        }
+    }
+}
+
+// stands in for colored without the cli feature, so Colored comes out the
+// same as Plain
+#[cfg(not(feature = "cli"))]
+trait Colorize {
+    fn red(&self) -> &Self {
+        self
+    }
+
+    fn blue(&self) -> &Self {
+        self
+    }
+
+    fn yellow(&self) -> &Self {
+        self
+    }
+}
+
+#[cfg(not(feature = "cli"))]
+impl<T: fmt::Display + ?Sized> Colorize for T {}
+
+// whether formatted quotes carry ansi color codes. Display is always plain,
+// color has to be asked for. Canonical is plain with every field at a fixed
+// width and times in full, for output that gets diffed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisplayStyle {
+    #[default]
+    Plain,
+    Colored,
+    Canonical,
+}
+
+impl PriceQuote {
+    pub fn fmt_plain(&self) -> String {
+        self.fmt_styled(DisplayStyle::Plain)
+    }
+
+    pub fn fmt_colored(&self) -> String {
+        self.fmt_styled(DisplayStyle::Colored)
+    }
+
+    // prices as plain integers
+    pub fn fmt_styled(&self, style: DisplayStyle) -> String {
+        self.fmt_with(style, PriceFormat::default())
+    }
+
+    pub fn fmt_with(&self, style: DisplayStyle, format: PriceFormat) -> String {
+        let mut result = String::new();
+        self.write_styled(&mut result, style, format)
+            .expect("writing to a string can't fail");
+        result
+    }
+
+    fn write_styled(
+        &self,
+        f: &mut impl fmt::Write,
+        style: DisplayStyle,
+        format: PriceFormat,
+    ) -> fmt::Result {
+        if style == DisplayStyle::Canonical {
+            write!(
+                f,
+                "{} {} {}",
+                fmt_rcv_time_iso(self.packet_rcv_time),
+                fmt_accept_time(self.quote_accept_time),
+                self.issue_code
+            )?;
+            let pairs: Vec<(Price, Qty)> =
+                self.bids.iter().rev().map(|l| (l.price, l.qty)).collect();
+            write!(f, " {}", format_pairs(&pairs, style, format))?;
+            let pairs: Vec<(Price, Qty)> = self.asks.iter().map(|l| (l.price, l.qty)).collect();
+            return write!(f, " {}", format_pairs(&pairs, style, format));
+        }
+
+        // packet time
+        let packet_time_fmt = fmt_rcv_time(self.packet_rcv_time);
+
+        // quote accept time is ascii for some reason. shown as sent, with
+        // '?' for any byte that isn't printable so the slicing below is
+        // always on char boundaries
+        let quote_time_str: String = self
+            .quote_accept_time
+            .to_le_bytes()
+            .iter()
+            .map(|&b| if b.is_ascii_graphic() { b as char } else { '?' })
+            .collect();
         let hours = &quote_time_str[0..2];
         let minutes = &quote_time_str[2..4];
         let seconds = &quote_time_str[4..6];
         let microseconds = &quote_time_str[6..8];
-        let quote_time_fmt = format!("{}:{}:{}.{}", hours, minutes, seconds, microseconds).blue();
-        write!(
-            f,
-            "{} {} {}",
-            packet_time_fmt,
-            quote_time_fmt,
-            self.issue_code.yellow()
-        )?;
-
-        // display best bid prices and quantities
-        let s = format_pairs(&[
-            (self.best_bid_price_5th, self.best_bid_quantity_5th),
-            (self.best_bid_price_4th, self.best_bid_quantity_4th),
-            (self.best_bid_price_3rd, self.best_bid_quantity_3rd),
-            (self.best_bid_price_2nd, self.best_bid_quantity_2nd),
-            (self.best_bid_price_1st, self.best_bid_quantity_1st),
-        ]);
-        write!(f, " {}", s)?;
+        let quote_time_fmt = format!("{}:{}:{}.{}", hours, minutes, seconds, microseconds);
+        match style {
+            DisplayStyle::Plain | DisplayStyle::Canonical => write!(
+                f,
+                "{} {} {}",
+                packet_time_fmt, quote_time_fmt, self.issue_code
+            )?,
+            DisplayStyle::Colored => write!(
+                f,
+                "{} {} {}",
+                packet_time_fmt,
+                quote_time_fmt.blue(),
+                self.issue_code.yellow()
+            )?,
+        }
+
+        // display best bid prices and quantities, worst to best so the
+        // two sides meet in the middle
+        let pairs: Vec<(Price, Qty)> = self.bids.iter().rev().map(|l| (l.price, l.qty)).collect();
+        write!(f, " {}", format_pairs(&pairs, style, format))?;
 
         // display best ask prices and quantities
-        let s = format_pairs(&[
-            (self.best_ask_price_1st, self.best_ask_quantity_1st),
-            (self.best_ask_price_2nd, self.best_ask_quantity_2nd),
-            (self.best_ask_price_3rd, self.best_ask_quantity_3rd),
-            (self.best_ask_price_4th, self.best_ask_quantity_4th),
-            (self.best_ask_price_5th, self.best_ask_quantity_5th),
-        ]);
-        write!(f, " {}", s)?;
+        let pairs: Vec<(Price, Qty)> = self.asks.iter().map(|l| (l.price, l.qty)).collect();
+        write!(f, " {}", format_pairs(&pairs, style, format))?;
 
         Ok(())
     }
 }
 
-pub fn format_pairs(pairs: &[(u64, u64)]) -> String {
-    let mut result = String::new();
+impl fmt::Display for PriceQuote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_styled(f, DisplayStyle::Plain, PriceFormat::default())
+    }
+}
 
-    for (val, qty) in pairs.iter() {
-        // write!(f, " {}{}{}", qty_str, "@".red(), price_str)?;
-        // get the last 2 digits of val
-        let val_start = val / 100;
-        let val_end = val % 100;
+pub fn format_pairs(pairs: &[(Price, Qty)], style: DisplayStyle, format: PriceFormat) -> String {
+    let mut result = String::new();
 
-        result.push_str(&format!(" {}{}{}.{}", qty, "@".red(), val_start, val_end));
+    for (price, qty) in pairs.iter() {
+        let price = price.display(format);
+        match style {
+            DisplayStyle::Plain => result.push_str(&format!(" {}@{}", qty, price)),
+            DisplayStyle::Colored => result.push_str(&format!(" {}{}{}", qty, "@".red(), price)),
+            // as wide as the biggest ascii qty and price
+            DisplayStyle::Canonical => {
+                let price_width = Price(99_999).display(format).to_string().len();
+                result.push_str(&format!(
+                    " {:>7}@{:>price_width$}",
+                    qty.to_string(),
+                    price.to_string()
+                ))
+            }
+        }
     }
 
     result
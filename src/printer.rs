@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use colored::{Color, Colorize};
+
+use crate::price::{Price, PriceFormat};
+use crate::price_quote::{DisplayStyle, PriceQuote};
+
+// how a quote's spread moved compared to the previous quote for its issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpreadChange {
+    First,
+    Narrowed,
+    Widened,
+    Unchanged,
+}
+
+impl SpreadChange {
+    pub fn color(&self) -> Option<Color> {
+        match self {
+            SpreadChange::Narrowed => Some(Color::Green),
+            SpreadChange::Widened => Some(Color::Red),
+            SpreadChange::First | SpreadChange::Unchanged => None,
+        }
+    }
+}
+
+// --spread-thresholds, in ticks of the smallest price step the feed has.
+// spreads up to tight are green, up to wide yellow and past that red
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpreadThresholds {
+    pub tight: u64,
+    pub wide: u64,
+}
+
+impl Default for SpreadThresholds {
+    fn default() -> Self {
+        SpreadThresholds { tight: 2, wide: 10 }
+    }
+}
+
+impl SpreadThresholds {
+    // "tight,wide", with tight no more than wide
+    pub fn parse(text: &str) -> Option<Self> {
+        let (tight, wide) = text.split_once(',')?;
+        let tight = tight.trim().parse().ok()?;
+        let wide = wide.trim().parse().ok()?;
+        (tight <= wide).then_some(SpreadThresholds { tight, wide })
+    }
+
+    pub fn color(&self, spread: Price) -> Color {
+        match spread.raw() {
+            ticks if ticks <= self.tight => Color::Green,
+            ticks if ticks <= self.wide => Color::Yellow,
+            _ => Color::Red,
+        }
+    }
+}
+
+// Display has no memory between quotes, so anything that depends on the
+// previous quote for the same issue goes through here instead
+pub struct QuotePrinter {
+    pub show_spread: bool,
+    // spread colors are only shown when this is Colored too
+    pub style: DisplayStyle,
+    // --color-by-spread. the whole line takes the color of its spread in
+    // place of the usual field colors, again only when style is Colored
+    pub line_colors: Option<SpreadThresholds>,
+    // --price-scale and --price-precision
+    pub price_format: PriceFormat,
+    last_spreads: HashMap<String, Price>,
+}
+
+impl QuotePrinter {
+    pub fn new(show_spread: bool, style: DisplayStyle) -> Self {
+        QuotePrinter {
+            show_spread,
+            style,
+            line_colors: None,
+            price_format: PriceFormat::default(),
+            last_spreads: HashMap::new(),
+        }
+    }
+
+    // records the quote's spread and reports how it moved. None when one
+    // side of the book is empty
+    pub fn observe(&mut self, price_quote: &PriceQuote) -> Option<(Price, SpreadChange)> {
+        let spread = price_quote.spread()?;
+
+        let change = match self
+            .last_spreads
+            .insert(price_quote.issue_code.clone(), spread)
+        {
+            None => SpreadChange::First,
+            Some(last) if spread < last => SpreadChange::Narrowed,
+            Some(last) if spread > last => SpreadChange::Widened,
+            Some(_) => SpreadChange::Unchanged,
+        };
+        Some((spread, change))
+    }
+
+    pub fn format(&mut self, price_quote: &PriceQuote) -> String {
+        let line_colors = self
+            .line_colors
+            .filter(|_| self.style == DisplayStyle::Colored);
+        let style = match line_colors {
+            Some(_) => DisplayStyle::Plain,
+            None => self.style,
+        };
+        let line = if self.show_spread {
+            let spread_fmt = match self.observe(price_quote) {
+                Some((spread, change)) => match (change.color(), style) {
+                    (Some(color), DisplayStyle::Colored) => spread
+                        .display(self.price_format)
+                        .to_string()
+                        .color(color)
+                        .to_string(),
+                    _ => spread.display(self.price_format).to_string(),
+                },
+                None => "-".to_string(),
+            };
+            format!(
+                "{} spread {}",
+                price_quote.fmt_with(style, self.price_format),
+                spread_fmt
+            )
+        } else {
+            price_quote.fmt_with(style, self.price_format)
+        };
+
+        // one sided books have no spread to go by
+        match (line_colors, price_quote.spread()) {
+            (Some(thresholds), Some(spread)) => line.color(thresholds.color(spread)).to_string(),
+            _ => line,
+        }
+    }
+}
@@ -0,0 +1,143 @@
+#[cfg(feature = "chrono")]
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+#[cfg(feature = "chrono")]
+use chrono::NaiveTime;
+
+// a small number standing in for an issue code, so keys are Copy and cheap
+// to hash. only meaningful to the IssueInterner that handed it out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IssueCodeId(u32);
+
+// hands out an IssueCodeId per distinct issue code, in order of first sight
+#[derive(Debug, Clone, Default)]
+pub struct IssueInterner {
+    ids: HashMap<String, IssueCodeId>,
+    codes: Vec<String>,
+}
+
+impl IssueInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, issue_code: &str) -> IssueCodeId {
+        if let Some(id) = self.ids.get(issue_code) {
+            return *id;
+        }
+        let id = IssueCodeId(self.codes.len() as u32);
+        self.codes.push(issue_code.to_string());
+        self.ids.insert(issue_code.to_string(), id);
+        id
+    }
+
+    // the id issue_code already has, without handing out a new one
+    pub fn get(&self, issue_code: &str) -> Option<IssueCodeId> {
+        self.ids.get(issue_code).copied()
+    }
+
+    pub fn resolve(&self, id: IssueCodeId) -> &str {
+        &self.codes[id.0 as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+}
+
+// one quote's identity on the feed: which issue, and where in that issue's
+// sequence. orders by issue (in interning order), then seq
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct QuoteKey {
+    pub issue: IssueCodeId,
+    pub seq: u32,
+}
+
+// a map from QuoteKey that holds at most max_len entries. each entry carries
+// an accept time, and when the map is full the entry with the earliest one
+// is evicted to make room, so a long capture can be indexed in bounded
+// memory as long as what's still needed is recent
+#[cfg(feature = "chrono")]
+#[derive(Debug, Clone)]
+pub struct QuoteIndex<V> {
+    entries: HashMap<QuoteKey, (NaiveTime, V)>,
+    // every entry's accept time and key, earliest first
+    by_time: BTreeSet<(NaiveTime, QuoteKey)>,
+    max_len: usize,
+    evicted: u64,
+}
+
+#[cfg(feature = "chrono")]
+impl<V> QuoteIndex<V> {
+    // max_len is at least one
+    pub fn new(max_len: usize) -> Self {
+        QuoteIndex {
+            entries: HashMap::new(),
+            by_time: BTreeSet::new(),
+            max_len: max_len.max(1),
+            evicted: 0,
+        }
+    }
+
+    // any entry already under key is replaced and returned
+    pub fn insert(&mut self, key: QuoteKey, accept_time: NaiveTime, value: V) -> Option<V> {
+        let old = self.remove(&key);
+        if self.entries.len() >= self.max_len {
+            if let Some((_, oldest)) = self.by_time.pop_first() {
+                self.entries.remove(&oldest);
+                self.evicted += 1;
+            }
+        }
+        self.by_time.insert((accept_time, key));
+        self.entries.insert(key, (accept_time, value));
+        old
+    }
+
+    pub fn get(&self, key: &QuoteKey) -> Option<&V> {
+        self.entries.get(key).map(|(_, value)| value)
+    }
+
+    pub fn get_mut(&mut self, key: &QuoteKey) -> Option<&mut V> {
+        self.entries.get_mut(key).map(|(_, value)| value)
+    }
+
+    pub fn contains_key(&self, key: &QuoteKey) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn remove(&mut self, key: &QuoteKey) -> Option<V> {
+        let (accept_time, value) = self.entries.remove(key)?;
+        self.by_time.remove(&(accept_time, *key));
+        Some(value)
+    }
+
+    // drops everything accepted before time, for when the caller knows
+    // nothing that old can matter any more. not counted as evictions
+    pub fn remove_before(&mut self, time: NaiveTime) {
+        while let Some(&(accept_time, key)) = self.by_time.first() {
+            if accept_time >= time {
+                break;
+            }
+            self.by_time.pop_first();
+            self.entries.remove(&key);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // how many entries were pushed out by the size limit
+    pub fn evicted(&self) -> u64 {
+        self.evicted
+    }
+}
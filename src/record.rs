@@ -0,0 +1,115 @@
+use std::io::{self, Write};
+use std::time::Duration;
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::error::QuoteDecodeError;
+use crate::price::{Price, Qty};
+use crate::price_quote::{Level, PriceQuote, ISSUE_CODE_LEN, LEVELS};
+
+// --output bin writes every quote as one of these back to back, with no
+// header, so a reader can map the file and index straight into it. packed,
+// every number little endian:
+//
+//   offset  size  field
+//        0     8  packet_rcv_time, nanos since the epoch
+//        8     2  data_type
+//       10     2  information_type
+//       12     1  market_type
+//       13    12  issue_code, nul padded
+//       25     4  issue_seq_no
+//       29     2  market_status_type
+//       31     8  total_bid_quote_volume
+//       39     8  total_ask_quote_volume
+//       47   100  bids, best first, each price u64, qty u64, order_count u32
+//      147   100  asks, the same
+//      247     8  no_of_best_bid_valid_quote_total
+//      255     8  no_of_best_ask_valid_quote_total
+//      263     8  quote_accept_time
+pub const RECORD_LEN: usize = 271;
+
+const LEVEL_LEN: usize = 20;
+const ISSUE_CODE_OFFSET: usize = 13;
+const BIDS_OFFSET: usize = 47;
+const ASKS_OFFSET: usize = BIDS_OFFSET + LEVELS * LEVEL_LEN;
+
+// issue codes longer than 12 bytes are cut
+pub fn encode_record(q: &PriceQuote) -> [u8; RECORD_LEN] {
+    let mut record = [0u8; RECORD_LEN];
+    LittleEndian::write_u64(&mut record[0..8], q.packet_rcv_time.as_nanos() as u64);
+    LittleEndian::write_u16(&mut record[8..10], q.data_type);
+    LittleEndian::write_u16(&mut record[10..12], q.information_type);
+    record[12] = q.market_type;
+    let issue_code = q.issue_code.as_bytes();
+    let issue_code = &issue_code[..issue_code.len().min(ISSUE_CODE_LEN)];
+    record[ISSUE_CODE_OFFSET..ISSUE_CODE_OFFSET + issue_code.len()].copy_from_slice(issue_code);
+    LittleEndian::write_u32(&mut record[25..29], q.issue_seq_no);
+    LittleEndian::write_u16(&mut record[29..31], q.market_status_type);
+    LittleEndian::write_u64(&mut record[31..39], q.total_bid_quote_volume.raw());
+    LittleEndian::write_u64(&mut record[39..47], q.total_ask_quote_volume.raw());
+    for (side, offset) in [(&q.bids, BIDS_OFFSET), (&q.asks, ASKS_OFFSET)] {
+        for (level, bytes) in side
+            .iter()
+            .zip(record[offset..offset + LEVELS * LEVEL_LEN].chunks_exact_mut(LEVEL_LEN))
+        {
+            LittleEndian::write_u64(&mut bytes[0..8], level.price.raw());
+            LittleEndian::write_u64(&mut bytes[8..16], level.qty.raw());
+            LittleEndian::write_u32(&mut bytes[16..20], level.order_count);
+        }
+    }
+    LittleEndian::write_u64(&mut record[247..255], q.no_of_best_bid_valid_quote_total);
+    LittleEndian::write_u64(&mut record[255..263], q.no_of_best_ask_valid_quote_total);
+    LittleEndian::write_u64(&mut record[263..271], q.quote_accept_time);
+    record
+}
+
+pub fn write_record<W: Write>(writer: &mut W, q: &PriceQuote) -> io::Result<()> {
+    writer.write_all(&encode_record(q))
+}
+
+// reads back what encode_record wrote. anything past RECORD_LEN is ignored
+pub fn from_record(bytes: &[u8]) -> Result<PriceQuote, QuoteDecodeError> {
+    if bytes.len() < RECORD_LEN {
+        return Err(QuoteDecodeError::Truncated {
+            field: "record",
+            offset: 0,
+            needed: RECORD_LEN,
+            available: bytes.len(),
+        });
+    }
+    let issue_code = &bytes[ISSUE_CODE_OFFSET..ISSUE_CODE_OFFSET + ISSUE_CODE_LEN];
+    let issue_code_len = issue_code
+        .iter()
+        .rposition(|&b| b != 0)
+        .map_or(0, |last| last + 1);
+    let read_side = |offset: usize| {
+        let mut side = [Level::default(); LEVELS];
+        for (level, bytes) in side
+            .iter_mut()
+            .zip(bytes[offset..offset + LEVELS * LEVEL_LEN].chunks_exact(LEVEL_LEN))
+        {
+            *level = Level {
+                price: Price(LittleEndian::read_u64(&bytes[0..8])),
+                qty: Qty(LittleEndian::read_u64(&bytes[8..16])),
+                order_count: LittleEndian::read_u32(&bytes[16..20]),
+            };
+        }
+        side
+    };
+    Ok(PriceQuote {
+        packet_rcv_time: Duration::from_nanos(LittleEndian::read_u64(&bytes[0..8])),
+        data_type: LittleEndian::read_u16(&bytes[8..10]),
+        information_type: LittleEndian::read_u16(&bytes[10..12]),
+        market_type: bytes[12],
+        issue_code: String::from_utf8_lossy(&issue_code[..issue_code_len]).into_owned(),
+        issue_seq_no: LittleEndian::read_u32(&bytes[25..29]),
+        market_status_type: LittleEndian::read_u16(&bytes[29..31]),
+        total_bid_quote_volume: Qty(LittleEndian::read_u64(&bytes[31..39])),
+        total_ask_quote_volume: Qty(LittleEndian::read_u64(&bytes[39..47])),
+        bids: read_side(BIDS_OFFSET),
+        asks: read_side(ASKS_OFFSET),
+        no_of_best_bid_valid_quote_total: LittleEndian::read_u64(&bytes[247..255]),
+        no_of_best_ask_valid_quote_total: LittleEndian::read_u64(&bytes[255..263]),
+        quote_accept_time: LittleEndian::read_u64(&bytes[263..271]),
+    })
+}
@@ -0,0 +1,178 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::price_quote::PriceQuote;
+
+// quote counts grouped by the first prefix_len characters of the issue code.
+// krx codes put the instrument category up front, so this reads as a rough
+// sector breakdown
+pub fn issue_prefix_histogram(
+    price_quotes: &[PriceQuote],
+    prefix_len: usize,
+) -> BTreeMap<String, u64> {
+    let mut histogram = BTreeMap::new();
+    for price_quote in price_quotes {
+        let prefix: String = price_quote.issue_code.chars().take(prefix_len).collect();
+        *histogram.entry(prefix).or_insert(0) += 1;
+    }
+    histogram
+}
+
+pub fn format_issue_prefix_histogram(histogram: &BTreeMap<String, u64>) -> String {
+    let total: u64 = histogram.values().sum();
+    let width = histogram.keys().map(|k| k.len()).max().unwrap_or(0);
+
+    let mut result = String::from("Issue Code Prefixes:\n");
+    for (prefix, count) in histogram {
+        result.push_str(&format!(
+            "  {:<width$} {:>8} ({:.2}%)\n",
+            prefix,
+            count,
+            *count as f64 / total as f64 * 100.0,
+            width = width
+        ));
+    }
+    result
+}
+
+// one line per issue code, sorted, with its quote count right aligned in
+// front. issues with fewer than min_count quotes are left out
+pub fn format_issue_list(counts: &HashMap<String, u64>, min_count: u64) -> String {
+    let mut issues: Vec<(&String, &u64)> = counts
+        .iter()
+        .filter(|(_, count)| **count >= min_count)
+        .collect();
+    issues.sort();
+
+    let mut result = String::new();
+    for (issue_code, count) in issues {
+        result.push_str(&format!("{:>10} {}\n", count, issue_code));
+    }
+    result
+}
+
+// quote counts per issue code in bins equal slices of the capture, from the
+// first quote's capture time to the last's
+pub fn build_heatmap(quotes: &[PriceQuote], bins: usize) -> HashMap<String, Vec<usize>> {
+    let mut heatmap: HashMap<String, Vec<usize>> = HashMap::new();
+    let times = quotes.iter().map(|q| q.packet_rcv_time);
+    let (Some(start), Some(end)) = (times.clone().min(), times.max()) else {
+        return heatmap;
+    };
+    let bins = bins.max(1);
+    let span = (end - start).as_nanos() + 1;
+    for price_quote in quotes {
+        let offset = (price_quote.packet_rcv_time - start).as_nanos();
+        let bin = (offset * bins as u128 / span) as usize;
+        heatmap
+            .entry(price_quote.issue_code.clone())
+            .or_insert_with(|| vec![0; bins])[bin] += 1;
+    }
+    heatmap
+}
+
+// a row of shades per issue, sorted by issue code. shades are scaled to the
+// busiest bin of any issue, so rows compare with each other. empty bins are
+// blank
+pub fn format_heatmap(heatmap: &HashMap<String, Vec<usize>>) -> String {
+    const SHADES: [char; 4] = ['\u{2591}', '\u{2592}', '\u{2593}', '\u{2588}'];
+    let most = heatmap.values().flatten().copied().max().unwrap_or(0);
+    let width = heatmap.keys().map(|k| k.len()).max().unwrap_or(0);
+    let mut issues: Vec<(&String, &Vec<usize>)> = heatmap.iter().collect();
+    issues.sort();
+
+    let mut result = String::from("Quote Heatmap:\n");
+    for (issue_code, counts) in issues {
+        let row: String = counts
+            .iter()
+            .map(|&count| match count {
+                0 => ' ',
+                count => SHADES[(count * SHADES.len()).div_ceil(most) - 1],
+            })
+            .collect();
+        result.push_str(&format!(
+            "  {:<width$} |{}|\n",
+            issue_code,
+            row,
+            width = width
+        ));
+    }
+    result
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct VolumeStats {
+    pub quotes: u64,
+    pub bid_volume: u64,
+    pub ask_volume: u64,
+    // running sums for the volume weighted mid
+    weighted_mid_sum: f64,
+    mid_weight: f64,
+}
+
+impl VolumeStats {
+    pub fn total_volume(&self) -> u64 {
+        self.bid_volume + self.ask_volume
+    }
+
+    // mid price weighted by each quote's total quoted volume. None if no
+    // quote had both sides populated
+    pub fn volume_weighted_mid(&self) -> Option<f64> {
+        if self.mid_weight == 0.0 {
+            return None;
+        }
+        Some(self.weighted_mid_sum / self.mid_weight)
+    }
+}
+
+// the n issues with the most total quoted volume (bid plus ask), largest
+// first. mids are scaled by scale
+pub fn top_by_volume(
+    price_quotes: &[PriceQuote],
+    n: usize,
+    scale: u32,
+) -> Vec<(String, VolumeStats)> {
+    let mut by_issue: HashMap<&str, VolumeStats> = HashMap::new();
+    for price_quote in price_quotes {
+        let stats = by_issue.entry(price_quote.issue_code.as_str()).or_default();
+        let bid_volume = price_quote.total_bid_quote_volume.raw();
+        let ask_volume = price_quote.total_ask_quote_volume.raw();
+        stats.quotes += 1;
+        stats.bid_volume += bid_volume;
+        stats.ask_volume += ask_volume;
+        if let Some(mid) = price_quote.mid_price(scale) {
+            let weight = (bid_volume + ask_volume) as f64;
+            stats.weighted_mid_sum += mid * weight;
+            stats.mid_weight += weight;
+        }
+    }
+
+    let mut ranked: Vec<(String, VolumeStats)> = by_issue
+        .into_iter()
+        .map(|(issue, stats)| (issue.to_string(), stats))
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.1.total_volume()
+            .cmp(&a.1.total_volume())
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    ranked.truncate(n);
+    ranked
+}
+
+pub fn format_top_by_volume(ranked: &[(String, VolumeStats)]) -> String {
+    let mut result = format!(
+        "{:<12} {:>10} {:>14} {:>14} {:>12}\n",
+        "Issue", "Quotes", "Bid Volume", "Ask Volume", "VWAP Mid"
+    );
+    for (issue, stats) in ranked {
+        let vwap = match stats.volume_weighted_mid() {
+            Some(mid) => format!("{:.2}", mid),
+            None => "-".to_string(),
+        };
+        result.push_str(&format!(
+            "{:<12} {:>10} {:>14} {:>14} {:>12}\n",
+            issue, stats.quotes, stats.bid_volume, stats.ask_volume, vwap
+        ));
+    }
+    result
+}
@@ -0,0 +1,104 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use crate::price_quote::PriceQuote;
+
+// turns an irregular stream of quotes into one snapshot per issue per tick.
+// quotes are pushed in capture order and tick is called at each grid time,
+// handing back the latest quote of every issue that quoted since the last
+// tick, stamped with the tick's time. with forward_fill issues that were
+// quiet are handed back too, as long as they've quoted at all
+pub struct Resampler {
+    interval: Duration,
+    forward_fill: bool,
+    // latest quote per issue, in issue code order
+    latest: BTreeMap<String, PriceQuote>,
+    // issues that quoted since the last tick
+    fresh: BTreeSet<String>,
+}
+
+impl Resampler {
+    pub fn new(interval: Duration, forward_fill: bool) -> Self {
+        assert!(!interval.is_zero(), "resample interval can't be zero");
+        Resampler {
+            interval,
+            forward_fill,
+            latest: BTreeMap::new(),
+            fresh: BTreeSet::new(),
+        }
+    }
+
+    pub fn push(&mut self, price_quote: PriceQuote) {
+        self.fresh.insert(price_quote.issue_code.clone());
+        self.latest
+            .insert(price_quote.issue_code.clone(), price_quote);
+    }
+
+    // true if the next tick wouldn't hand anything back
+    pub fn is_idle(&self) -> bool {
+        self.fresh.is_empty() && (!self.forward_fill || self.latest.is_empty())
+    }
+
+    // the first grid time at or after time. the grid starts at the epoch
+    pub fn align(&self, time: Duration) -> Duration {
+        let interval = self.interval.as_nanos();
+        let ticks = time.as_nanos().div_ceil(interval);
+        duration_from_nanos(ticks * interval)
+    }
+
+    // snapshots for the window ending at current_time, in issue code order
+    pub fn tick(&mut self, current_time: Duration) -> Vec<&PriceQuote> {
+        let fresh = std::mem::take(&mut self.fresh);
+        let forward_fill = self.forward_fill;
+        self.latest
+            .iter_mut()
+            .filter(|(issue_code, _)| forward_fill || fresh.contains(*issue_code))
+            .map(|(_, price_quote)| {
+                price_quote.packet_rcv_time = current_time;
+                &*price_quote
+            })
+            .collect()
+    }
+}
+
+fn duration_from_nanos(nanos: u128) -> Duration {
+    Duration::new(
+        (nanos / 1_000_000_000) as u64,
+        (nanos % 1_000_000_000) as u32,
+    )
+}
+
+// --resample-to-ticks over quotes already in memory. a quote belongs to the
+// first tick at or after it arrived, ticks run from the first quote's to the
+// last one's. empty windows are skipped over without ticking unless there's
+// something to forward fill
+pub fn resample(
+    price_quotes: &[PriceQuote],
+    interval: Duration,
+    forward_fill: bool,
+) -> Vec<PriceQuote> {
+    let mut by_time: Vec<&PriceQuote> = price_quotes.iter().collect();
+    by_time.sort_by_key(|q| q.packet_rcv_time);
+
+    let mut resampler = Resampler::new(interval, forward_fill);
+    let mut snapshots = vec![];
+    let mut next_tick = None;
+    for price_quote in by_time {
+        let rcv_time = price_quote.packet_rcv_time;
+        let mut tick = next_tick.unwrap_or_else(|| resampler.align(rcv_time));
+        while tick < rcv_time {
+            if resampler.is_idle() {
+                tick = resampler.align(rcv_time);
+                break;
+            }
+            snapshots.extend(resampler.tick(tick).into_iter().cloned());
+            tick += interval;
+        }
+        next_tick = Some(tick);
+        resampler.push(price_quote.clone());
+    }
+    if let Some(tick) = next_tick {
+        snapshots.extend(resampler.tick(tick).into_iter().cloned());
+    }
+    snapshots
+}
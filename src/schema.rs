@@ -0,0 +1,61 @@
+use std::io::{self, Write};
+
+#[cfg(feature = "json-schema")]
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[cfg(feature = "json-schema")]
+use crate::PriceQuote;
+
+// every json object the crate writes carries these two, so consumers can
+// tell which layout they're reading. bump JSON_SCHEMA_VERSION whenever a
+// field is renamed, removed or changes meaning; adding a field doesn't need
+// it
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+pub const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// value's own fields, after the version fields
+#[derive(Serialize)]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+pub struct Versioned<'a, T> {
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(
+            range(min = 1),
+            description = "Layout of this object, see JSON_SCHEMA_VERSION"
+        )
+    )]
+    pub schema_version: u32,
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(description = "Version of the crate that wrote it")
+    )]
+    pub crate_version: &'static str,
+    #[serde(flatten)]
+    pub value: &'a T,
+}
+
+impl<'a, T> Versioned<'a, T> {
+    pub fn new(value: &'a T) -> Self {
+        Versioned {
+            schema_version: JSON_SCHEMA_VERSION,
+            crate_version: CRATE_VERSION,
+            value,
+        }
+    }
+}
+
+// value as one line of json lines output
+pub fn write_json_line<W: Write, T: Serialize>(writer: &mut W, value: &T) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, &Versioned::new(value))?;
+    writer.write_all(b"\n")
+}
+
+// JSON Schema (draft 2020-12) of a quote as write_json_line writes it, for
+// --json-schema
+#[cfg(feature = "json-schema")]
+pub fn price_quote_json_schema() -> schemars::Schema {
+    let mut schema = schemars::schema_for!(Versioned<'static, PriceQuote>);
+    schema.insert("title".to_string(), "PriceQuote".into());
+    schema
+}
@@ -0,0 +1,88 @@
+use mlua::{Function, Lua, Table};
+
+use crate::error::ScriptError;
+use crate::price_quote::{Level, PriceQuote};
+
+// --filter-script, a lua script defining filter(quote) that says which quotes
+// to keep. anything but false or nil keeps the quote. the quote is a table:
+//   issue_code, country_code, seq, market, market_status (the codes),
+//   rcv_time (capture seconds), accept_time ("HH:MM:SS.mmm"),
+//   total_bid_quote_volume, total_ask_quote_volume, spread, mid_price,
+//   bids and asks (best first, each {price, qty, order_count})
+// prices are numbers with --price-scale applied. fields a quote doesn't
+// have, like the spread of a one sided book, are nil
+pub struct ScriptFilter {
+    lua: Lua,
+    filter: Function,
+    scale: u32,
+}
+
+impl ScriptFilter {
+    // scale is --price-scale
+    pub fn load(path: &str, scale: u32) -> Result<Self, ScriptError> {
+        let source = std::fs::read_to_string(path).map_err(|source| ScriptError::Open {
+            path: path.to_string(),
+            source,
+        })?;
+        Self::from_source(path, &source, scale)
+    }
+
+    // name is what lua error messages call the script
+    pub fn from_source(name: &str, source: &str, scale: u32) -> Result<Self, ScriptError> {
+        let lua = Lua::new();
+        lua.load(source).set_name(name).exec()?;
+        let filter = lua
+            .globals()
+            .get::<Option<Function>>("filter")?
+            .ok_or(ScriptError::NoFilter)?;
+        Ok(ScriptFilter { lua, filter, scale })
+    }
+
+    pub fn keep(&self, quote: &PriceQuote) -> Result<bool, ScriptError> {
+        Ok(self.filter.call::<bool>(self.quote_table(quote)?)?)
+    }
+
+    fn quote_table(&self, quote: &PriceQuote) -> mlua::Result<Table> {
+        let table = self.lua.create_table()?;
+        table.set("issue_code", quote.issue_code.as_str())?;
+        table.set("country_code", quote.country_code())?;
+        table.set("seq", quote.seq())?;
+        table.set("market", quote.market().code())?;
+        table.set("market_status", quote.market_status().code())?;
+        table.set("rcv_time", quote.packet_rcv_time.as_secs_f64())?;
+        table.set(
+            "accept_time",
+            quote
+                .accept_time()
+                .map(|t| t.format("%H:%M:%S%.3f").to_string()),
+        )?;
+        table.set(
+            "total_bid_quote_volume",
+            quote.total_bid_quote_volume.raw() as i64,
+        )?;
+        table.set(
+            "total_ask_quote_volume",
+            quote.total_ask_quote_volume.raw() as i64,
+        )?;
+        table.set(
+            "spread",
+            quote.spread().map(|spread| spread.to_f64(self.scale)),
+        )?;
+        table.set("mid_price", quote.mid_price(self.scale))?;
+        table.set("bids", self.levels_table(&quote.bids)?)?;
+        table.set("asks", self.levels_table(&quote.asks)?)?;
+        Ok(table)
+    }
+
+    fn levels_table(&self, levels: &[Level]) -> mlua::Result<Table> {
+        let table = self.lua.create_table()?;
+        for level in levels {
+            let entry = self.lua.create_table()?;
+            entry.set("price", level.price.to_f64(self.scale))?;
+            entry.set("qty", level.qty.raw() as i64)?;
+            entry.set("order_count", level.orders())?;
+            table.push(entry)?;
+        }
+        Ok(table)
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{NaiveTime, TimeZone, Utc};
+
+use crate::price_quote::PriceQuote;
+
+// everything --session-stats reports for one issue, built up one quote at a
+// time in capture order
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssueSessionStats {
+    pub quotes: u64,
+    pub first_rcv_time: Duration,
+    pub last_rcv_time: Duration,
+    // earliest and latest accept times, which needn't be the first and last
+    // quotes to arrive. None until a quote with a readable one turns up
+    pub first_accept_time: Option<NaiveTime>,
+    pub last_accept_time: Option<NaiveTime>,
+    pub bid_volume: u64,
+    pub ask_volume: u64,
+    pub max_quotes_per_second: u64,
+
+    // spread of the last quote, held until the next one says how long it
+    // lasted. raw price units
+    last_spread: Option<f64>,
+    spread_time_sum: f64,
+    spread_time: f64,
+
+    // the capture second currently being counted and its quotes so far
+    bucket_second: u64,
+    bucket_quotes: u64,
+}
+
+impl IssueSessionStats {
+    fn new(price_quote: &PriceQuote) -> Self {
+        let rcv_time = price_quote.packet_rcv_time;
+        IssueSessionStats {
+            quotes: 0,
+            first_rcv_time: rcv_time,
+            last_rcv_time: rcv_time,
+            first_accept_time: None,
+            last_accept_time: None,
+            bid_volume: 0,
+            ask_volume: 0,
+            max_quotes_per_second: 0,
+            last_spread: None,
+            spread_time_sum: 0.0,
+            spread_time: 0.0,
+            bucket_second: rcv_time.as_secs(),
+            bucket_quotes: 0,
+        }
+    }
+
+    fn observe(&mut self, price_quote: &PriceQuote) {
+        let rcv_time = price_quote.packet_rcv_time;
+
+        // each spread counts for as long as it stood
+        if let Some(spread) = self.last_spread {
+            let held = rcv_time.saturating_sub(self.last_rcv_time).as_secs_f64();
+            self.spread_time_sum += spread * held;
+            self.spread_time += held;
+        }
+        self.last_spread = price_quote.spread().map(|spread| spread.raw() as f64);
+
+        if rcv_time.as_secs() != self.bucket_second {
+            self.bucket_second = rcv_time.as_secs();
+            self.bucket_quotes = 0;
+        }
+        self.bucket_quotes += 1;
+        self.max_quotes_per_second = self.max_quotes_per_second.max(self.bucket_quotes);
+
+        if let Some(accept_time) = price_quote.accept_time() {
+            self.first_accept_time = Some(
+                self.first_accept_time
+                    .map_or(accept_time, |t| t.min(accept_time)),
+            );
+            self.last_accept_time = Some(
+                self.last_accept_time
+                    .map_or(accept_time, |t| t.max(accept_time)),
+            );
+        }
+
+        self.quotes += 1;
+        self.last_rcv_time = rcv_time;
+        self.bid_volume += price_quote.total_bid_quote_volume.raw();
+        self.ask_volume += price_quote.total_ask_quote_volume.raw();
+    }
+
+    // None with fewer than two quotes
+    pub fn mean_interval_ms(&self) -> Option<f64> {
+        if self.quotes < 2 {
+            return None;
+        }
+        let span = self.last_rcv_time.saturating_sub(self.first_rcv_time);
+        Some(span.as_secs_f64() * 1000.0 / (self.quotes - 1) as f64)
+    }
+
+    // spread weighted by how long each one was on the book, scaled by
+    // scale. the last quote's spread has no duration yet so it doesn't count
+    pub fn time_weighted_spread(&self, scale: u32) -> Option<f64> {
+        if self.spread_time == 0.0 {
+            return None;
+        }
+        Some(self.spread_time_sum / self.spread_time / 10f64.powi(scale as i32))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SessionStats {
+    pub issues: BTreeMap<String, IssueSessionStats>,
+    // --price-scale, what the spreads are printed in
+    pub price_scale: u32,
+}
+
+impl SessionStats {
+    pub fn new(price_scale: u32) -> Self {
+        SessionStats {
+            issues: BTreeMap::new(),
+            price_scale,
+        }
+    }
+
+    pub fn from_quotes(price_quotes: &[PriceQuote], price_scale: u32) -> Self {
+        let mut session_stats = Self::new(price_scale);
+        for price_quote in price_quotes {
+            session_stats.observe(price_quote);
+        }
+        session_stats
+    }
+
+    pub fn observe(&mut self, price_quote: &PriceQuote) {
+        self.issues
+            .entry(price_quote.issue_code.clone())
+            .or_insert_with(|| IssueSessionStats::new(price_quote))
+            .observe(price_quote);
+    }
+
+    // --first-last-seen, a quick check of which issues a capture covers and
+    // over what part of the day, sorted by issue
+    pub fn format_first_last_seen(&self) -> String {
+        let fmt_time = |time: Option<NaiveTime>| match time {
+            Some(time) => time.format("%H:%M:%S%.3f").to_string(),
+            None => "-".to_string(),
+        };
+        let mut result = format!(
+            "{:<12} {:>12} {:>12} {:>8}\n",
+            "Issue", "First Seen", "Last Seen", "Updates"
+        );
+        for (issue, stats) in &self.issues {
+            result.push_str(&format!(
+                "{:<12} {:>12} {:>12} {:>8}\n",
+                issue,
+                fmt_time(stats.first_accept_time),
+                fmt_time(stats.last_accept_time),
+                stats.quotes
+            ));
+        }
+        result
+    }
+}
+
+impl fmt::Display for SessionStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fmt_time =
+            |time: Duration| match Utc.timestamp_opt(time.as_secs() as i64, time.subsec_nanos()) {
+                chrono::LocalResult::Single(dt) => dt.format("%H:%M:%S%.3f").to_string(),
+                _ => "-".to_string(),
+            };
+        let fmt_optional = |value: Option<f64>| match value {
+            Some(value) => format!("{:.2}", value),
+            None => "-".to_string(),
+        };
+
+        writeln!(
+            f,
+            "{:<12} {:>8} {:>12} {:>12} {:>12} {:>12} {:>12} {:>10} {:>8}",
+            "Issue",
+            "Quotes",
+            "First",
+            "Last",
+            "Interval ms",
+            "Bid Volume",
+            "Ask Volume",
+            "TW Spread",
+            "Max/s"
+        )?;
+        for (issue, stats) in &self.issues {
+            writeln!(
+                f,
+                "{:<12} {:>8} {:>12} {:>12} {:>12} {:>12} {:>12} {:>10} {:>8}",
+                issue,
+                stats.quotes,
+                fmt_time(stats.first_rcv_time),
+                fmt_time(stats.last_rcv_time),
+                fmt_optional(stats.mean_interval_ms()),
+                stats.bid_volume,
+                stats.ask_volume,
+                fmt_optional(stats.time_weighted_spread(self.price_scale)),
+                stats.max_quotes_per_second
+            )?;
+        }
+        Ok(())
+    }
+}
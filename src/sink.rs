@@ -0,0 +1,5 @@
+// places quotes go that aren't files
+#[cfg(feature = "redis")]
+pub mod redis;
+#[cfg(feature = "websocket")]
+pub mod websocket;
@@ -0,0 +1,97 @@
+use redis::streams::StreamMaxlen;
+use redis::{Client, Commands, Connection, RedisResult};
+
+use crate::price_quote::{
+    PriceQuote, ASK_COUNT_FIELDS, ASK_PRICE_FIELDS, ASK_QTY_FIELDS, BID_COUNT_FIELDS,
+    BID_PRICE_FIELDS, BID_QTY_FIELDS, LEVELS,
+};
+
+// adds every quote to a redis stream as its own entry, for consumers
+// reading with XREAD. one XADD per quote, so a quote is readable as soon as
+// send returns
+pub struct RedisSink {
+    connection: Connection,
+    stream: String,
+    // --redis-maxlen, trimmed with MAXLEN ~ so redis can trim whole nodes
+    maxlen: Option<usize>,
+}
+
+impl RedisSink {
+    pub fn connect(url: &str, stream: &str, maxlen: Option<usize>) -> RedisResult<RedisSink> {
+        let connection = Client::open(url)?.get_connection()?;
+        Ok(RedisSink {
+            connection,
+            stream: stream.to_string(),
+            maxlen,
+        })
+    }
+
+    // the entry id is left to redis
+    pub fn send(&mut self, price_quote: &PriceQuote) -> RedisResult<()> {
+        let fields = entry_fields(price_quote);
+        match self.maxlen {
+            Some(maxlen) => self.connection.xadd_maxlen(
+                &self.stream,
+                StreamMaxlen::Approx(maxlen),
+                "*",
+                &fields,
+            ),
+            None => self.connection.xadd(&self.stream, "*", &fields),
+        }
+    }
+}
+
+// one field per PriceQuote field, with the levels flattened out under their
+// wire names. values are the raw fields, prices unscaled, and the capture
+// time is seconds.nanoseconds since the epoch
+pub fn entry_fields(price_quote: &PriceQuote) -> Vec<(&'static str, String)> {
+    let rcv_time = price_quote.packet_rcv_time;
+    let mut fields = vec![
+        (
+            "packet_rcv_time",
+            format!("{}.{:09}", rcv_time.as_secs(), rcv_time.subsec_nanos()),
+        ),
+        ("data_type", price_quote.data_type.to_string()),
+        ("information_type", price_quote.information_type.to_string()),
+        ("market_type", price_quote.market_type.to_string()),
+        ("issue_code", price_quote.issue_code.clone()),
+        ("issue_seq_no", price_quote.issue_seq_no.to_string()),
+        (
+            "market_status_type",
+            price_quote.market_status_type.to_string(),
+        ),
+        (
+            "total_bid_quote_volume",
+            price_quote.total_bid_quote_volume.to_string(),
+        ),
+        (
+            "total_ask_quote_volume",
+            price_quote.total_ask_quote_volume.to_string(),
+        ),
+    ];
+    for i in 0..LEVELS {
+        let level = &price_quote.bids[i];
+        fields.push((BID_PRICE_FIELDS[i], level.price.raw().to_string()));
+        fields.push((BID_QTY_FIELDS[i], level.qty.to_string()));
+        fields.push((BID_COUNT_FIELDS[i], level.order_count.to_string()));
+    }
+    for i in 0..LEVELS {
+        let level = &price_quote.asks[i];
+        fields.push((ASK_PRICE_FIELDS[i], level.price.raw().to_string()));
+        fields.push((ASK_QTY_FIELDS[i], level.qty.to_string()));
+        fields.push((ASK_COUNT_FIELDS[i], level.order_count.to_string()));
+    }
+    fields.push((
+        "no_of_best_bid_valid_quote_total",
+        price_quote.no_of_best_bid_valid_quote_total.to_string(),
+    ));
+    fields.push((
+        "no_of_best_ask_valid_quote_total",
+        price_quote.no_of_best_ask_valid_quote_total.to_string(),
+    ));
+    fields.push((
+        "quote_accept_time",
+        price_quote.quote_accept_time.to_string(),
+    ));
+    fields
+}
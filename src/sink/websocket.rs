@@ -0,0 +1,138 @@
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Runtime;
+use tokio::sync::{mpsc, watch};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::schema::Versioned;
+use crate::PriceQuote;
+
+// quotes a client can fall behind by before send waits for it. a slow
+// client slows the replay down rather than missing quotes
+const CLIENT_BUFFER: usize = 4096;
+// how long close gives clients to take the quotes still queued for them
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// serves websocket connections on localhost and sends every quote to all of
+// them as a json text message, laid out like a line of the jsonl output.
+// the server runs on its own threads, send and close are called from
+// ordinary code
+pub struct WsServer {
+    runtime: Runtime,
+    local_addr: SocketAddr,
+    clients: Arc<Mutex<Vec<mpsc::Sender<Message>>>>,
+    // how many clients are connected
+    connected: Arc<watch::Sender<usize>>,
+}
+
+impl WsServer {
+    // port 0 picks a free one, see local_addr
+    pub fn bind(port: u16) -> io::Result<WsServer> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .enable_all()
+            .build()?;
+        let listener = runtime.block_on(TcpListener::bind((Ipv4Addr::LOCALHOST, port)))?;
+        let local_addr = listener.local_addr()?;
+        let clients = Arc::new(Mutex::new(vec![]));
+        let connected = Arc::new(watch::Sender::new(0));
+        runtime.spawn(accept_clients(listener, clients.clone(), connected.clone()));
+        Ok(WsServer {
+            runtime,
+            local_addr,
+            clients,
+            connected,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn client_count(&self) -> usize {
+        *self.connected.borrow()
+    }
+
+    // blocks until someone has connected, so a replay isn't over before
+    // anyone sees it
+    pub fn wait_for_client(&self) {
+        let mut connected = self.connected.subscribe();
+        let _ = self.runtime.block_on(connected.wait_for(|&n| n > 0));
+    }
+
+    // clients that have gone away are dropped here
+    pub fn send(&self, price_quote: &PriceQuote) {
+        let json =
+            serde_json::to_string(&Versioned::new(price_quote)).expect("quotes always serialize");
+        let message = Message::text(json);
+        let mut clients = self.clients.lock().unwrap();
+        clients.retain(|client| client.blocking_send(message.clone()).is_ok());
+    }
+
+    // sends what's still queued, then a close frame to every client
+    pub fn close(self) {
+        self.clients.lock().unwrap().clear();
+        let mut connected = self.connected.subscribe();
+        self.runtime.block_on(async {
+            let _ = tokio::time::timeout(CLOSE_TIMEOUT, connected.wait_for(|&n| n == 0)).await;
+        });
+    }
+}
+
+async fn accept_clients(
+    listener: TcpListener,
+    clients: Arc<Mutex<Vec<mpsc::Sender<Message>>>>,
+    connected: Arc<watch::Sender<usize>>,
+) {
+    loop {
+        // a failed accept is about that one connection, keep listening
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        tokio::spawn(serve_client(stream, clients.clone(), connected.clone()));
+    }
+}
+
+async fn serve_client(
+    stream: TcpStream,
+    clients: Arc<Mutex<Vec<mpsc::Sender<Message>>>>,
+    connected: Arc<watch::Sender<usize>>,
+) {
+    // not a websocket client, nothing to tell it
+    let Ok(socket) = tokio_tungstenite::accept_async(stream).await else {
+        return;
+    };
+    let (sender, mut quotes) = mpsc::channel(CLIENT_BUFFER);
+    clients.lock().unwrap().push(sender);
+    connected.send_modify(|n| *n += 1);
+
+    let (mut write, mut read) = socket.split();
+    loop {
+        tokio::select! {
+            quote = quotes.recv() => match quote {
+                Some(message) => {
+                    if write.send(message).await.is_err() {
+                        break;
+                    }
+                }
+                // the server is done
+                None => {
+                    let _ = write.close().await;
+                    break;
+                }
+            },
+            // what clients send is ignored, but reading is how a close or a
+            // dropped connection shows up
+            incoming = read.next() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    connected.send_modify(|n| *n -= 1);
+}
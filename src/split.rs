@@ -0,0 +1,159 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+
+use crate::price_quote::PriceQuote;
+use crate::schema::write_json_line;
+
+// --output-dir, every issue's quotes as json lines in <dir>/<issue_code>.jsonl.
+//
+// at most max_open files are kept open (at least one). when another issue needs one the
+// least recently written file is flushed and closed, and reopened for append
+// if that issue shows up again. so any number of issues works, but a feed
+// that hops between more issues than max_open pays an open and close per
+// quote. files already in the directory are truncated the first time their
+// issue is written in a run
+pub struct IssueSplitter {
+    dir: PathBuf,
+    max_open: usize,
+    writers: HashMap<String, BufWriter<File>>,
+    // open issues, least recently written first
+    recency: VecDeque<String>,
+    started: HashSet<String>,
+}
+
+impl IssueSplitter {
+    pub fn new(dir: impl AsRef<Path>, max_open: usize) -> io::Result<Self> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        Ok(IssueSplitter {
+            dir: dir.as_ref().to_path_buf(),
+            max_open: max_open.max(1),
+            writers: HashMap::new(),
+            recency: VecDeque::new(),
+            started: HashSet::new(),
+        })
+    }
+
+    pub fn path_for(&self, issue_code: &str) -> PathBuf {
+        // issue codes are isins, but don't let a bad one escape the directory
+        let name: String = issue_code
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        self.dir.join(format!("{}.jsonl", name))
+    }
+
+    pub fn write(&mut self, quote: &PriceQuote) -> io::Result<()> {
+        let issue_code = &quote.issue_code;
+        if self.writers.contains_key(issue_code) {
+            self.recency.retain(|open| open != issue_code);
+        } else {
+            if self.writers.len() >= self.max_open {
+                if let Some(oldest) = self.recency.pop_front() {
+                    if let Some(mut writer) = self.writers.remove(&oldest) {
+                        writer.flush()?;
+                    }
+                }
+            }
+            let first = self.started.insert(issue_code.clone());
+            let file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(first)
+                .append(!first)
+                .open(self.path_for(issue_code))?;
+            self.writers
+                .insert(issue_code.clone(), BufWriter::new(file));
+        }
+        self.recency.push_back(issue_code.clone());
+
+        let writer = self.writers.get_mut(issue_code).unwrap();
+        write_json_line(writer, quote)
+    }
+
+    // how many issues have been written so far
+    pub fn issue_count(&self) -> usize {
+        self.started.len()
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        for writer in self.writers.values_mut() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+// --split-by-date, quotes as json lines in <dir>/<YYYY-MM-DD>.jsonl by the
+// date they were captured on in tz.
+//
+// the date comes from the pcap timestamp, never the accept time, which only
+// has a time of day and wraps at midnight. captures are expected in time
+// order, so only the current date's file is open and it's closed as soon as
+// the next date shows up. a date that comes back later is appended to
+pub struct DateSplitter {
+    dir: PathBuf,
+    tz: Tz,
+    current: Option<(NaiveDate, BufWriter<File>)>,
+    // every date written so far, in the order they were first seen
+    dates: Vec<NaiveDate>,
+}
+
+impl DateSplitter {
+    pub fn new(dir: impl AsRef<Path>, tz: Tz) -> io::Result<Self> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        Ok(DateSplitter {
+            dir: dir.as_ref().to_path_buf(),
+            tz,
+            current: None,
+            dates: vec![],
+        })
+    }
+
+    pub fn path_for(&self, date: NaiveDate) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", date.format("%Y-%m-%d")))
+    }
+
+    pub fn write(&mut self, quote: &PriceQuote) -> io::Result<()> {
+        let date = quote.capture_date(self.tz).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("capture time {:?} is out of range", quote.packet_rcv_time),
+            )
+        })?;
+        if !matches!(&self.current, Some((open, _)) if *open == date) {
+            if let Some((_, mut writer)) = self.current.take() {
+                writer.flush()?;
+            }
+            let first = !self.dates.contains(&date);
+            let file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(first)
+                .append(!first)
+                .open(self.path_for(date))?;
+            if first {
+                self.dates.push(date);
+            }
+            self.current = Some((date, BufWriter::new(file)));
+        }
+
+        let (_, writer) = self.current.as_mut().unwrap();
+        write_json_line(writer, quote)
+    }
+
+    pub fn dates(&self) -> &[NaiveDate] {
+        &self.dates
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        match &mut self.current {
+            Some((_, writer)) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::time::Duration;
+
+use futures_core::Stream;
+use tokio::io::{AsyncRead, ReadBuf};
+
+use crate::error::QuoteStreamError;
+use crate::parser::{B6034Parser, PacketParser};
+use crate::parsing::{quote_parser, screen_quote, InputFormat, PacketLoop, Stopwatch};
+use crate::{PacketParseStats, ParseOptions, PriceQuote};
+
+const PCAP_HEADER_LEN: usize = 24;
+const RECORD_HEADER_LEN: usize = 16;
+// way past any real snaplen. a bigger record means the file is garbage and
+// buffering it would just eat memory
+const MAX_RECORD_LEN: u32 = 256 * 1024;
+const READ_CHUNK: usize = 16 * 1024;
+
+// parses quotes as the bytes come in, with the same classification, filters
+// and stats as parse_price_quotes_with. pcapng isn't supported, and neither
+// is options.event_log, writing it would block. the stream ends after the
+// first error
+pub fn quote_stream<R: AsyncRead + Unpin>(reader: R, options: ParseOptions) -> QuoteStream<R> {
+    QuoteStream {
+        reader,
+        buf: vec![],
+        start: 0,
+        eof: false,
+        framing: Framing::Detect,
+        packet_loop: PacketLoop::new(quote_parser(&options), options),
+        started: Stopwatch::start(),
+        done: false,
+    }
+}
+
+enum Framing {
+    // haven't seen enough bytes to know what the input is yet
+    Detect,
+    Pcap { big_endian: bool, nanos: bool },
+    Raw,
+}
+
+// what the framer made of the bytes buffered so far
+enum Step {
+    Quote(Box<PriceQuote>),
+    // consumed a packet that didn't produce a quote
+    Skipped,
+    NeedMore,
+    End,
+}
+
+pub struct QuoteStream<R> {
+    reader: R,
+    buf: Vec<u8>,
+    // bytes of buf already consumed
+    start: usize,
+    eof: bool,
+    framing: Framing,
+    packet_loop: PacketLoop<B6034Parser>,
+    started: Stopwatch,
+    done: bool,
+}
+
+impl<R> QuoteStream<R> {
+    // counts so far. complete once the stream has ended
+    pub fn stats(&self) -> &PacketParseStats {
+        &self.packet_loop.parse_stats
+    }
+
+    pub fn into_stats(self) -> PacketParseStats {
+        self.packet_loop.parse_stats
+    }
+
+    fn step(&mut self) -> Result<Step, QuoteStreamError> {
+        let available = &self.buf[self.start..];
+        match self.framing {
+            Framing::Detect => {
+                if available.len() < PCAP_HEADER_LEN && !self.eof {
+                    return Ok(Step::NeedMore);
+                }
+                let format = match self.packet_loop.options.input_format {
+                    Some(format) => format,
+                    None => InputFormat::detect(&self.packet_loop.parser, available),
+                };
+                self.framing = match format {
+                    InputFormat::PcapNg => return Err(QuoteStreamError::UnsupportedPcapNg),
+                    InputFormat::Raw => Framing::Raw,
+                    InputFormat::Pcap => {
+                        if available.len() < PCAP_HEADER_LEN {
+                            return Err(QuoteStreamError::TruncatedHeader);
+                        }
+                        let magic = u32::from_le_bytes(available[..4].try_into().unwrap());
+                        let framing = match magic {
+                            0xa1b2c3d4 => Framing::Pcap {
+                                big_endian: false,
+                                nanos: false,
+                            },
+                            0xa1b23c4d => Framing::Pcap {
+                                big_endian: false,
+                                nanos: true,
+                            },
+                            0xd4c3b2a1 => Framing::Pcap {
+                                big_endian: true,
+                                nanos: false,
+                            },
+                            0x4d3cb2a1 => Framing::Pcap {
+                                big_endian: true,
+                                nanos: true,
+                            },
+                            _ => return Err(QuoteStreamError::BadMagic { magic }),
+                        };
+                        self.start += PCAP_HEADER_LEN;
+                        framing
+                    }
+                };
+                Ok(Step::Skipped)
+            }
+            Framing::Pcap { big_endian, nanos } => {
+                if available.is_empty() && self.eof {
+                    return Ok(Step::End);
+                }
+                let packet_index = self.packet_loop.parse_stats.packet_count + 1;
+                let truncated = QuoteStreamError::TruncatedRecord { packet_index };
+                if available.len() < RECORD_HEADER_LEN {
+                    return if self.eof {
+                        Err(truncated)
+                    } else {
+                        Ok(Step::NeedMore)
+                    };
+                }
+                let field = |i: usize| {
+                    let bytes = available[i * 4..i * 4 + 4].try_into().unwrap();
+                    if big_endian {
+                        u32::from_be_bytes(bytes)
+                    } else {
+                        u32::from_le_bytes(bytes)
+                    }
+                };
+                let (secs, frac, incl_len, orig_len) = (field(0), field(1), field(2), field(3));
+                if incl_len > MAX_RECORD_LEN {
+                    return Err(QuoteStreamError::RecordTooLarge {
+                        packet_index,
+                        len: incl_len,
+                    });
+                }
+                let record_len = RECORD_HEADER_LEN + incl_len as usize;
+                if available.len() < record_len {
+                    return if self.eof {
+                        Err(truncated)
+                    } else {
+                        Ok(Step::NeedMore)
+                    };
+                }
+                let subsec_nanos = if nanos {
+                    frac
+                } else {
+                    frac.saturating_mul(1000)
+                };
+                let timestamp =
+                    Duration::new(secs as u64, 0) + Duration::from_nanos(subsec_nanos as u64);
+
+                let frame = &self.buf[self.start + RECORD_HEADER_LEN..self.start + record_len];
+                self.start += record_len;
+                let message =
+                    self.packet_loop
+                        .handle_frame(timestamp, frame, orig_len, record_len as u64)?;
+                Ok(self.screen(message))
+            }
+            Framing::Raw => {
+                let message_len = self.packet_loop.parser.message_len();
+                if available.len() < message_len && !self.eof {
+                    return Ok(Step::NeedMore);
+                }
+                if available.is_empty() {
+                    return Ok(Step::End);
+                }
+                let len = available.len().min(message_len);
+                let payload = &self.buf[self.start..self.start + len];
+                self.start += len;
+                let message = self.packet_loop.handle_raw(payload)?;
+                Ok(self.screen(message))
+            }
+        }
+    }
+
+    fn screen(&mut self, message: Option<Box<dyn std::any::Any>>) -> Step {
+        let Some(message) = message else {
+            return Step::Skipped;
+        };
+        let price_quote = message
+            .downcast::<PriceQuote>()
+            .expect("B6034Parser only produces price quotes");
+        let packet_loop = &mut self.packet_loop;
+        if screen_quote(
+            &packet_loop.options,
+            &mut packet_loop.parse_stats,
+            &price_quote,
+        ) {
+            Step::Quote(price_quote)
+        } else {
+            Step::Skipped
+        }
+    }
+
+    fn finish(&mut self) {
+        self.done = true;
+        self.packet_loop.parse_stats.parse_time = self.started.elapsed();
+    }
+}
+
+impl<R: AsyncRead + Unpin> QuoteStream<R> {
+    fn poll_fill(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // drop what's been consumed before it piles up
+        if self.start > 0 {
+            self.buf.drain(..self.start);
+            self.start = 0;
+        }
+        let mut chunk = [0u8; READ_CHUNK];
+        let mut read_buf = ReadBuf::new(&mut chunk);
+        let reader = Pin::new(&mut self.reader);
+        // one clock read either side of a whole chunk
+        let stopwatch = Stopwatch::start();
+        let read = reader.poll_read(cx, &mut read_buf);
+        self.packet_loop.parse_stats.io_time += stopwatch.elapsed();
+        ready!(read)?;
+        let filled = read_buf.filled();
+        if filled.is_empty() {
+            self.eof = true;
+        }
+        self.buf.extend_from_slice(filled);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for QuoteStream<R> {
+    type Item = Result<PriceQuote, QuoteStreamError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if this.done {
+                return Poll::Ready(None);
+            }
+            match this.step() {
+                Ok(Step::Quote(price_quote)) => return Poll::Ready(Some(Ok(*price_quote))),
+                Ok(Step::Skipped) => {}
+                Ok(Step::NeedMore) => {
+                    if let Err(e) = ready!(this.poll_fill(cx)) {
+                        this.finish();
+                        return Poll::Ready(Some(Err(e.into())));
+                    }
+                }
+                Ok(Step::End) => this.finish(),
+                Err(e) => {
+                    this.finish();
+                    return Poll::Ready(Some(Err(e)));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::io::Write;
+use std::time::Duration;
+
+use etherparse::PacketBuilder;
+use pcap_file::pcap::{PcapPacket, PcapWriter};
+use pcap_file::PcapError;
+
+use crate::builder::PriceQuoteBuilder;
+use crate::price_quote::PriceQuote;
+
+// pcap captures built in memory for tests, so nothing recorded off a real
+// feed has to be checked in. frames are ethernet / ipv4 / udp from one
+// source to the feed's multicast group, with lengths and checksums filled in
+// the way a nic would see them, unless a Corruption says otherwise
+
+pub const SOURCE_MAC: [u8; 6] = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
+pub const GROUP_MAC: [u8; 6] = [0x01, 0x00, 0x5e, 0x25, 0x36, 0x47];
+pub const SOURCE_IP: [u8; 4] = [10, 0, 0, 1];
+pub const GROUP_IP: [u8; 4] = [233, 37, 54, 71];
+pub const SOURCE_PORT: u16 = 40000;
+
+const ETHERNET_LEN: usize = 14;
+const IPV4_LEN: usize = 20;
+
+// ways to break a packet on its way into the capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corruption {
+    // only the first n payload bytes are sent, with headers that agree
+    Truncate(usize),
+    // the udp checksum doesn't match the payload
+    BadUdpChecksum,
+    // the ipv4 header checksum doesn't match the header
+    BadIpChecksum,
+    // the same payload carried over tcp
+    Tcp,
+    // the frame stops partway through the ipv4 header
+    CutHeaders,
+}
+
+// an ethernet / ipv4 / udp frame carrying payload to port
+pub fn udp_frame(port: u16, payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::ethernet2(SOURCE_MAC, GROUP_MAC)
+        .ipv4(SOURCE_IP, GROUP_IP, 64)
+        .udp(SOURCE_PORT, port);
+    let mut frame = Vec::with_capacity(builder.size(payload.len()));
+    builder
+        .write(&mut frame, payload)
+        .expect("writing to a vec can't fail");
+    frame
+}
+
+pub fn tcp_frame(port: u16, payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::ethernet2(SOURCE_MAC, GROUP_MAC)
+        .ipv4(SOURCE_IP, GROUP_IP, 64)
+        .tcp(SOURCE_PORT, port, 1, 65535);
+    let mut frame = Vec::with_capacity(builder.size(payload.len()));
+    builder
+        .write(&mut frame, payload)
+        .expect("writing to a vec can't fail");
+    frame
+}
+
+pub fn corrupted_frame(port: u16, payload: &[u8], corruption: Corruption) -> Vec<u8> {
+    match corruption {
+        Corruption::Truncate(len) => udp_frame(port, &payload[..len.min(payload.len())]),
+        Corruption::BadUdpChecksum => {
+            let mut frame = udp_frame(port, payload);
+            frame[ETHERNET_LEN + IPV4_LEN + 7] ^= 0xff;
+            frame
+        }
+        Corruption::BadIpChecksum => {
+            let mut frame = udp_frame(port, payload);
+            frame[ETHERNET_LEN + 11] ^= 0xff;
+            frame
+        }
+        Corruption::Tcp => tcp_frame(port, payload),
+        Corruption::CutHeaders => udp_frame(port, payload)[..ETHERNET_LEN + 10].to_vec(),
+    }
+}
+
+// a message from the same feed that isn't a price quote, A3 in place of
+// B6034, len bytes long
+pub fn non_quote_payload(len: usize) -> Vec<u8> {
+    let mut payload = b"A3034".to_vec();
+    payload.resize(len, b'0');
+    payload
+}
+
+// a capture assembled packet by packet. build gives the pcap file bytes
+#[derive(Debug, Clone, Default)]
+pub struct CaptureBuilder {
+    // with each frame's length on the wire, longer when the snaplen cut it
+    packets: Vec<(Duration, Vec<u8>, u32)>,
+}
+
+impl CaptureBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn udp(self, timestamp: Duration, port: u16, payload: &[u8]) -> Self {
+        self.frame(timestamp, &udp_frame(port, payload))
+    }
+
+    pub fn corrupted(
+        self,
+        timestamp: Duration,
+        port: u16,
+        payload: &[u8],
+        corruption: Corruption,
+    ) -> Self {
+        self.frame(timestamp, &corrupted_frame(port, payload, corruption))
+    }
+
+    // a udp packet captured with a snaplen of only snaplen bytes
+    pub fn snapped(self, timestamp: Duration, port: u16, payload: &[u8], snaplen: usize) -> Self {
+        let mut frame = udp_frame(port, payload);
+        let orig_len = frame.len() as u32;
+        frame.truncate(snaplen);
+        self.record(timestamp, frame, orig_len)
+    }
+
+    // any link layer frame, as is
+    pub fn frame(self, timestamp: Duration, frame: &[u8]) -> Self {
+        self.record(timestamp, frame.to_vec(), frame.len() as u32)
+    }
+
+    fn record(mut self, timestamp: Duration, frame: Vec<u8>, orig_len: u32) -> Self {
+        self.packets.push((timestamp, frame, orig_len));
+        self
+    }
+
+    pub fn frames(&self) -> impl Iterator<Item = (Duration, &[u8])> {
+        self.packets
+            .iter()
+            .map(|(timestamp, frame, _)| (*timestamp, &frame[..]))
+    }
+
+    pub fn len(&self) -> usize {
+        self.packets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packets.is_empty()
+    }
+
+    pub fn write<W: Write>(&self, writer: W) -> Result<W, PcapError> {
+        let mut pcap = PcapWriter::new(writer)?;
+        for (timestamp, frame, orig_len) in &self.packets {
+            pcap.write_packet(&PcapPacket::new(*timestamp, *orig_len, frame))?;
+        }
+        Ok(pcap.into_writer())
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        self.write(Vec::new()).expect("writing to a vec can't fail")
+    }
+}
+
+// a capture of well formed udp packets, one per (timestamp, port, payload)
+pub fn write_capture(packets: &[(Duration, u16, &[u8])]) -> Vec<u8> {
+    packets
+        .iter()
+        .fold(
+            CaptureBuilder::new(),
+            |capture, &(timestamp, port, payload)| capture.udp(timestamp, port, payload),
+        )
+        .build()
+}
+
+// 2011-02-16 09:00:00 KST
+const SAMPLE_START: Duration = Duration::from_secs(1297814400);
+
+// the three quotes sample_capture carries intact, in capture order
+pub fn sample_quotes() -> Vec<PriceQuote> {
+    [
+        (1, "KR4301F42629", 1),
+        (2, "KR4301F42959", 2),
+        (7, "KR4301F62551", 3),
+    ]
+    .into_iter()
+    .map(|(millis, issue_code, seq)| {
+        PriceQuoteBuilder::new()
+            .issue_code(issue_code)
+            .seq(seq)
+            .rcv_time(SAMPLE_START + Duration::from_millis(millis))
+            .build()
+    })
+    .collect()
+}
+
+// seven packets a millisecond apart, one of each thing a capture of the feed
+// tends to hold: a quote on 15515, a quote on 15516, an A3 message on 15515,
+// a quote on the wrong port, a quote over tcp, a quote cut off at 100 bytes,
+// and a final quote on 15515
+pub fn sample_capture() -> CaptureBuilder {
+    let quotes = sample_quotes();
+    let other = PriceQuoteBuilder::new()
+        .issue_code("KR4301F52651")
+        .seq(4)
+        .build()
+        .encode_b6034();
+    let at = |millis| SAMPLE_START + Duration::from_millis(millis);
+    CaptureBuilder::new()
+        .udp(at(1), 15515, &quotes[0].encode_b6034())
+        .udp(at(2), 15516, &quotes[1].encode_b6034())
+        .udp(at(3), 15515, &non_quote_payload(82))
+        .udp(at(4), 9999, &other)
+        .corrupted(at(5), 15515, &other, Corruption::Tcp)
+        .corrupted(at(6), 15515, &other, Corruption::Truncate(100))
+        .udp(at(7), 15515, &quotes[2].encode_b6034())
+}
@@ -0,0 +1,159 @@
+use std::ops::Range;
+use std::time::Duration;
+
+use chrono::NaiveTime;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+use crate::builder::PriceQuoteBuilder;
+use crate::market::MarketStatus;
+use crate::parser::B6034Parser;
+use crate::price_quote::{PriceQuote, B6034_LEN, ISSUE_CODE_LEN, LEVELS};
+
+// 2011-02-16 09:00:00 KST
+const SESSION_START: Duration = Duration::from_secs(1297814400);
+
+// the ascii decimal fields (total volumes, prices and quantities) sit back to
+// back in the middle of the message. they're the only bytes the decoder
+// checks, everything else is taken as is
+pub const DECIMAL_FIELDS: Range<usize> = {
+    let start = 5 + ISSUE_CODE_LEN + 3 + 2;
+    start..start + 2 * (7 + LEVELS * (5 + 7))
+};
+
+// kospi200 style isins, always the full 12 bytes so they survive a round trip
+pub fn issue_code() -> impl Strategy<Value = String> {
+    "KR4[1-3]0[1-9][A-Z0-9][0-9]{5}"
+}
+
+// anywhere from before the open to after the close, to the hundredth
+pub fn accept_time() -> impl Strategy<Value = NaiveTime> {
+    (8 * 3600 * 100..16 * 3600 * 100u32).prop_map(|hundredths| {
+        NaiveTime::from_num_seconds_from_midnight_opt(
+            hundredths / 100,
+            (hundredths % 100) * 10_000_000,
+        )
+        .unwrap()
+    })
+}
+
+// named statuses most of the time, with the odd code we don't know
+pub fn market_status() -> impl Strategy<Value = MarketStatus> {
+    prop_oneof![
+        4 => prop::sample::select(vec![0u16, 10, 11, 20, 21, 30, 40, 80, 90, 99]),
+        1 => 0..100u16,
+    ]
+    .prop_map(MarketStatus::from_code)
+}
+
+// one side of the book, best first. prices fit their ascii field, and
+// quantities are small enough that the side's total fits its 7 digits too
+pub fn book_side() -> impl Strategy<Value = Vec<(u64, u64, u32)>> {
+    vec((0..100_000u64, 0..2_000_000u64, 0..10_000u32), LEVELS)
+}
+
+// a quote that encodes to a valid B6034 payload
+pub fn price_quote() -> impl Strategy<Value = PriceQuote> {
+    (
+        issue_code(),
+        0..1000u32,
+        accept_time(),
+        market_status(),
+        book_side(),
+        book_side(),
+        // capture times over the following day or so
+        0..100_000_000u64,
+    )
+        .prop_map(
+            |(issue_code, seq, accept_time, status, bids, asks, rcv_ms)| {
+                let levels = |side: &[(u64, u64, u32)]| -> Vec<(u64, u64)> {
+                    side.iter().map(|&(price, qty, _)| (price, qty)).collect()
+                };
+                let mut builder = PriceQuoteBuilder::new()
+                    .issue_code(&issue_code)
+                    .seq(seq)
+                    .rcv_time(SESSION_START + Duration::from_millis(rcv_ms))
+                    .accept_time(accept_time)
+                    .market_status(status)
+                    .bids(&levels(&bids))
+                    .asks(&levels(&asks));
+                for level in 0..LEVELS {
+                    builder = builder
+                        .bid_orders(level, bids[level].2)
+                        .ask_orders(level, asks[level].2);
+                }
+                builder.build()
+            },
+        )
+}
+
+pub fn b6034_payload() -> impl Strategy<Value = Vec<u8>> {
+    price_quote().prop_map(|q| q.encode_b6034())
+}
+
+// one change to a valid payload. the length and the B6034 prefix are always
+// left alone, so the packet still looks like a quote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mutation {
+    // a digit in a decimal field swapped for something that isn't one
+    NonDigit { offset: usize, byte: u8 },
+    // a digit in a decimal field swapped for another digit
+    Digit { offset: usize, digit: u8 },
+    // any byte outside the decimal fields and the prefix
+    Opaque { offset: usize, byte: u8 },
+}
+
+impl Mutation {
+    // whether the decoder should refuse the payload after this change
+    pub fn corrupts(&self) -> bool {
+        matches!(self, Mutation::NonDigit { .. })
+    }
+
+    pub fn offset(&self) -> usize {
+        match *self {
+            Mutation::NonDigit { offset, .. }
+            | Mutation::Digit { offset, .. }
+            | Mutation::Opaque { offset, .. } => offset,
+        }
+    }
+
+    pub fn apply(&self, payload: &mut [u8]) {
+        match *self {
+            Mutation::NonDigit { offset, byte } | Mutation::Opaque { offset, byte } => {
+                payload[offset] = byte
+            }
+            Mutation::Digit { offset, digit } => payload[offset] = b'0' + digit,
+        }
+    }
+}
+
+pub fn mutation() -> impl Strategy<Value = Mutation> {
+    let prefix = B6034Parser::PREFIX.len();
+    // '+' is left out, rust's integer parsing takes it as a leading sign
+    let non_digit =
+        any::<u8>().prop_filter("a digit or a sign", |b| !b.is_ascii_digit() && *b != b'+');
+    let opaque_offset = prop_oneof![prefix..DECIMAL_FIELDS.start, DECIMAL_FIELDS.end..B6034_LEN];
+    prop_oneof![
+        (DECIMAL_FIELDS, non_digit).prop_map(|(offset, byte)| Mutation::NonDigit { offset, byte }),
+        (DECIMAL_FIELDS, 0..10u8).prop_map(|(offset, digit)| Mutation::Digit { offset, digit }),
+        (opaque_offset, any::<u8>()).prop_map(|(offset, byte)| Mutation::Opaque { offset, byte }),
+    ]
+}
+
+// a valid payload with up to three mutations applied, and whether any of
+// them corrupted it
+pub fn mutated_payload() -> impl Strategy<Value = (Vec<u8>, bool)> {
+    (b6034_payload(), vec(mutation(), 0..=3)).prop_map(|(mut payload, mutations)| {
+        // a later mutation can overwrite an earlier one, so what counts is
+        // the last change at each offset
+        let mut corrupted_offsets = Vec::new();
+        for mutation in &mutations {
+            mutation.apply(&mut payload);
+            corrupted_offsets.retain(|&offset| offset != mutation.offset());
+            if mutation.corrupts() {
+                corrupted_offsets.push(mutation.offset());
+            }
+        }
+        (payload, !corrupted_offsets.is_empty())
+    })
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+
+use crate::parsing::PacketParseStats;
+
+// limits a capture has to stay within, checked against its stats once
+// parsing is done. --max-failed, --max-failed-pct and --min-quotes
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Thresholds {
+    pub max_failed: Option<u64>,
+    // percent of the quote payloads that reached the decoder
+    pub max_failed_pct: Option<f64>,
+    pub min_quotes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Violation {
+    MaxFailed { failed: u64, max: u64 },
+    MaxFailedPct { pct: f64, max: f64 },
+    MinQuotes { quotes: u64, min: u64 },
+}
+
+impl Violation {
+    // what it's called in the json stats
+    pub fn name(&self) -> &'static str {
+        match self {
+            Violation::MaxFailed { .. } => "max_failed",
+            Violation::MaxFailedPct { .. } => "max_failed_pct",
+            Violation::MinQuotes { .. } => "min_quotes",
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::MaxFailed { failed, max } => {
+                write!(
+                    f,
+                    "{} quotes failed to decode, over --max-failed {}",
+                    failed, max
+                )
+            }
+            Violation::MaxFailedPct { pct, max } => write!(
+                f,
+                "{:.2}% of quotes failed to decode, over --max-failed-pct {}",
+                pct, max
+            ),
+            Violation::MinQuotes { quotes, min } => {
+                write!(f, "{} quotes decoded, under --min-quotes {}", quotes, min)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Violation {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl Thresholds {
+    // every limit the stats are outside of, in the order of the fields
+    pub fn check(&self, stats: &PacketParseStats) -> Vec<Violation> {
+        let mut violations = vec![];
+        if let Some(max) = self.max_failed {
+            if stats.failed > max {
+                violations.push(Violation::MaxFailed {
+                    failed: stats.failed,
+                    max,
+                });
+            }
+        }
+        if let Some(max) = self.max_failed_pct {
+            let decoded = stats.successfully_parsed + stats.failed;
+            if decoded > 0 {
+                let pct = stats.failed as f64 / decoded as f64 * 100.0;
+                if pct > max {
+                    violations.push(Violation::MaxFailedPct { pct, max });
+                }
+            }
+        }
+        if let Some(min) = self.min_quotes {
+            if stats.successfully_parsed < min {
+                violations.push(Violation::MinQuotes {
+                    quotes: stats.successfully_parsed,
+                    min,
+                });
+            }
+        }
+        violations
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::price::{Price, PriceFormat, Qty};
+use crate::price_quote::{fmt_rcv_time_iso, Level, PriceQuote, Side};
+
+// a trade the book suggests happened. the feed carries quotes, not
+// executions, so these are guesses: a cancel looks just like a fill
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct InferredTrade {
+    // capture time of the quote that gave it away
+    pub time: Duration,
+    pub issue_code: String,
+    // the side of the book it took from, bid for a sell into the bid
+    pub side: Side,
+    pub price: Price,
+    pub inferred_quantity: Qty,
+}
+
+impl InferredTrade {
+    pub fn fmt_with(&self, format: PriceFormat) -> String {
+        format!(
+            "{} {} {} {}@{}",
+            fmt_rcv_time_iso(self.time),
+            self.issue_code,
+            self.side,
+            self.inferred_quantity,
+            self.price.display(format)
+        )
+    }
+}
+
+// prices as plain integers
+impl fmt::Display for InferredTrade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.fmt_with(PriceFormat::default()))
+    }
+}
+
+// --aggregate-to-trades. keeps the top of book of every issue's last quote
+// and compares each new quote against it. a best level that shrank at the
+// same price lost the difference to a trade, and one that's gone with the
+// price moving away from the spread lost all of it
+#[derive(Debug, Default)]
+pub struct TradeInferrer {
+    // best bid and ask, by issue
+    tops: HashMap<String, (Level, Level)>,
+}
+
+impl TradeInferrer {
+    pub fn new() -> Self {
+        TradeInferrer::default()
+    }
+
+    // quotes go in capture order. the first quote of an issue has nothing
+    // to compare against, so never gives any trades
+    pub fn observe(&mut self, q: &PriceQuote) -> Vec<InferredTrade> {
+        let top = (q.bids[0], q.asks[0]);
+        let (last_bid, last_ask) = match self.tops.get_mut(&q.issue_code) {
+            Some(last) => std::mem::replace(last, top),
+            None => {
+                self.tops.insert(q.issue_code.clone(), top);
+                return vec![];
+            }
+        };
+
+        [(Side::Bid, last_bid, top.0), (Side::Ask, last_ask, top.1)]
+            .into_iter()
+            .filter_map(|(side, before, after)| {
+                taken(side, before, after).map(|qty| InferredTrade {
+                    time: q.packet_rcv_time,
+                    issue_code: q.issue_code.clone(),
+                    side,
+                    price: before.price,
+                    inferred_quantity: qty,
+                })
+            })
+            .collect()
+    }
+}
+
+// how much of the best level before went, if it looks traded
+fn taken(side: Side, before: Level, after: Level) -> Option<Qty> {
+    if before.price.is_zero() {
+        return None;
+    }
+    if after.price == before.price {
+        return (after.qty < before.qty).then(|| Qty(before.qty.raw() - after.qty.raw()));
+    }
+    let moved_away = after.price.is_zero()
+        || match side {
+            Side::Bid => after.price < before.price,
+            Side::Ask => after.price > before.price,
+        };
+    moved_away.then_some(before.qty)
+}
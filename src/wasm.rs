@@ -0,0 +1,34 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::schema::{CRATE_VERSION, JSON_SCHEMA_VERSION};
+use crate::{parse_price_quotes_from_bytes, PacketParseStats, ParseOptions, PriceQuote};
+
+#[derive(Serialize)]
+struct ParsedCapture<'a> {
+    schema_version: u32,
+    crate_version: &'static str,
+    quotes: &'a [PriceQuote],
+    stats: &'a PacketParseStats,
+}
+
+// a whole capture handed over from javascript, e.g. the bytes of a file
+// dropped on the page. returns { schema_version, crate_version, quotes,
+// stats } with the same field names as the rust structs, see schema.rs for
+// the versions. the raw u64 fields too big for a js number, like
+// quote_accept_time, come out as BigInt. throws if the capture can't be read
+// at all
+#[wasm_bindgen]
+pub fn parse_pcap_bytes(data: &[u8]) -> Result<JsValue, JsValue> {
+    let (quotes, stats) = parse_price_quotes_from_bytes(data, &ParseOptions::default())
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    let serializer =
+        serde_wasm_bindgen::Serializer::new().serialize_large_number_types_as_bigints(true);
+    let parsed = ParsedCapture {
+        schema_version: JSON_SCHEMA_VERSION,
+        crate_version: CRATE_VERSION,
+        quotes: &quotes,
+        stats: &stats,
+    };
+    Ok(parsed.serialize(&serializer)?)
+}
@@ -0,0 +1,141 @@
+use chrono::{TimeZone, Utc};
+use rust_xlsxwriter::{
+    ConditionalFormatCell, ConditionalFormatCellRule, ConditionalFormatTop,
+    ConditionalFormatTopRule, Format, Workbook, XlsxError,
+};
+
+use crate::price_quote::{
+    PriceQuote, ASK_PRICE_FIELDS, ASK_QTY_FIELDS, BID_PRICE_FIELDS, BID_QTY_FIELDS, LEVELS,
+};
+
+const SPREAD_COL: u16 = 5;
+const TOTAL_BID_VOLUME_COL: u16 = 7;
+const TOTAL_ASK_VOLUME_COL: u16 = 8;
+
+// one row per quote under a frozen, filterable header. negative spreads
+// (crossed books) are red and the top 10% of total volumes are green.
+// prices are scaled by scale
+pub fn write_quotes_to_xlsx(
+    quotes: &[PriceQuote],
+    path: &str,
+    scale: u32,
+) -> Result<(), XlsxError> {
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name("quotes")?;
+
+    let mut headers = vec![
+        "packet_rcv_time",
+        "quote_accept_time",
+        "issue_code",
+        "issue_seq_no",
+        "market_status_type",
+        "spread",
+        "mid_price",
+        "total_bid_quote_volume",
+        "total_ask_quote_volume",
+    ];
+    for i in 0..LEVELS {
+        headers.push(BID_PRICE_FIELDS[i]);
+        headers.push(BID_QTY_FIELDS[i]);
+    }
+    for i in 0..LEVELS {
+        headers.push(ASK_PRICE_FIELDS[i]);
+        headers.push(ASK_QTY_FIELDS[i]);
+    }
+
+    let header_format = Format::new()
+        .set_bold()
+        .set_background_color("#D9E1F2")
+        .set_border_bottom(rust_xlsxwriter::FormatBorder::Thin);
+    for (col, header) in headers.iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, *header, &header_format)?;
+    }
+
+    for (i, quote) in quotes.iter().enumerate() {
+        let row = i as u32 + 1;
+        let rcv_time = Utc
+            .timestamp_opt(
+                quote.packet_rcv_time.as_secs() as i64,
+                quote.packet_rcv_time.subsec_nanos(),
+            )
+            .single()
+            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S%.6f").to_string())
+            .unwrap_or_default();
+        let accept_time = quote
+            .accept_time()
+            .map(|t| t.format("%H:%M:%S%.3f").to_string())
+            .unwrap_or_default();
+
+        worksheet.write_string(row, 0, rcv_time)?;
+        worksheet.write_string(row, 1, accept_time)?;
+        worksheet.write_string(row, 2, &quote.issue_code)?;
+        worksheet.write_number(row, 3, quote.issue_seq_no)?;
+        worksheet.write_number(row, 4, quote.market_status_type)?;
+
+        // signed here, unlike PriceQuote::spread, so crossed books show up
+        let best_bid = quote.bids[0].price;
+        let best_ask = quote.asks[0].price;
+        if !best_bid.is_zero() && !best_ask.is_zero() {
+            worksheet.write_number(
+                row,
+                SPREAD_COL,
+                best_ask.to_f64(scale) - best_bid.to_f64(scale),
+            )?;
+        }
+        if let Some(mid) = quote.mid_price(scale) {
+            worksheet.write_number(row, 6, mid)?;
+        }
+        worksheet.write_number(
+            row,
+            TOTAL_BID_VOLUME_COL,
+            quote.total_bid_quote_volume.to_f64(),
+        )?;
+        worksheet.write_number(
+            row,
+            TOTAL_ASK_VOLUME_COL,
+            quote.total_ask_quote_volume.to_f64(),
+        )?;
+
+        let mut col = 9;
+        for level in quote.bids.iter().chain(quote.asks.iter()) {
+            worksheet.write_number(row, col, level.price.to_f64(scale))?;
+            worksheet.write_number(row, col + 1, level.qty.to_f64())?;
+            col += 2;
+        }
+    }
+
+    let last_row = quotes.len() as u32;
+    let last_col = headers.len() as u16 - 1;
+    worksheet.set_freeze_panes(1, 0)?;
+    worksheet.autofilter(0, 0, last_row, last_col)?;
+
+    if !quotes.is_empty() {
+        let negative = ConditionalFormatCell::new()
+            .set_rule(ConditionalFormatCellRule::LessThan(0))
+            .set_format(
+                Format::new()
+                    .set_font_color("#9C0006")
+                    .set_background_color("#FFC7CE"),
+            );
+        worksheet.add_conditional_format(1, SPREAD_COL, last_row, SPREAD_COL, &negative)?;
+
+        let high_volume = ConditionalFormatTop::new()
+            .set_rule(ConditionalFormatTopRule::TopPercent(10))
+            .set_format(
+                Format::new()
+                    .set_font_color("#006100")
+                    .set_background_color("#C6EFCE"),
+            );
+        worksheet.add_conditional_format(
+            1,
+            TOTAL_BID_VOLUME_COL,
+            last_row,
+            TOTAL_ASK_VOLUME_COL,
+            &high_volume,
+        )?;
+    }
+
+    worksheet.autofit();
+    workbook.save(path)
+}
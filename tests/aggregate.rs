@@ -0,0 +1,91 @@
+use std::ops::ControlFlow;
+use std::time::Duration;
+
+use tsc_solution::aggregate::{aggregate_books_from_readers, merge_books};
+use tsc_solution::price_quote::Level;
+use tsc_solution::testdata::{sample_capture, CaptureBuilder};
+use tsc_solution::{concat_price_quotes_with_port, ParseOptions, PriceQuote, PriceQuoteBuilder};
+
+const ISSUE: &str = "KR4301F42629";
+
+fn quote(millis: u64, bids: &[(u64, u64)], asks: &[(u64, u64)]) -> PriceQuote {
+    PriceQuoteBuilder::new()
+        .issue_code(ISSUE)
+        .rcv_time(Duration::from_millis(millis))
+        .bids(bids)
+        .asks(asks)
+        .build()
+}
+
+fn prices(levels: &[Level]) -> Vec<(u64, u64)> {
+    levels
+        .iter()
+        .map(|level| (level.price.raw(), level.qty.raw()))
+        .collect()
+}
+
+#[test]
+fn merges_levels_from_both_books() {
+    let a = quote(1, &[(100, 5), (99, 5), (97, 5)], &[(101, 5)]);
+    let b = quote(2, &[(99, 50), (98, 1)], &[(102, 1), (101, 1)]);
+    let merged = merge_books(&a, &b);
+
+    // b has more bid volume, so its 99 wins
+    assert_eq!(
+        prices(&merged.bids),
+        [(100, 5), (99, 50), (98, 1), (97, 5), (0, 0)]
+    );
+    assert_eq!(merged.total_bid_quote_volume, b.total_bid_quote_volume);
+    // and a more ask volume, so its 101 does
+    assert_eq!(prices(&merged.asks)[..2], [(101, 5), (102, 1)]);
+    assert_eq!(merged.total_ask_quote_volume, a.total_ask_quote_volume);
+    // the rest is the newer quote's
+    assert_eq!(merged.packet_rcv_time, b.packet_rcv_time);
+
+    // only the best five survive
+    let deep = quote(3, &[(96, 1), (95, 1), (94, 1), (93, 1), (92, 1)], &[]);
+    let merged = merge_books(&a, &deep);
+    assert_eq!(
+        prices(&merged.bids),
+        [(100, 5), (99, 5), (97, 5), (96, 1), (95, 1)]
+    );
+}
+
+#[test]
+fn aggregates_books_across_ports() {
+    let a = quote(1, &[(100, 5)], &[(102, 5)]);
+    let b = quote(2, &[(99, 5)], &[(101, 5)]);
+    let c = quote(3, &[(98, 5)], &[(103, 5)]);
+    let capture = CaptureBuilder::new()
+        .udp(a.packet_rcv_time, 15515, &a.encode_b6034())
+        .udp(b.packet_rcv_time, 15516, &b.encode_b6034())
+        .udp(c.packet_rcv_time, 15515, &c.encode_b6034())
+        .build();
+
+    let (books, stats) =
+        aggregate_books_from_readers(vec![&capture[..]], &ParseOptions::default()).unwrap();
+    assert_eq!(stats.successfully_parsed, 3);
+    assert_eq!(books.len(), 3);
+    // nothing to merge with yet
+    assert_eq!(books[0], a);
+    assert_eq!(prices(&books[1].bids)[..2], [(100, 5), (99, 5)]);
+    assert_eq!(prices(&books[1].asks)[..2], [(101, 5), (102, 5)]);
+    // c replaces a as port 15515's latest
+    assert_eq!(prices(&books[2].bids)[..2], [(99, 5), (98, 5)]);
+    assert_eq!(books[2].packet_rcv_time, c.packet_rcv_time);
+}
+
+#[test]
+fn quotes_come_with_their_port() {
+    let mut ports = vec![];
+    concat_price_quotes_with_port(
+        vec![&sample_capture().build()[..]],
+        &ParseOptions::default(),
+        |_, port| {
+            ports.push(port);
+            ControlFlow::Continue(())
+        },
+    )
+    .unwrap();
+    assert_eq!(ports, [Some(15515), Some(15516), Some(15515)]);
+}
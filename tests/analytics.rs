@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use tsc_solution::analytics::{
+    compute_twap, parse_duration, Burst, BurstDetector, LatencyBucket, LatencyHistogram,
+    RateCounter, DEFAULT_LATENCY_MAX_US,
+};
+use tsc_solution::PriceQuoteBuilder;
+
+fn quote(issue: &str, secs: u64, bid: u64, ask: u64) -> tsc_solution::PriceQuote {
+    PriceQuoteBuilder::new()
+        .issue_code(issue)
+        .rcv_time(Duration::from_secs(1297814400 + secs))
+        .bids(&[(bid, 1)])
+        .asks(&[(ask, 1)])
+        .build()
+}
+
+#[test]
+fn twap_weights_mids_by_time_held() {
+    let quotes = [
+        quote("A", 0, 100, 110),
+        quote("B", 0, 50, 50),
+        quote("A", 30, 200, 210),
+        // one sided, doesn't count
+        PriceQuoteBuilder::new()
+            .issue_code("A")
+            .rcv_time(Duration::from_secs(1297814400 + 60))
+            .bids(&[])
+            .build(),
+        quote("A", 90, 300, 310),
+        quote("B", 100, 90, 90),
+    ];
+
+    let twap = compute_twap(&quotes, 0);
+    // 105 for 30s, 205 for 60s, 305 for 10s
+    assert!((twap["A"] - (105.0 * 30.0 + 205.0 * 60.0 + 305.0 * 10.0) / 100.0).abs() < 1e-9);
+    // the last quote holds for no time at all
+    assert!((twap["B"] - 50.0).abs() < 1e-9);
+}
+
+#[test]
+fn twap_of_single_quote_covers_the_whole_window() {
+    let quotes = [
+        quote("A", 0, 100, 110),
+        quote("B", 40, 500, 520),
+        quote("A", 100, 100, 110),
+    ];
+    let twap = compute_twap(&quotes, 0);
+    assert_eq!(twap["B"], 510.0);
+    assert_eq!(twap["A"], 105.0);
+
+    assert_eq!(compute_twap(&quotes[1..2], 0)["B"], 510.0);
+    assert!(compute_twap(&[], 0).is_empty());
+}
+
+#[test]
+fn latency_percentiles_are_exact_below_a_bucket_width() {
+    let mut histogram = LatencyHistogram::new(DEFAULT_LATENCY_MAX_US);
+    for latency_us in 1..=50 {
+        histogram.record(latency_us);
+    }
+    assert_eq!(histogram.count(), 50);
+    assert_eq!(histogram.percentile(50.0), Some(25));
+    assert_eq!(histogram.percentile(90.0), Some(45));
+    assert_eq!(histogram.percentile(100.0), Some(50));
+
+    let report = histogram.report().unwrap();
+    assert_eq!(report.deciles_us.len(), 9);
+    assert_eq!(report.deciles_us[0], Some(5));
+    assert_eq!(report.deciles_us[8], Some(45));
+    assert!(report.to_string().contains("p90 ###"), "{}", report);
+}
+
+#[test]
+fn large_latencies_land_within_a_bucket() {
+    let mut histogram = LatencyHistogram::new(DEFAULT_LATENCY_MAX_US);
+    histogram.record(1_000);
+    histogram.record(1_000_000);
+    let p50 = histogram.percentile(50.0).unwrap();
+    assert!((1_000..1_000 * 33 / 32).contains(&p50), "{}", p50);
+    // the top bucket is clamped to the largest latency seen
+    assert_eq!(histogram.percentile(100.0), Some(1_000_000));
+
+    let report = histogram.report().unwrap();
+    assert_eq!(report.buckets.len(), 2);
+    let bucket = report.buckets[0];
+    assert!(bucket.low_us <= 1_000 && 1_000 < bucket.high_us);
+    assert_eq!(bucket.count, 1);
+}
+
+#[test]
+fn negative_and_oversized_latencies_are_counted_apart() {
+    let mut histogram = LatencyHistogram::new(1_000);
+    histogram.record(-30);
+    histogram.record(-5);
+    histogram.record(10);
+    histogram.record(5_000);
+
+    let report = histogram.report().unwrap();
+    assert_eq!(report.count, 2);
+    assert_eq!(report.negative, 2);
+    assert_eq!(report.most_negative_us, Some(-30));
+    assert_eq!(report.min_us, Some(10));
+    assert_eq!(report.above_max, 1);
+    assert_eq!(report.max_us, Some(5_000));
+    assert_eq!(report.p99_us, Some(5_000));
+    assert_eq!(
+        report.buckets,
+        [LatencyBucket {
+            low_us: 10,
+            high_us: 11,
+            count: 1
+        }]
+    );
+    let text = report.to_string();
+    assert!(
+        text.contains("Negative (clock skew): 2, down to -30us"),
+        "{}",
+        text
+    );
+    assert!(text.contains("Over 1000us: 1"), "{}", text);
+}
+
+#[test]
+fn only_negative_latencies_have_no_percentiles() {
+    let mut histogram = LatencyHistogram::new(DEFAULT_LATENCY_MAX_US);
+    assert_eq!(histogram.report(), None);
+    histogram.record(-1);
+    let report = histogram.report().unwrap();
+    assert_eq!((report.count, report.p50_us), (0, None));
+    assert!(report.to_string().contains("p50: -"));
+}
+
+#[test]
+fn parses_bucket_widths() {
+    assert_eq!(parse_duration("500ms"), Some(Duration::from_millis(500)));
+    assert_eq!(parse_duration("1s"), Some(Duration::from_secs(1)));
+    assert_eq!(parse_duration("2"), Some(Duration::from_secs(2)));
+    assert_eq!(parse_duration("1m"), Some(Duration::from_secs(60)));
+    assert_eq!(parse_duration("250us"), Some(Duration::from_micros(250)));
+    assert_eq!(parse_duration("1.5s"), None);
+    assert_eq!(parse_duration("ms"), None);
+    assert_eq!(parse_duration("3 days"), None);
+}
+
+#[test]
+fn detects_bursts_within_a_sliding_window() {
+    let start = Duration::from_secs(1297814400);
+    let mut bursts = BurstDetector::new(Duration::from_millis(1), 2);
+    let mut ended = vec![];
+    for (micros, issue_code) in [
+        (0, "A"),
+        (400, "B"),
+        // a third within 1ms starts a burst
+        (900, "A"),
+        (1_000, "A"),
+        (1_100, "C"),
+        // everything before has slid out by then, which ends it
+        (2_100, "B"),
+        (5_000, "C"),
+        (5_100, "C"),
+        (5_200, "C"),
+        (5_300, "C"),
+    ] {
+        ended.extend(bursts.record(start + Duration::from_micros(micros), issue_code));
+    }
+    ended.extend(bursts.finish());
+
+    assert_eq!(ended.len(), 2);
+    // busiest at 400-1_100, 4 quotes
+    assert_eq!(
+        ended[0],
+        Burst {
+            start_ns: (start + Duration::from_micros(400)).as_nanos() as u64,
+            window_ns: 1_000_000,
+            count: 4,
+            top_issues: vec![
+                ("A".to_string(), 2),
+                ("B".to_string(), 1),
+                ("C".to_string(), 1)
+            ],
+        }
+    );
+    assert_eq!(ended[1].count, 4);
+    assert_eq!(ended[1].top_issues, [("C".to_string(), 4)]);
+    // ties go to the earlier burst
+    assert_eq!(bursts.worst(), Some(&ended[0]));
+    assert_eq!(
+        ended[1].to_string(),
+        "4 quotes within 1ms from 2011-02-16T00:00:00.005000000Z, mostly C (4)"
+    );
+}
+
+#[test]
+fn counts_quotes_per_bucket() {
+    let start = Duration::from_secs(1297814400);
+    let mut rates = RateCounter::new(Duration::from_millis(500));
+    assert_eq!(rates.series(), None);
+    for millis in [100, 200, 1_600, 1_700, 1_900, 600, 2_100, 2_200, 2_300] {
+        rates.record(start + Duration::from_millis(millis));
+    }
+    let series = rates.series().unwrap();
+    assert_eq!(series.start_ns, start.as_nanos() as u64);
+    assert_eq!(series.bucket_ns, 500_000_000);
+    // the 1000-1500ms bucket is empty but still there
+    assert_eq!(series.counts, [2, 1, 0, 3, 3]);
+    // ties go to the earlier bucket
+    assert_eq!(series.peak_count, 3);
+    assert_eq!(
+        series.peak_start_ns,
+        (start + Duration::from_millis(1_500)).as_nanos() as u64
+    );
+
+    let text = series.to_string();
+    assert!(text.contains("    |▆▃ ██|\n"), "{}", text);
+    assert!(
+        text.contains("Peak: 3 quotes at 2011-02-16T00:00:01.500000000Z"),
+        "{}",
+        text
+    );
+}
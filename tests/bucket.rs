@@ -0,0 +1,48 @@
+#![cfg(feature = "chrono")]
+
+use chrono::NaiveTime;
+use tsc_solution::bucket::{Bucket, Bucketer};
+use tsc_solution::PriceQuoteBuilder;
+
+#[test]
+fn groups_quotes_into_windows_of_accept_time() {
+    let quote = |millis, bid, ask| {
+        PriceQuoteBuilder::new()
+            .accept_time(NaiveTime::from_hms_milli_opt(9, 0, 0, millis).unwrap())
+            .bid(0, bid, 1)
+            .ask(0, ask, 1)
+            .build()
+    };
+    let mut bucketer = Bucketer::new(100, 0);
+    // mids 527.5, 530, 526 then 532.5, with a one-sided quote in the first
+    bucketer.observe(&quote(0, 525, 530));
+    bucketer.observe(&quote(40, 525, 535));
+    bucketer.observe(&quote(50, 0, 535));
+    bucketer.observe(&quote(90, 520, 532));
+    bucketer.observe(&quote(100, 530, 535));
+
+    let start = 9 * 3_600_000;
+    assert_eq!(
+        bucketer.finish(),
+        [
+            Bucket {
+                issue_code: "KR4301F42629".to_string(),
+                start_ms: start,
+                updates: 4,
+                first_mid: Some(527.5),
+                last_mid: Some(526.0),
+                min_mid: Some(526.0),
+                max_mid: Some(530.0),
+            },
+            Bucket {
+                issue_code: "KR4301F42629".to_string(),
+                start_ms: start + 100,
+                updates: 1,
+                first_mid: Some(532.5),
+                last_mid: Some(532.5),
+                min_mid: Some(532.5),
+                max_mid: Some(532.5),
+            },
+        ]
+    );
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "chrono")]
+
+use std::time::Duration;
+
+use chrono::NaiveTime;
+use tsc_solution::market::MarketStatus;
+use tsc_solution::price::{Price, Qty};
+use tsc_solution::price_quote::B6034_LEN;
+use tsc_solution::{PriceQuote, PriceQuoteBuilder};
+
+const QUOTE_PAYLOAD: &[u8] = include_bytes!("fixtures/b6034_quote.bin");
+
+#[test]
+fn encode_matches_captured_bytes() {
+    let quote = PriceQuote::from_bytes(Duration::ZERO, QUOTE_PAYLOAD).unwrap();
+    assert_eq!(quote.encode_b6034(), QUOTE_PAYLOAD);
+}
+
+#[test]
+fn example_round_trips() {
+    let quote = PriceQuote::example();
+    let bytes = quote.encode_b6034();
+
+    assert_eq!(bytes.len(), B6034_LEN);
+    assert!(bytes.starts_with(b"B6034KR4301F42629"));
+    assert_eq!(
+        PriceQuote::from_bytes(quote.packet_rcv_time, &bytes).unwrap(),
+        quote
+    );
+}
+
+#[test]
+fn example_is_sane() {
+    let quote = PriceQuote::example();
+
+    assert_eq!(quote.market_status(), MarketStatus::Continuous);
+    assert_eq!(quote.spread(), Some(Price(5)));
+    assert!(quote.volumes_consistent());
+    assert_eq!(
+        quote.accept_time(),
+        NaiveTime::from_hms_milli_opt(9, 0, 0, 10)
+    );
+}
+
+#[test]
+fn setters_apply() {
+    let quote = PriceQuoteBuilder::new()
+        .issue_code("KR4201F32705")
+        .bids(&[(100, 3)])
+        .ask(0, 105, 4)
+        .total_bid_volume(50)
+        .market_status(MarketStatus::OpeningAuction)
+        .build();
+
+    assert_eq!(quote.issue_code, "KR4201F32705");
+    assert_eq!(quote.best_bid_price_1st(), Price(100));
+    assert_eq!(quote.best_bid_price_2nd(), Price(0));
+    assert_eq!(quote.best_ask_price_1st(), Price(105));
+    assert_eq!(quote.best_ask_price_2nd(), Price(535));
+    assert_eq!(quote.total_bid_quote_volume, Qty(50));
+    assert_eq!(quote.market_status(), MarketStatus::OpeningAuction);
+}
+
+#[test]
+fn accept_time_follows_seq() {
+    let earlier = PriceQuoteBuilder::new().seq(5).build();
+    let later = PriceQuoteBuilder::new().seq(6).build();
+    assert!(earlier.accept_time() < later.accept_time());
+    assert!(earlier < later);
+}
@@ -0,0 +1,598 @@
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tsc_solution::testdata::{sample_capture, sample_quotes, write_capture};
+use tsc_solution::{PriceQuote, PriceQuoteBuilder};
+
+// runs the binary over the sample capture, written to its own file each time
+// since tests run in parallel
+fn run(args: &[&str]) -> Output {
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    let pcap = std::env::temp_dir().join(format!(
+        "tsc-cli-{}-{}.pcap",
+        std::process::id(),
+        RUNS.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&pcap, sample_capture().build()).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_tsc-solution"))
+        .arg(&pcap)
+        .args(args)
+        .output()
+        .unwrap();
+    std::fs::remove_file(&pcap).unwrap();
+    output
+}
+
+#[test]
+fn quiet_prints_only_quotes() {
+    for args in [&["--quiet"][..], &["--quiet", "-r"]] {
+        let output = run(args);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 3, "{:?}", args);
+        assert!(lines.iter().all(|line| line.contains("KR4301F")));
+        assert!(!stdout.contains("Packet Parse Stats"));
+    }
+}
+
+#[test]
+fn quiet_conflicts_with_stats_only() {
+    let output = run(&["--quiet", "--stats-only"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("cannot be used with"));
+}
+
+#[test]
+fn prints_issue_list() {
+    let output = run(&["--print-issue-list", "-q"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "         1 KR4301F42629\n         1 KR4301F42959\n         1 KR4301F62551\n"
+    );
+
+    let output = run(&["--print-issue-list", "-q", "--issue-list-min-count", "2"]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn writes_npy_arrays() {
+    let dir = std::env::temp_dir().join(format!("tsc-cli-npy-{}", std::process::id()));
+    let output = run(&["--output", "npy", "--output-dir", dir.to_str().unwrap()]);
+    assert!(output.status.success());
+    // the sample's three quotes, not split per issue
+    let bytes = std::fs::read(dir.join("best_bid_price_1st.npy")).unwrap();
+    let prices: Vec<f64> = npy::NpyData::from_bytes(&bytes).unwrap().to_vec();
+    assert_eq!(prices.len(), 3);
+    assert!(!dir.join("KR4301F42629.jsonl").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let output = run(&["--output", "npy"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn writes_binary_records() {
+    let path = std::env::temp_dir().join(format!("tsc-cli-{}.bin", std::process::id()));
+    let output = run(&["--output", "bin", "--output-path", path.to_str().unwrap()]);
+    assert!(output.status.success());
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let quotes: Vec<PriceQuote> = bytes
+        .chunks_exact(tsc_solution::record::RECORD_LEN)
+        .map(|record| tsc_solution::record::from_record(record).unwrap())
+        .collect();
+    assert_eq!(quotes, sample_quotes());
+
+    assert!(!run(&["--output", "bin"]).status.success());
+}
+
+#[test]
+fn json_schema_describes_the_jsonl_quotes() {
+    let output = Command::new(env!("CARGO_BIN_EXE_tsc-solution"))
+        .arg("--json-schema")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        schema["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+
+    let dir = std::env::temp_dir().join(format!("tsc-cli-schema-{}", std::process::id()));
+    assert!(run(&["--output-dir", dir.to_str().unwrap()])
+        .status
+        .success());
+    let jsonl = std::fs::read_to_string(dir.join("KR4301F42629.jsonl")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let quote: serde_json::Value = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+
+    // every field that's required is there, every field is described and
+    // every number is in range
+    for field in schema["required"].as_array().unwrap() {
+        assert!(quote.get(field.as_str().unwrap()).is_some(), "{}", field);
+    }
+    for (field, value) in quote.as_object().unwrap() {
+        let property = &schema["properties"][field];
+        assert!(property["description"].is_string(), "{}", field);
+        if let Some(number) = value.as_u64() {
+            assert!(number >= property["minimum"].as_u64().unwrap(), "{}", field);
+            if let Some(maximum) = property["maximum"].as_u64() {
+                assert!(number <= maximum, "{}", field);
+            }
+        }
+    }
+    let level = &schema["$defs"]["Level"]["properties"];
+    assert_eq!(level["price"]["maximum"], (1u64 << 40) - 1);
+
+    // the raw ascii fields are read little endian, so their examples give
+    // the digits back in order
+    let orders = level["order_count"]["examples"][0].as_u64().unwrap() as u32;
+    assert_eq!(&orders.to_le_bytes(), b"0012");
+    let accept_time = schema["properties"]["quote_accept_time"]["examples"][0]
+        .as_u64()
+        .unwrap();
+    assert_eq!(&accept_time.to_le_bytes(), b"09000001");
+
+    // it takes nothing else
+    assert!(!run(&["--json-schema"]).status.success());
+}
+
+#[test]
+fn price_precision_rounds_or_pads() {
+    let first_line = |args: &[&str]| {
+        let output = run(args);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        stdout.lines().next().unwrap().to_string()
+    };
+
+    // 525 and 530 with two implied decimals are 5.25 and 5.30
+    let natural = first_line(&["-q", "--price-scale", "2"]);
+    assert!(natural.contains(" 24@5.25  1@5.30 "), "{}", natural);
+    let rounded = first_line(&["-q", "--price-scale", "2", "--price-precision", "0"]);
+    assert!(rounded.contains(" 24@5  1@5 "), "{}", rounded);
+    // halves round up
+    let half = first_line(&["-q", "--price-scale", "2", "--price-precision", "1"]);
+    assert!(half.contains(" 24@5.3  1@5.3 "), "{}", half);
+    let padded = first_line(&["-q", "--price-scale", "2", "--price-precision", "4"]);
+    assert!(padded.contains(" 24@5.2500  1@5.3000 "), "{}", padded);
+}
+
+#[test]
+fn filters_by_country() {
+    let output = run(&["--country", "kr"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().filter(|l| l.contains("KR4301F")).count(), 3);
+    assert!(stdout.contains("Other Countries: 0"));
+
+    let output = run(&["--country", "US"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("KR4301F"));
+    assert!(stdout.contains("Other Countries: 3"));
+
+    assert!(!run(&["--country", "KOR"]).status.success());
+}
+
+#[cfg(feature = "lua")]
+#[test]
+fn filters_with_a_lua_script() {
+    let script = std::env::temp_dir().join(format!("tsc-filter-{}.lua", std::process::id()));
+    std::fs::write(
+        &script,
+        "function filter(quote) return quote.issue_code == 'KR4301F42959' end",
+    )
+    .unwrap();
+    let output = run(&["--filter-script", script.to_str().unwrap()]);
+    std::fs::remove_file(&script).unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().filter(|l| l.contains("KR4301F")).count(), 1);
+    assert!(stdout.contains("Filtered By Script: 2"));
+}
+
+#[test]
+fn prints_per_issue_stats() {
+    let output = run(&["--per-issue-stats", "--top", "2"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let table = stdout.split("Issue ").nth(1).unwrap();
+    // three issues with one quote each, so the first two by issue code
+    assert_eq!(table.lines().filter(|l| !l.is_empty()).count(), 3);
+    assert!(table.contains("KR4301F42629"));
+    assert!(table.contains("KR4301F42959"));
+    assert!(!table.contains("KR4301F62551"));
+
+    // on its own --top prints the issues with their last top of book
+    let output = run(&["--top", "2"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let table = stdout.split("Issue ").nth(1).unwrap();
+    assert!(table.contains("Bid Qty"));
+    assert!(table.contains("KR4301F42959"));
+    assert!(!table.contains("KR4301F62551"));
+    assert!(!stdout.contains("Min Spread"));
+}
+
+#[test]
+fn diffs_two_captures() {
+    let dir = std::env::temp_dir();
+    let a = dir.join(format!("tsc-cli-diff-a-{}.pcap", std::process::id()));
+    let b = dir.join(format!("tsc-cli-diff-b-{}.pcap", std::process::id()));
+    let quotes = sample_quotes();
+    let changed = PriceQuoteBuilder::new()
+        .issue_code(&quotes[1].issue_code)
+        .seq(2)
+        .asks(&[(545, 4)])
+        .build();
+    let write = |path: &std::path::Path, quotes: &[&PriceQuote]| {
+        let payloads: Vec<Vec<u8>> = quotes.iter().map(|q| q.encode_b6034()).collect();
+        let packets: Vec<(Duration, u16, &[u8])> = payloads
+            .iter()
+            .map(|payload| (Duration::ZERO, 15515, payload.as_slice()))
+            .collect();
+        std::fs::write(path, write_capture(&packets)).unwrap();
+    };
+    write(&a, &[&quotes[0], &quotes[1]]);
+    write(&b, &[&changed, &quotes[2]]);
+
+    let diff = |a: &std::path::Path, b: &std::path::Path| {
+        Command::new(env!("CARGO_BIN_EXE_tsc-solution"))
+            .arg("--diff-files")
+            .args([a, b])
+            .output()
+            .unwrap()
+    };
+    let output = diff(&a, &b);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[0].starts_with("- ") && lines[0].contains("KR4301F42629"));
+    assert!(
+        lines[1].starts_with("~ KR4301F42959 09:00:00.020"),
+        "{}",
+        stdout
+    );
+    assert!(
+        lines.contains(&"    best_ask_price_1st: 530 -> 545"),
+        "{}",
+        stdout
+    );
+    assert!(lines.last().unwrap().starts_with("+ ") && stdout.contains("KR4301F62551"));
+
+    assert!(diff(&a, &a).status.success());
+    std::fs::remove_file(&a).unwrap();
+    std::fs::remove_file(&b).unwrap();
+}
+
+#[test]
+fn diff_reports_quotes_only_one_capture_has() {
+    let dir = std::env::temp_dir();
+    let a = dir.join(format!(
+        "tsc-cli-content-diff-a-{}.pcap",
+        std::process::id()
+    ));
+    let b = dir.join(format!(
+        "tsc-cli-content-diff-b-{}.pcap",
+        std::process::id()
+    ));
+    let quotes = sample_quotes();
+    // the same capture but for the last quote's ask
+    let changed = PriceQuoteBuilder::new()
+        .issue_code(&quotes[2].issue_code)
+        .seq(3)
+        .asks(&[(545, 4)])
+        .build();
+    let write = |path: &std::path::Path, quotes: &[&PriceQuote]| {
+        let payloads: Vec<Vec<u8>> = quotes.iter().map(|q| q.encode_b6034()).collect();
+        let packets: Vec<(Duration, u16, &[u8])> = payloads
+            .iter()
+            .map(|payload| (Duration::ZERO, 15515, payload.as_slice()))
+            .collect();
+        std::fs::write(path, write_capture(&packets)).unwrap();
+    };
+    write(&a, &[&quotes[0], &quotes[1], &quotes[2]]);
+    write(&b, &[&quotes[0], &quotes[1], &changed]);
+
+    let diff = |a: &std::path::Path, b: &std::path::Path| {
+        Command::new(env!("CARGO_BIN_EXE_tsc-solution"))
+            .arg("diff")
+            .args([a, b])
+            .output()
+            .unwrap()
+    };
+    let output = diff(&a, &b);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 4, "{}", stdout);
+    assert!(lines[0].ends_with(": 3 quotes, 1 only here"), "{}", stdout);
+    assert!(lines[1].ends_with(": 3 quotes, 1 only here"), "{}", stdout);
+    assert!(lines[2].starts_with("- ") && lines[2].contains("KR4301F62551"));
+    assert!(lines[3].starts_with("+ ") && lines[3].contains("4@545"));
+
+    assert!(diff(&a, &a).status.success());
+    std::fs::remove_file(&a).unwrap();
+    std::fs::remove_file(&b).unwrap();
+}
+
+#[test]
+fn reports_latency_distribution() {
+    // the sample's quotes are captured a few ms before their accept times
+    // in korea, so they're all clock skew
+    let output = run(&["--stats-only", "--latency-report"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Latency (0 quotes):"), "{}", stdout);
+    assert!(
+        stdout.contains("Negative (clock skew): 3, down to -"),
+        "{}",
+        stdout
+    );
+
+    // against a session a day earlier they're all about a day late
+    let output = run(&[
+        "--stats-only",
+        "--latency-report",
+        "--session-date",
+        "2011-02-15",
+    ]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Latency (3 quotes):"), "{}", stdout);
+    assert!(stdout.contains("Over 60000000us: 3"), "{}", stdout);
+    assert!(stdout.contains("p50: 8639"), "{}", stdout);
+}
+
+#[test]
+fn warns_about_bursts() {
+    // the sample's quotes arrive 1, 2 and 7ms into a whole second
+    let output = run(&[
+        "--stats-only",
+        "--burst-window",
+        "2ms",
+        "--burst-threshold",
+        "1",
+    ]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(
+        stderr.matches("burst: 2 quotes within 2ms").count(),
+        1,
+        "{}",
+        stderr
+    );
+    assert!(
+        stdout.contains("  Worst Burst: 2 quotes within 2ms"),
+        "{}",
+        stdout
+    );
+
+    // the collected path finds the same
+    let output = run(&[
+        "--sorted",
+        "--burst-window",
+        "2ms",
+        "--burst-threshold",
+        "1",
+    ]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("  Worst Burst: 2 quotes within 2ms"),
+        "{}",
+        stdout
+    );
+
+    // the two only make sense together
+    let output = run(&["--stats-only", "--burst-window", "2ms"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn warns_about_latency_spikes() {
+    let output = run(&[
+        "--stats-only",
+        "--session-date",
+        "2011-02-15",
+        "--max-latency-us",
+        "10000",
+    ]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stdout.contains("Latency Spikes: 3"), "{}", stdout);
+    // the report comes along with it
+    assert!(stdout.contains("Deciles:"), "{}", stdout);
+    assert_eq!(stderr.matches("warning: ").count(), 3, "{}", stderr);
+
+    // accept times can't resolve anything finer
+    let output = run(&["--stats-only", "--max-latency-us", "9999"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn resamples_to_ticks() {
+    // the sample's quotes arrive 1, 2 and 7ms into a whole second
+    for (args, snapshots) in [
+        (&["--quiet", "--resample-to-ticks", "5"][..], 3),
+        (
+            &["--quiet", "--resample-to-ticks", "5", "--forward-fill"],
+            5,
+        ),
+    ] {
+        let output = run(args);
+        assert!(output.status.success(), "{:?}", args);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout.lines().count(), snapshots, "{:?}", args);
+    }
+    assert!(!run(&["--forward-fill"]).status.success());
+}
+
+#[test]
+fn canonical_output_matches_golden_file() {
+    let output = run(&["--canonical"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write("tests/fixtures/canonical.txt", &stdout).unwrap();
+    }
+    assert_eq!(stdout, include_str!("fixtures/canonical.txt"));
+    // and the same again
+    assert_eq!(run(&["--canonical"]).stdout, stdout.as_bytes());
+}
+
+#[test]
+fn reports_quote_rate() {
+    // the sample's quotes arrive 1, 2 and 7ms into a whole second
+    let output = run(&["--stats-only", "--rate-buckets", "5ms"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("Quote Rate (2 buckets of 5ms"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("    |█▄|\n"), "{}", stdout);
+    assert!(!run(&["--rate-buckets", "soon"]).status.success());
+    assert!(!run(&["--rate-buckets", "0s"]).status.success());
+}
+
+#[test]
+fn prints_interim_stats() {
+    let pcap = std::env::temp_dir().join(format!("tsc-cli-interim-{}.pcap", std::process::id()));
+    let payload = PriceQuoteBuilder::new().build().encode_b6034();
+    let packets: Vec<(Duration, u16, &[u8])> = (0..2048)
+        .map(|_| (Duration::ZERO, 15515, payload.as_slice()))
+        .collect();
+    std::fs::write(&pcap, write_capture(&packets)).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_tsc-solution"))
+        .arg(&pcap)
+        .args(["--stats-only", "--stats-interval", "1ns"])
+        .output()
+        .unwrap();
+    std::fs::remove_file(&pcap).unwrap();
+
+    assert!(output.status.success());
+    // the clock is only looked at every 1024 packets
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let lines: Vec<&str> = stderr.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", stderr);
+    assert!(lines[0].starts_with("interim: "));
+    assert!(lines[0].contains(" 1024 packets, 1024 quotes, "));
+    assert!(lines[1].contains(" 2048 packets, 2048 quotes, "));
+    assert!(lines[1].contains("% read, about "));
+    // and the final stats don't change
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Packet Parse Stats:"));
+    assert!(!stdout.contains("interim"));
+
+    assert!(!run(&["--stats-interval", "0s"]).status.success());
+}
+
+#[test]
+fn exits_with_status_3_past_a_threshold() {
+    // the sample has three quotes, none of them failing
+    let output = run(&["--count", "--max-failed", "0", "--min-quotes", "3"]);
+    assert!(output.status.success());
+
+    for args in [
+        ["--count", "--min-quotes", "4"],
+        ["--sorted", "--min-quotes", "4"],
+        ["--headers-only", "--min-quotes", "4"],
+    ] {
+        let output = run(&args);
+        assert_eq!(output.status.code(), Some(3), "{:?}", args);
+        // the output and stats still come out first
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("  Threshold Violated: 3 quotes decoded, under --min-quotes 4\n"));
+        assert_eq!(
+            String::from_utf8(output.stderr).unwrap(),
+            "error: 3 quotes decoded, under --min-quotes 4\n"
+        );
+    }
+}
+
+#[test]
+fn reports_the_prefix_of_wrong_prefix_packets() {
+    // the sample's third packet is an A3 message on a quote port
+    let output = run(&["-q", "--require-prefix-exact", "1"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "packet 3: port 15515 payload doesn't start with the expected prefix, saw \"A3034000\" (4133303334303030)\n"
+    );
+
+    // nothing said without the option
+    let output = run(&["-q"]);
+    assert!(!String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("expected prefix"));
+}
+
+#[test]
+fn assert_sorted_fails_on_out_of_order_accept_times() {
+    let dir = std::env::temp_dir();
+    let pcap = dir.join(format!("tsc-cli-unsorted-{}.pcap", std::process::id()));
+    // accept times follow seq, so the second quote was accepted first
+    let quotes = sample_quotes();
+    let payloads: Vec<Vec<u8>> = [&quotes[1], &quotes[0], &quotes[2]]
+        .iter()
+        .map(|q| q.encode_b6034())
+        .collect();
+    let packets: Vec<(Duration, u16, &[u8])> = payloads
+        .iter()
+        .map(|payload| (Duration::ZERO, 15515, payload.as_slice()))
+        .collect();
+    std::fs::write(&pcap, write_capture(&packets)).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_tsc-solution"))
+        .arg(&pcap)
+        .arg("--assert-sorted")
+        .output()
+        .unwrap();
+    std::fs::remove_file(&pcap).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: quotes not sorted by accept time: quote 0 (KR4301F42959 09:00:00.020) comes before quote 1 (KR4301F42629 09:00:00.010)\n"
+    );
+    assert!(output.stdout.is_empty());
+
+    // the sample itself is in order
+    assert!(run(&["-q", "--assert-sorted"]).status.success());
+}
+
+#[test]
+fn counts_quotes_per_issue_code_prefix() {
+    let output = run(&["-q", "--prefix-histogram", "8"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.ends_with(
+            "\nIssue Code Prefixes:\n  KR4301F4        2 (66.67%)\n  KR4301F6        1 (33.33%)\n"
+        ),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn reports_a_cbor_output_it_cant_write() {
+    let path = std::env::temp_dir().join("tsc-cli-no-such-dir/quotes.cbor");
+    let output = run(&["--output", "cbor", "--output-path", path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.starts_with(&format!("error: couldn't write {}: ", path.display())),
+        "{stderr}"
+    );
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "cli")]
+
+use std::time::Duration;
+
+use tsc_solution::csv_writer::{CsvWriter, ZstdCsvWriter};
+use tsc_solution::testdata::sample_quotes;
+use tsc_solution::PriceQuoteBuilder;
+
+#[test]
+fn writes_one_row_per_quote() {
+    let crossed = PriceQuoteBuilder::new()
+        .rcv_time(Duration::new(1297814400, 123_456_000))
+        .bids(&[(535, 10)])
+        .asks(&[(530, 4)])
+        .build();
+    let one_sided = PriceQuoteBuilder::new().asks(&[]).build();
+
+    let mut csv = CsvWriter::new(vec![], 0).unwrap();
+    csv.write(&crossed).unwrap();
+    csv.write(&one_sided).unwrap();
+    let csv = String::from_utf8(csv.into_inner()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("packet_rcv_time,quote_accept_time,issue_code,"));
+    assert!(lines[0].ends_with(",best_ask_price_5th,best_ask_quantity_5th"));
+    assert_eq!(
+        lines[1],
+        "2011-02-16 00:00:00.123456,09:00:00.010,KR4301F42629,1,40,-5,532.5,10,4,\
+         535,10,0,0,0,0,0,0,0,0,530,4,0,0,0,0,0,0,0,0"
+    );
+    // nothing on the ask, so no spread or mid
+    assert!(lines[2].contains(",40,,,74,0,"));
+    let columns = |line: &str| line.split(',').count();
+    assert!(lines.iter().all(|line| columns(line) == columns(lines[0])));
+}
+
+#[test]
+fn compresses_without_a_plain_copy() {
+    let dir = std::env::temp_dir().join(format!("tsc-zstd-csv-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("quotes.csv.zst");
+
+    let quotes: Vec<_> = sample_quotes().into_iter().cycle().take(3000).collect();
+    let mut writer = ZstdCsvWriter::create(path.to_str().unwrap(), 19, 0).unwrap();
+    for quote in &quotes {
+        writer.write(quote).unwrap();
+    }
+    writer.finish().unwrap();
+
+    let mut plain = CsvWriter::new(vec![], 0).unwrap();
+    for quote in &quotes {
+        plain.write(quote).unwrap();
+    }
+    let plain = plain.into_inner();
+
+    let compressed = std::fs::read(&path).unwrap();
+    assert_eq!(zstd::decode_all(&compressed[..]).unwrap(), plain);
+    assert!(compressed.len() * 10 < plain.len());
+    // the compressed file is the only thing written
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
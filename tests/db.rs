@@ -0,0 +1,96 @@
+#![cfg(feature = "duckdb")]
+
+use std::time::Duration;
+
+use duckdb::Connection;
+use tsc_solution::db::write_quotes_to_duckdb;
+use tsc_solution::db::{create_duckdb_index, create_duckdb_schema, insert_quotes, BATCH_SIZE};
+use tsc_solution::PriceQuoteBuilder;
+
+#[test]
+fn writes_quotes_to_a_table() {
+    let path = std::env::temp_dir().join(format!("tsc-duckdb-{}.db", std::process::id()));
+    let quotes = [
+        PriceQuoteBuilder::new()
+            .issue_code("KR4301F42629")
+            .seq(1)
+            .rcv_time(Duration::new(1297814400, 123_456_000))
+            .bids(&[(535, 10), (530, 3)])
+            .asks(&[(540, 4)])
+            .bid_orders(0, 7)
+            .build(),
+        PriceQuoteBuilder::new()
+            .issue_code("KR4301F42959")
+            .seq(2)
+            .build(),
+    ];
+    write_quotes_to_duckdb(&quotes, path.to_str().unwrap(), 0).unwrap();
+
+    let conn = Connection::open(&path).unwrap();
+    let row: (String, i32, String, String, f64, u64, u64) = conn
+        .query_row(
+            "SELECT issue_code, issue_seq_no, CAST(packet_rcv_time AS VARCHAR), \
+             CAST(quote_accept_time AS VARCHAR), best_bid_price_2nd, best_bid_quantity_1st, \
+             no_of_best_bid_quote_1st FROM quotes ORDER BY issue_seq_no LIMIT 1",
+            [],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                    row.get(6)?,
+                ))
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        row,
+        (
+            "KR4301F42629".to_string(),
+            1,
+            "2011-02-16 00:00:00.123456".to_string(),
+            "09:00:00.01".to_string(),
+            530.0,
+            10,
+            7
+        )
+    );
+
+    let indexes: i64 = conn
+        .query_row(
+            "SELECT count(*) FROM duckdb_indexes() WHERE table_name = 'quotes'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(indexes, 1);
+
+    // writing again appends
+    drop(conn);
+    write_quotes_to_duckdb(&quotes, path.to_str().unwrap(), 0).unwrap();
+    let conn = Connection::open(&path).unwrap();
+    let count: i64 = conn
+        .query_row("SELECT count(*) FROM quotes", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 4);
+    drop(conn);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn inserts_across_batches() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    create_duckdb_schema(&conn).unwrap();
+    let quotes: Vec<_> = (0..BATCH_SIZE as u32 + 5)
+        .map(|seq| PriceQuoteBuilder::new().seq(seq).build())
+        .collect();
+    insert_quotes(&mut conn, &quotes, 0).unwrap();
+    create_duckdb_index(&conn).unwrap();
+    let count: usize = conn
+        .query_row("SELECT count(*) FROM quotes", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, quotes.len());
+}
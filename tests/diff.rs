@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use tsc_solution::diff::{content_diff, diff_quote_sets, FieldChange, QuoteChange};
+use tsc_solution::PriceQuoteBuilder;
+
+// the builder's accept time follows seq, so seq picks the match key
+fn quote(issue_code: &str, seq: u32) -> PriceQuoteBuilder {
+    PriceQuoteBuilder::new().issue_code(issue_code).seq(seq)
+}
+
+#[test]
+fn identical_sets_have_no_changes() {
+    let a = [
+        quote("KR4301F42629", 1).build(),
+        quote("KR4301F42959", 2).build(),
+    ];
+    // capture times don't count
+    let b = [
+        quote("KR4301F42629", 1)
+            .rcv_time(Duration::from_secs(5))
+            .build(),
+        quote("KR4301F42959", 2).build(),
+    ];
+    let diff = diff_quote_sets(&a, &b);
+    assert!(diff.is_empty());
+    assert_eq!(diff.unchanged, 2);
+}
+
+#[test]
+fn unmatched_quotes_are_removed_or_added() {
+    let a = [
+        quote("KR4301F42629", 1).build(),
+        quote("KR4301F42629", 2).build(),
+    ];
+    let b = [
+        quote("KR4301F42629", 2).build(),
+        quote("KR4301F42959", 3).build(),
+    ];
+    let diff = diff_quote_sets(&a, &b);
+    assert_eq!(diff.unchanged, 1);
+    assert_eq!(
+        diff.changes,
+        [QuoteChange::Removed(&a[0]), QuoteChange::Added(&b[1])]
+    );
+    assert_eq!((diff.removed(), diff.added(), diff.changed()), (1, 1, 0));
+}
+
+#[test]
+fn matched_quotes_list_the_fields_that_differ() {
+    let a = [quote("KR4301F42629", 1)
+        .bids(&[(535, 10), (530, 3)])
+        .asks(&[(540, 4)])
+        .build()];
+    let b = [quote("KR4301F42629", 1)
+        .bids(&[(535, 12), (530, 3)])
+        .asks(&[(545, 4)])
+        .build()];
+    let diff = diff_quote_sets(&a, &b);
+    let QuoteChange::Changed { fields, .. } = &diff.changes[0] else {
+        panic!("{:?}", diff.changes);
+    };
+    let field = |field: &'static str, a: &str, b: &str| FieldChange {
+        field,
+        a: a.to_string(),
+        b: b.to_string(),
+    };
+    assert_eq!(
+        fields,
+        &[
+            field("total_bid_quote_volume", "13", "15"),
+            field("best_bid_quantity_1st", "10", "12"),
+            field("best_ask_price_1st", "540", "545"),
+        ]
+    );
+}
+
+#[test]
+fn repeated_keys_match_in_file_order() {
+    let a = [
+        quote("KR4301F42629", 1).bids(&[(535, 1)]).build(),
+        quote("KR4301F42629", 1).bids(&[(535, 2)]).build(),
+    ];
+    let b = [quote("KR4301F42629", 1).bids(&[(535, 1)]).build()];
+    let diff = diff_quote_sets(&a, &b);
+    assert_eq!(diff.unchanged, 1);
+    assert_eq!(diff.changes, [QuoteChange::Removed(&a[1])]);
+}
+
+#[test]
+fn content_diff_matches_whole_quotes() {
+    let a = [
+        quote("KR4301F42629", 1).build(),
+        quote("KR4301F42629", 1).build(),
+        quote("KR4301F42959", 2).build(),
+    ];
+    // capture times don't count, everything else does
+    let b = [
+        quote("KR4301F42629", 1)
+            .rcv_time(Duration::from_secs(5))
+            .build(),
+        quote("KR4301F42959", 2).asks(&[(545, 4)]).build(),
+    ];
+    let diff = content_diff(&a, &b);
+    assert_eq!(diff.only_in_a, [&a[1], &a[2]]);
+    assert_eq!(diff.only_in_b, [&b[1]]);
+    assert!(content_diff(&a, &a).is_empty());
+}
+
+#[test]
+fn identical_quotes_match_before_keys_do() {
+    let a = [quote("KR4301F42629", 1).bids(&[(535, 1)]).build()];
+    let b = [
+        quote("KR4301F42629", 1).bids(&[(535, 2)]).build(),
+        quote("KR4301F42629", 1).bids(&[(535, 1)]).build(),
+    ];
+    let diff = diff_quote_sets(&a, &b);
+    assert_eq!(diff.unchanged, 1);
+    assert_eq!(diff.changes, [QuoteChange::Added(&b[0])]);
+
+    // the same quotes differ as the diff subcommand sees them
+    let content = content_diff(&a, &b);
+    assert!(content.only_in_a.is_empty());
+    assert_eq!(content.only_in_b, [&b[0]]);
+}
@@ -0,0 +1,181 @@
+#![cfg(feature = "cli")]
+
+use tsc_solution::price::{Price, PriceFormat, Qty};
+use tsc_solution::price_quote::{format_pairs, DisplayStyle};
+use tsc_solution::printer::{QuotePrinter, SpreadChange, SpreadThresholds};
+use tsc_solution::{PriceQuote, PriceQuoteBuilder};
+
+const PLAIN: &str = "2011-02-16 00:00:00 09:00:00.01 KR4301F42629  \
+3@505 5@510 10@515 32@520 24@525  1@530 7@535 12@540 4@545 9@550";
+
+const COLORED: &str = "2011-02-16 00:00:00 \x1b[34m09:00:00.01\x1b[0m \x1b[33mKR4301F42629\x1b[0m  \
+3\x1b[31m@\x1b[0m505 5\x1b[31m@\x1b[0m510 10\x1b[31m@\x1b[0m515 32\x1b[31m@\x1b[0m520 24\x1b[31m@\x1b[0m525  \
+1\x1b[31m@\x1b[0m530 7\x1b[31m@\x1b[0m535 12\x1b[31m@\x1b[0m540 4\x1b[31m@\x1b[0m545 9\x1b[31m@\x1b[0m550";
+
+// drops ansi escape sequences, enough for the sgr codes colored emits
+fn strip_ansi(s: &str) -> String {
+    let mut result = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[test]
+fn display_is_plain() {
+    let quote = PriceQuote::example();
+    assert_eq!(quote.to_string(), PLAIN);
+    assert_eq!(quote.fmt_plain(), PLAIN);
+}
+
+#[test]
+fn colored_matches_plain() {
+    colored::control::set_override(true);
+    let colored = PriceQuote::example().fmt_colored();
+    assert_eq!(colored, COLORED);
+    assert_eq!(strip_ansi(&colored), PLAIN);
+}
+
+#[test]
+fn format_pairs_styles() {
+    colored::control::set_override(true);
+    let pairs = [(Price(100), Qty(2)), (Price(105), Qty(0))];
+    assert_eq!(
+        format_pairs(&pairs, DisplayStyle::Plain, PriceFormat::default()),
+        " 2@100 0@105"
+    );
+    let colored = format_pairs(&pairs, DisplayStyle::Colored, PriceFormat::default());
+    assert_eq!(colored, " 2\x1b[31m@\x1b[0m100 0\x1b[31m@\x1b[0m105");
+    assert_eq!(strip_ansi(&colored), " 2@100 0@105");
+}
+
+#[test]
+fn prices_follow_scale_and_precision() {
+    let show = |price, scale, precision| {
+        Price(price)
+            .display(PriceFormat::new(scale, precision))
+            .to_string()
+    };
+    assert_eq!(Price(52_550).to_string(), "52550");
+    assert_eq!(show(52_550, 0, None), "52550");
+    assert_eq!(show(52_550, 2, None), "525.50");
+    assert_eq!(show(52_550, 2, Some(0)), "526");
+    assert_eq!(show(52_549, 2, Some(1)), "525.5");
+    assert_eq!(show(52_550, 2, Some(4)), "525.5000");
+    assert_eq!(show(5, 2, None), "0.05");
+
+    let pairs = [(Price(52_550), Qty(2))];
+    assert_eq!(
+        format_pairs(&pairs, DisplayStyle::Plain, PriceFormat::new(2, None)),
+        " 2@525.50"
+    );
+}
+
+#[test]
+fn colors_spreads_by_how_they_moved() {
+    colored::control::set_override(true);
+    let quote = |issue_code, bid, ask| {
+        PriceQuoteBuilder::new()
+            .issue_code(issue_code)
+            .bid(0, bid, 1)
+            .ask(0, ask, 1)
+            .build()
+    };
+
+    let mut printer = QuotePrinter::new(true, DisplayStyle::Colored);
+    let first = quote("KR4301F42629", 525, 530);
+    assert_eq!(
+        printer.format(&first),
+        format!("{} spread 5", first.fmt_colored())
+    );
+    let narrowed = quote("KR4301F42629", 527, 530);
+    assert_eq!(
+        printer.format(&narrowed),
+        format!("{} spread \x1b[32m3\x1b[0m", narrowed.fmt_colored())
+    );
+    let widened = quote("KR4301F42629", 520, 530);
+    assert_eq!(
+        printer.format(&widened),
+        format!("{} spread \x1b[31m10\x1b[0m", widened.fmt_colored())
+    );
+    // each issue against its own last spread
+    let other = quote("KR4301F42959", 529, 530);
+    assert_eq!(
+        printer.format(&other),
+        format!("{} spread 1", other.fmt_colored())
+    );
+
+    let mut printer = QuotePrinter::new(true, DisplayStyle::Plain);
+    assert_eq!(
+        printer.observe(&first),
+        Some((Price(5), SpreadChange::First))
+    );
+    assert_eq!(
+        printer.observe(&narrowed),
+        Some((Price(3), SpreadChange::Narrowed))
+    );
+    assert_eq!(
+        printer.observe(&widened),
+        Some((Price(10), SpreadChange::Widened))
+    );
+    assert_eq!(
+        printer.observe(&widened),
+        Some((Price(10), SpreadChange::Unchanged))
+    );
+    // no colors off a terminal
+    assert_eq!(
+        printer.format(&narrowed),
+        format!("{} spread 3", narrowed.fmt_plain())
+    );
+}
+
+#[test]
+fn colors_whole_lines_by_spread() {
+    colored::control::set_override(true);
+    let thresholds = SpreadThresholds::parse("2,10").unwrap();
+    assert_eq!(thresholds, SpreadThresholds::default());
+    assert_eq!(
+        SpreadThresholds::parse(" 5 , 5"),
+        Some(SpreadThresholds { tight: 5, wide: 5 })
+    );
+    assert_eq!(SpreadThresholds::parse("10,2"), None);
+    assert_eq!(SpreadThresholds::parse("2"), None);
+
+    let mut printer = QuotePrinter::new(false, DisplayStyle::Colored);
+    printer.line_colors = Some(thresholds);
+    let quote = |bid, ask| {
+        PriceQuoteBuilder::new()
+            .issue_code("KR4301F42629")
+            .bid(0, bid, 1)
+            .ask(0, ask, 1)
+            .build()
+    };
+    // green up to 2 ticks, yellow up to 10 and red past that
+    for (bid, ask, code) in [
+        (528, 530, "32"),
+        (527, 530, "33"),
+        (520, 530, "33"),
+        (519, 530, "31"),
+    ] {
+        let line = printer.format(&quote(bid, ask));
+        assert!(line.starts_with(&format!("\x1b[{}m", code)), "{}", line);
+        assert!(line.ends_with("\x1b[0m"));
+        // and nothing colored inside
+        assert_eq!(line.matches('\x1b').count(), 2);
+    }
+    // no spread, no color
+    let one_sided = PriceQuoteBuilder::new().asks(&[]).build();
+    assert_eq!(printer.format(&one_sided), one_sided.fmt_plain());
+
+    // only ever on a terminal, like the other colors
+    printer.style = DisplayStyle::Plain;
+    assert_eq!(
+        printer.format(&quote(519, 530)),
+        quote(519, 530).fmt_plain()
+    );
+}
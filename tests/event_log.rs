@@ -0,0 +1,48 @@
+#![cfg(feature = "cli")]
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use tsc_solution::schema::JSON_SCHEMA_VERSION;
+use tsc_solution::testdata::sample_capture;
+use tsc_solution::{parse_price_quotes_from_bytes, ParseOptions};
+
+fn read_events(path: &std::path::Path) -> Vec<Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn logs_every_packet_outcome() {
+    let path = std::env::temp_dir().join(format!("tsc-events-{}.jsonl", std::process::id()));
+    let options = ParseOptions {
+        event_log: Some(path.display().to_string()),
+        ..Default::default()
+    };
+    parse_price_quotes_from_bytes(&sample_capture().build(), &options).unwrap();
+    let events = read_events(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let mut counts = BTreeMap::new();
+    for event in &events {
+        *counts
+            .entry(event["event_type"].as_str().unwrap())
+            .or_insert(0) += 1;
+    }
+    assert_eq!(counts, BTreeMap::from([("quote", 3), ("rejected", 4)]));
+
+    let first = &events[0];
+    assert_eq!(first["timestamp"].as_f64().unwrap(), 1297814400.001);
+    assert_eq!(first["detail"]["issue_code"], "KR4301F42629");
+    assert_eq!(first["detail"]["packet"], 1);
+    assert_eq!(events[3]["detail"]["reason"], "wrong port 9999");
+
+    for event in &events {
+        assert_eq!(event["schema_version"], JSON_SCHEMA_VERSION);
+        assert_eq!(event["crate_version"], env!("CARGO_PKG_VERSION"));
+    }
+}
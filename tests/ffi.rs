@@ -0,0 +1,60 @@
+#![cfg(feature = "ffi")]
+
+use std::ffi::CStr;
+use std::mem::{offset_of, size_of, MaybeUninit};
+use std::ptr;
+
+use tsc_solution::ffi::{
+    tsc_decode_quote, TscLevel, TscQuote, TSC_ERR_INVALID, TSC_ERR_NULL, TSC_ERR_SHORT, TSC_OK,
+};
+
+const QUOTE_PAYLOAD: &[u8] = include_bytes!("fixtures/b6034_quote.bin");
+
+fn decode(bytes: &[u8]) -> (i32, MaybeUninit<TscQuote>) {
+    let mut out = MaybeUninit::<TscQuote>::uninit();
+    let status = unsafe { tsc_decode_quote(bytes.as_ptr(), bytes.len(), out.as_mut_ptr()) };
+    (status, out)
+}
+
+#[test]
+fn layout_matches_header() {
+    assert_eq!(size_of::<TscLevel>(), 24);
+    assert_eq!(offset_of!(TscQuote, issue_code), 5);
+    assert_eq!(offset_of!(TscQuote, issue_seq_no), 20);
+    assert_eq!(offset_of!(TscQuote, total_bid_quote_volume), 32);
+    assert_eq!(offset_of!(TscQuote, bids), 48);
+    assert_eq!(offset_of!(TscQuote, asks), 168);
+    assert_eq!(offset_of!(TscQuote, quote_accept_time), 304);
+    assert_eq!(size_of::<TscQuote>(), 312);
+}
+
+#[test]
+fn decodes_through_c_abi() {
+    let (status, out) = decode(QUOTE_PAYLOAD);
+    assert_eq!(status, TSC_OK);
+    let quote = unsafe { out.assume_init() };
+
+    let issue_code = unsafe { CStr::from_ptr(quote.issue_code.as_ptr()) };
+    assert_eq!(issue_code.to_str().unwrap(), "KR4301F42629");
+    assert_eq!(quote.total_bid_quote_volume, 1299);
+    assert_eq!(quote.bids[0].price, 525);
+    assert_eq!(quote.bids[0].qty, 24);
+    assert_eq!(quote.asks[0].price, 630);
+    assert_eq!(quote.asks[4].price, 650);
+}
+
+#[test]
+fn reports_bad_input() {
+    assert_eq!(decode(&QUOTE_PAYLOAD[..100]).0, TSC_ERR_SHORT);
+
+    // first digit of total_bid_quote_volume
+    let mut corrupt = QUOTE_PAYLOAD.to_vec();
+    corrupt[22] = b'x';
+    assert_eq!(decode(&corrupt).0, TSC_ERR_INVALID);
+
+    let mut out = MaybeUninit::<TscQuote>::uninit();
+    let status = unsafe { tsc_decode_quote(ptr::null(), 0, out.as_mut_ptr()) };
+    assert_eq!(status, TSC_ERR_NULL);
+    let status = unsafe { tsc_decode_quote(QUOTE_PAYLOAD.as_ptr(), 0, ptr::null_mut()) };
+    assert_eq!(status, TSC_ERR_NULL);
+}
@@ -0,0 +1,124 @@
+#![cfg(feature = "cli")]
+
+use std::time::Duration;
+
+use tsc_solution::fix::{CompIds, QuickFixStore, DEFAULT_SENDER, DEFAULT_TARGET};
+use tsc_solution::PriceQuoteBuilder;
+
+// tag=value pairs, in order
+fn fields(message: &str) -> Vec<(&str, &str)> {
+    message
+        .split_terminator('\x01')
+        .map(|field| field.split_once('=').unwrap())
+        .collect()
+}
+
+fn field<'a>(message: &'a str, tag: &str) -> Vec<&'a str> {
+    fields(message)
+        .into_iter()
+        .filter(|(t, _)| *t == tag)
+        .map(|(_, value)| value)
+        .collect()
+}
+
+// BodyLength and CheckSum agree with the rest of the message
+fn assert_well_formed(message: &str) {
+    let body_start = message.find("\x0135=").unwrap() + 1;
+    let checksum_start = message.rfind("10=").unwrap();
+    let body_len: usize = field(message, "9")[0].parse().unwrap();
+    assert_eq!(body_len, checksum_start - body_start);
+    let sum = message[..checksum_start]
+        .bytes()
+        .fold(0u8, |sum, b| sum.wrapping_add(b));
+    assert_eq!(field(message, "10"), [format!("{:03}", sum).as_str()]);
+    assert!(message.starts_with("8=FIX.4.2\x019="));
+    assert!(message.ends_with('\x01'));
+}
+
+#[test]
+fn writes_a_request_and_snapshot_per_quote() {
+    let quote = PriceQuoteBuilder::new()
+        .issue_code("KR4301F42629")
+        .rcv_time(Duration::new(1297814400, 123_456_000))
+        .bids(&[(535, 10), (530, 3)])
+        .asks(&[(540, 4)])
+        .build();
+
+    let mut seq = 7;
+    let (request, snapshot) =
+        quote.to_quickfix_messages_with(&mut seq, &CompIds::new("FEED", "DESK"), 0);
+    assert_eq!(seq, 9);
+    assert_well_formed(&request);
+    assert_well_formed(&snapshot);
+
+    assert_eq!(field(&request, "35"), ["V"]);
+    assert_eq!(field(&request, "34"), ["7"]);
+    assert_eq!(field(&request, "49"), ["FEED"]);
+    assert_eq!(field(&request, "56"), ["DESK"]);
+    assert_eq!(field(&request, "52"), ["20110216-00:00:00.123"]);
+    assert_eq!(field(&request, "55"), ["KR4301F42629"]);
+    assert_eq!(field(&request, "269"), ["0", "1"]);
+
+    assert_eq!(field(&snapshot, "35"), ["W"]);
+    assert_eq!(field(&snapshot, "34"), ["8"]);
+    assert_eq!(field(&snapshot, "262"), field(&request, "262"));
+    // empty levels aren't entries
+    assert_eq!(field(&snapshot, "268"), ["3"]);
+    assert_eq!(field(&snapshot, "269"), ["0", "0", "1"]);
+    assert_eq!(field(&snapshot, "270"), ["535", "530", "540"]);
+    assert_eq!(field(&snapshot, "271"), ["10", "3", "4"]);
+    assert_eq!(field(&snapshot, "290"), ["1", "2", "1"]);
+
+    // without comp ids it's the defaults, and prices as they came
+    let mut seq = 7;
+    let (request, snapshot) = quote.to_quickfix_messages(&mut seq);
+    assert_eq!(seq, 9);
+    assert_eq!(field(&request, "49"), [DEFAULT_SENDER]);
+    assert_eq!(field(&request, "56"), [DEFAULT_TARGET]);
+    assert_eq!(field(&snapshot, "270"), ["535", "530", "540"]);
+}
+
+#[test]
+fn writes_a_file_store() {
+    let prefix = std::env::temp_dir()
+        .join(format!("tsc-quickfix-{}", std::process::id()))
+        .to_str()
+        .unwrap()
+        .to_string();
+    let quotes = [
+        PriceQuoteBuilder::new().issue_code("KR4301F42629").build(),
+        PriceQuoteBuilder::new().issue_code("KR4301F42959").build(),
+    ];
+    let mut store = QuickFixStore::create(&prefix, CompIds::default(), 0).unwrap();
+    for quote in &quotes {
+        store.write(quote).unwrap();
+    }
+    store.finish().unwrap();
+
+    let read = |suffix: &str| std::fs::read(format!("{}.{}", prefix, suffix)).unwrap();
+    let body = read("body");
+    let header = read("header");
+    assert_eq!(header.len(), 4 * 16);
+    // each entry is seq, offset and length, and they tile the body
+    let mut offset = 0;
+    for (i, entry) in header.chunks(16).enumerate() {
+        let seq = i32::from_be_bytes(entry[0..4].try_into().unwrap());
+        let at = i64::from_be_bytes(entry[4..12].try_into().unwrap());
+        let len = i32::from_be_bytes(entry[12..16].try_into().unwrap());
+        assert_eq!(seq, i as i32 + 1);
+        assert_eq!(at, offset);
+        let message = std::str::from_utf8(&body[at as usize..(at + len as i64) as usize]).unwrap();
+        assert_well_formed(message);
+        assert_eq!(field(message, "34"), [seq.to_string().as_str()]);
+        assert_eq!(field(message, "49"), [DEFAULT_SENDER]);
+        assert_eq!(field(message, "56"), [DEFAULT_TARGET]);
+        offset += len as i64;
+    }
+    assert_eq!(offset as usize, body.len());
+    assert_eq!(read("senderseqnums"), b"\x00\x015");
+    assert_eq!(read("targetseqnums"), b"\x00\x011");
+
+    for suffix in ["body", "header", "senderseqnums", "targetseqnums"] {
+        std::fs::remove_file(format!("{}.{}", prefix, suffix)).unwrap();
+    }
+}
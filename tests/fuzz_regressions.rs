@@ -0,0 +1,46 @@
+#![cfg(feature = "cli")]
+
+// replays every input the fuzz targets ever crashed on, so they stay fixed
+// without needing nightly and cargo fuzz
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use tsc_solution::PriceQuote;
+
+fn regressions(target: &str) -> Vec<Vec<u8>> {
+    let dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "fuzz", "regressions", target]
+        .iter()
+        .collect();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    entries
+        .map(|entry| fs::read(entry.unwrap().path()).unwrap())
+        .collect()
+}
+
+#[test]
+fn from_bytes_regressions() {
+    let inputs = regressions("from_bytes");
+    assert!(!inputs.is_empty());
+    for data in inputs {
+        if let Ok(quote) = PriceQuote::from_bytes(Duration::ZERO, &data) {
+            let _ = quote.to_string();
+        }
+        if let Ok(header) = PriceQuote::header_from_bytes(Duration::ZERO, &data) {
+            let _ = header.to_string();
+        }
+    }
+}
+
+#[test]
+fn accept_time_that_isnt_ascii_still_displays() {
+    let mut payload = include_bytes!("fixtures/b6034_quote.bin").to_vec();
+    // accept time is the 8 bytes before the end of message byte
+    let end = payload.len() - 1;
+    payload[end - 8..end].copy_from_slice("0900€0".as_bytes());
+    let quote = PriceQuote::from_bytes(Duration::ZERO, &payload).unwrap();
+    assert!(quote.to_string().contains(" 09:00:??.?0 "));
+}
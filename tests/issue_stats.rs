@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use tsc_solution::issue_stats::{format_issue_stats, format_top_issues, IssueStatsCollector};
+use tsc_solution::price::{Price, PriceFormat};
+use tsc_solution::PriceQuoteBuilder;
+
+#[test]
+fn tallies_each_issue() {
+    let at = |ms: u64| Duration::from_millis(32_400_000 + ms);
+    let quotes = vec![
+        PriceQuoteBuilder::new()
+            .rcv_time(at(0))
+            .bids(&[(100, 10)])
+            .asks(&[(110, 20)])
+            .build(),
+        PriceQuoteBuilder::new()
+            .issue_code("KR4301F00002")
+            .rcv_time(at(100))
+            .asks(&[])
+            .build(),
+        PriceQuoteBuilder::new()
+            .rcv_time(at(2000))
+            .bids(&[(100, 4)])
+            .asks(&[(130, 25)])
+            .build(),
+        // one sided, so no spread, but its volumes still move
+        PriceQuoteBuilder::new()
+            .rcv_time(at(1500))
+            .bids(&[(100, 7)])
+            .asks(&[])
+            .build(),
+    ];
+
+    let collector = IssueStatsCollector::from_quotes(&quotes);
+    assert_eq!(collector.len(), 2);
+    let issues = collector.finish(None);
+    assert_eq!(issues[0].issue_code, "KR4301F42629");
+    assert_eq!(issues[0].quotes, 3);
+    assert_eq!(issues[0].first_rcv_time, at(0));
+    assert_eq!(issues[0].last_rcv_time, at(2000));
+    assert_eq!(issues[0].min_spread, Some(Price(10)));
+    assert_eq!(issues[0].max_spread, Some(Price(30)));
+    assert_eq!(issues[0].mean_spread, Some(20.0));
+    assert_eq!(issues[0].bid_volume_delta, 6 + 3);
+    assert_eq!(issues[0].ask_volume_delta, 5 + 25);
+    assert_eq!(issues[0].mean_bid_levels, 1.0);
+    assert!((issues[0].mean_ask_levels - 2.0 / 3.0).abs() < 1e-12);
+
+    assert_eq!(issues[1].issue_code, "KR4301F00002");
+    assert_eq!(issues[1].min_spread, None);
+    assert_eq!(issues[1].mean_spread, None);
+    assert_eq!(issues[1].bid_volume_delta, 0);
+
+    assert_eq!(collector.finish(Some(1)).len(), 1);
+
+    let table = format_issue_stats(&issues, PriceFormat::default());
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("Issue"));
+    assert!(lines[1].starts_with("KR4301F42629        3 09:00:00.000 09:00:02.000"));
+    assert!(lines[2].contains(" - "));
+    assert!(lines[1].ends_with("     1.00     0.67"), "{}", lines[1]);
+}
+
+#[test]
+fn ranks_the_most_active_issues() {
+    let quote = |issue: &str, bid: u64, ask: u64| {
+        PriceQuoteBuilder::new()
+            .issue_code(issue)
+            .bids(&[(bid, 10)])
+            .asks(&[(ask, 20)])
+            .build()
+    };
+    let quotes = [
+        quote("KR4301F00001", 100, 110),
+        quote("KR4301F00002", 200, 210),
+        quote("KR4301F00003", 300, 310),
+        quote("KR4301F00002", 201, 211),
+        quote("KR4301F00003", 301, 311),
+        quote("KR4301F00003", 302, 312),
+    ];
+
+    let top = IssueStatsCollector::from_quotes(&quotes).finish(Some(2));
+    let ranked: Vec<(&str, u64)> = top
+        .iter()
+        .map(|stats| (stats.issue_code.as_str(), stats.quotes))
+        .collect();
+    assert_eq!(ranked, [("KR4301F00003", 3), ("KR4301F00002", 2)]);
+    assert_eq!(top[0].last_bid.price, Price(302));
+    assert_eq!(top[0].last_ask.price, Price(312));
+
+    let table = format_top_issues(&top, PriceFormat::default());
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("Issue"));
+    assert_eq!(
+        lines[1],
+        "KR4301F00003        3         10        302        312         20"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn shows_up_in_the_json_stats() {
+    let mut stats = tsc_solution::PacketParseStats::new();
+    let json = serde_json::to_value(&stats).unwrap();
+    assert!(json["per_issue"].is_null());
+
+    let quotes = [PriceQuoteBuilder::new().build()];
+    stats.per_issue = Some(IssueStatsCollector::from_quotes(&quotes).finish(None));
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(json["per_issue"][0]["issue_code"], "KR4301F42629");
+    assert_eq!(json["per_issue"][0]["quotes"], 1);
+    assert_eq!(json["per_issue"][0]["min_spread"], 5);
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "cli")]
+
+use std::collections::HashSet;
+
+use tsc_solution::mock::write_mock_pcap;
+use tsc_solution::{parse_price_quotes_from_bytes, ParseOptions};
+
+#[test]
+fn mock_feed_parses_back() {
+    let pcap = write_mock_pcap(Vec::new(), 500, 7, 42).unwrap();
+    let (quotes, stats) = parse_price_quotes_from_bytes(&pcap, &ParseOptions::default()).unwrap();
+
+    assert_eq!(quotes.len(), 500);
+    assert_eq!(stats.packet_count, 500);
+    assert_eq!(stats.rejected, 0);
+    assert_eq!(stats.failed, 0);
+    assert_eq!(stats.volume_inconsistencies, 0);
+
+    let issues: HashSet<_> = quotes.iter().map(|q| q.issue_code.as_str()).collect();
+    assert_eq!(issues.len(), 7);
+    assert!(quotes
+        .windows(2)
+        .all(|w| w[0].packet_rcv_time < w[1].packet_rcv_time));
+    assert!(quotes.iter().all(|q| q.bids[0].price < q.asks[0].price));
+}
+
+#[test]
+fn mock_feed_is_deterministic_per_seed() {
+    let a = write_mock_pcap(Vec::new(), 50, 3, 7).unwrap();
+    let b = write_mock_pcap(Vec::new(), 50, 3, 7).unwrap();
+    let c = write_mock_pcap(Vec::new(), 50, 3, 8).unwrap();
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "cli")]
+
+use std::path::Path;
+use std::time::Duration;
+
+use npy::NpyData;
+use tsc_solution::npy_writer::write_quotes_to_npy;
+use tsc_solution::PriceQuoteBuilder;
+
+fn load<T: npy::Serializable>(dir: &Path, name: &str) -> Vec<T> {
+    let bytes = std::fs::read(dir.join(format!("{}.npy", name))).unwrap();
+    NpyData::from_bytes(&bytes).unwrap().to_vec()
+}
+
+#[test]
+fn writes_one_array_per_field() {
+    let dir = std::env::temp_dir().join(format!("tsc-npy-{}", std::process::id()));
+    let quotes = [
+        PriceQuoteBuilder::new()
+            .seq(1)
+            .rcv_time(Duration::new(1297814400, 123_456_000))
+            .bids(&[(535, 10), (530, 3)])
+            .asks(&[(540, 4)])
+            .bid_orders(0, 7)
+            .build(),
+        PriceQuoteBuilder::new().seq(2).bids(&[]).asks(&[]).build(),
+    ];
+
+    let written = write_quotes_to_npy(&quotes, &dir, 0).unwrap();
+    assert_eq!(written.len(), 6 + 6 * 5 + 2);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), written.len());
+
+    assert_eq!(
+        load::<i64>(&dir, "packet_rcv_time")[0],
+        1_297_814_400_123_456_000
+    );
+    // 09:00:00.01 and .02, in nanoseconds since midnight
+    assert_eq!(
+        load::<i64>(&dir, "quote_accept_time"),
+        [32_400_010_000_000, 32_400_020_000_000]
+    );
+    assert_eq!(load::<i64>(&dir, "issue_seq_no"), [1, 2]);
+    assert_eq!(load::<f64>(&dir, "best_bid_price_2nd"), [530.0, 0.0]);
+    assert_eq!(load::<u64>(&dir, "best_bid_quantity_1st"), [10, 0]);
+    assert_eq!(load::<f64>(&dir, "best_ask_price_1st"), [540.0, 0.0]);
+    assert_eq!(load::<u64>(&dir, "total_bid_quote_volume"), [13, 0]);
+    // order counts are the numbers the ascii spells
+    assert_eq!(load::<i64>(&dir, "no_of_best_bid_quote_1st"), [7, 1]);
+    assert_eq!(
+        load::<i64>(&dir, "no_of_best_bid_valid_quote_total"),
+        [5, 5]
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
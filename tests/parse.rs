@@ -0,0 +1,1177 @@
+#![cfg(feature = "cli")]
+
+use std::io::Cursor;
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::NaiveTime;
+use etherparse::SlicedPacket;
+
+use tsc_solution::decap::decapsulate;
+use tsc_solution::error::{HexQuoteError, PacketClassifyError, ParseFileError};
+use tsc_solution::market::MarketStatus;
+use tsc_solution::parser::B6034Parser;
+use tsc_solution::parsing::classify_packet;
+use tsc_solution::price::{Price, Qty};
+use tsc_solution::price_quote::{encode_hex, NumericEncoding};
+use tsc_solution::testdata::{
+    sample_capture, sample_quotes, udp_frame, write_capture, CaptureBuilder, Corruption,
+};
+use tsc_solution::{
+    clamp_accept_times, concat_price_quotes_from_readers,
+    concat_price_quotes_from_readers_parallel, concat_price_quotes_with,
+    concat_price_quotes_with_payload, concat_quote_headers_with, estimate_memory,
+    expand_capture_paths, filter_one_sided, merge_price_quotes_from_readers,
+    parse_price_quotes_from_bytes, parse_price_quotes_from_file, parse_price_quotes_with,
+    read_issue_list, InputFormat, PacketParseStats, ParseOptions, PortCounts, PriceQuote,
+    PriceQuoteBuilder, StatsInterval,
+};
+
+// a single B6034 payload for KR4301F42629, lifted from a real capture
+const QUOTE_PAYLOAD: &[u8] = include_bytes!("fixtures/b6034_quote.bin");
+
+// see testdata::sample_capture for what's in it
+fn sample() -> Vec<u8> {
+    sample_capture().build()
+}
+
+// a path under the temp dir no other test process will use
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("tsc-parse-{}-{}", std::process::id(), name))
+}
+
+fn parse_bytes(
+    bytes: &[u8],
+    options: &ParseOptions,
+) -> Result<(Vec<PriceQuote>, PacketParseStats), ParseFileError> {
+    parse_price_quotes_from_bytes(bytes, options)
+}
+
+#[test]
+fn decodes_payload_fixture() {
+    let quote = PriceQuote::from_bytes(Duration::from_secs(7), QUOTE_PAYLOAD).unwrap();
+
+    assert_eq!(quote.packet_rcv_time, Duration::from_secs(7));
+    assert_eq!(quote.issue_code, "KR4301F42629");
+    assert_eq!(quote.total_bid_quote_volume, Qty(1299));
+    assert_eq!(quote.best_bid_price_1st(), Price(525));
+    assert_eq!(quote.best_bid_quantity_1st(), Qty(24));
+    assert_eq!(quote.best_bid_price_2nd(), Price(520));
+    assert_eq!(quote.best_bid_quantity_2nd(), Qty(32));
+    assert_eq!(quote.best_ask_price_1st(), Price(630));
+    assert_eq!(quote.best_ask_quantity_1st(), Qty(1));
+    assert_eq!(quote.best_ask_price_5th(), Price(650));
+    assert_eq!(
+        quote
+            .accept_time()
+            .unwrap()
+            .format("%H:%M:%S%.3f")
+            .to_string(),
+        "09:00:00.000"
+    );
+}
+
+#[test]
+fn parses_hex_payload() {
+    let hex: Vec<String> = QUOTE_PAYLOAD.iter().map(|b| format!("{:02x}", b)).collect();
+    let expected = PriceQuote::from_bytes(Duration::ZERO, QUOTE_PAYLOAD).unwrap();
+
+    assert_eq!(hex.concat().parse::<PriceQuote>().unwrap(), expected);
+    assert_eq!(
+        format!("0x{}", hex.join("\n"))
+            .parse::<PriceQuote>()
+            .unwrap(),
+        expected
+    );
+    let spaced = hex
+        .iter()
+        .map(|b| format!("0X{}", b))
+        .collect::<Vec<_>>()
+        .join(" ");
+    assert_eq!(
+        PriceQuote::from_hex(Duration::from_secs(7), &spaced).unwrap(),
+        PriceQuote::from_bytes(Duration::from_secs(7), QUOTE_PAYLOAD).unwrap()
+    );
+
+    assert_eq!(
+        "42 36 3g".parse::<PriceQuote>(),
+        Err(HexQuoteError::InvalidChar {
+            offset: 7,
+            found: 'g'
+        })
+    );
+    assert_eq!(
+        "423".parse::<PriceQuote>(),
+        Err(HexQuoteError::OddLength { digits: 3 })
+    );
+    assert!(matches!(
+        "4236".parse::<PriceQuote>(),
+        Err(HexQuoteError::Decode(_))
+    ));
+}
+
+#[test]
+fn parses_sample_capture() {
+    let path = temp_path("sample.pcap");
+    std::fs::write(&path, sample()).unwrap();
+    let result = parse_price_quotes_from_file(path.to_str().unwrap(), &ParseOptions::default());
+    std::fs::remove_file(&path).unwrap();
+    let (quotes, stats) = result.unwrap();
+
+    assert_eq!(quotes, sample_quotes());
+    assert_eq!(stats.packet_count, 7);
+    assert_eq!(stats.successfully_parsed, 3);
+    assert_eq!(stats.rejected, 4);
+    assert_eq!(stats.failed, 0);
+    assert_eq!(stats.non_udp, 1);
+    assert_eq!(stats.wrong_port, 1);
+    assert_eq!(stats.not_a_price_quote, 1);
+    assert_eq!(stats.malformed_quote, 1);
+    assert_eq!(stats.checksum_failures, 0);
+    assert_eq!(
+        quotes[0].packet_rcv_time,
+        Duration::new(1297814400, 1_000_000)
+    );
+
+    // a real payload survives the trip through a capture untouched
+    let capture = CaptureBuilder::new()
+        .udp(Duration::from_secs(7), 15515, QUOTE_PAYLOAD)
+        .build();
+    let (quotes, _) = parse_bytes(&capture, &ParseOptions::default()).unwrap();
+    assert_eq!(
+        quotes,
+        [PriceQuote::from_bytes(Duration::from_secs(7), QUOTE_PAYLOAD).unwrap()]
+    );
+}
+
+#[test]
+fn classifies_every_kind_of_packet() {
+    let quote = PriceQuoteBuilder::new().build().encode_b6034();
+    let mut bad_digit = quote.clone();
+    bad_digit[30] = b'x';
+    let at = Duration::from_millis;
+    let capture = sample_capture()
+        .corrupted(at(8), 15515, &quote, Corruption::BadUdpChecksum)
+        .corrupted(at(9), 15515, &quote, Corruption::BadIpChecksum)
+        .corrupted(at(10), 15515, &quote, Corruption::CutHeaders)
+        .udp(at(11), 15515, &bad_digit)
+        .udp(at(12), 15516, &quote)
+        .build();
+
+    let options = ParseOptions {
+        verify_checksums: true,
+        ..Default::default()
+    };
+    let (quotes, stats) = parse_bytes(&capture, &options).unwrap();
+    assert_eq!(quotes.len(), 4);
+    assert_eq!(stats.packet_count, 12);
+    assert_eq!(stats.successfully_parsed, 4);
+    assert_eq!(stats.non_udp, 1);
+    assert_eq!(stats.wrong_port, 1);
+    assert_eq!(stats.not_a_price_quote, 1);
+    assert_eq!(stats.malformed_quote, 1);
+    assert_eq!(stats.checksum_failures, 2);
+    assert_eq!(stats.rejected, 6);
+    assert_eq!(stats.failed, 1);
+    assert_eq!(
+        stats.parse_failures["best_bid_price_1st (invalid digits)"],
+        1
+    );
+
+    // strict mode stops at the bad digit, the 11th packet
+    let options = ParseOptions {
+        strict_fail: true,
+        ..Default::default()
+    };
+    assert!(matches!(
+        parse_bytes(&capture, &options),
+        Err(ParseFileError::Decode {
+            packet_index: 11,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn parses_capture_from_memory() {
+    let path = temp_path("memory.pcap");
+    std::fs::write(&path, sample()).unwrap();
+    let from_file = parse_price_quotes_from_file(path.to_str().unwrap(), &ParseOptions::default());
+    std::fs::remove_file(&path).unwrap();
+    let (from_file, file_stats) = from_file.unwrap();
+    let (from_bytes, stats) =
+        parse_price_quotes_from_bytes(&sample(), &ParseOptions::default()).unwrap();
+    assert_eq!(from_bytes, from_file);
+    assert_eq!(stats.packet_count, file_stats.packet_count);
+    assert_eq!(stats.rejected, file_stats.rejected);
+
+    assert!(matches!(
+        parse_price_quotes_from_bytes(&[], &ParseOptions::default()),
+        Err(ParseFileError::Header(_))
+    ));
+}
+
+#[test]
+fn callback_sees_every_quote_and_can_stop() {
+    let (expected, expected_stats) = parse_bytes(&sample()[..], &ParseOptions::default()).unwrap();
+
+    let mut issues = vec![];
+    let stats = parse_price_quotes_with(&sample()[..], &ParseOptions::default(), |quote| {
+        issues.push(quote.issue_code.clone());
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+    let expected_issues: Vec<String> = expected.iter().map(|q| q.issue_code.clone()).collect();
+    assert_eq!(issues, expected_issues);
+    assert_eq!(stats.packet_count, expected_stats.packet_count);
+    assert_eq!(stats.successfully_parsed, 3);
+
+    // breaking on the second quote leaves the last four packets unread
+    let mut seen = 0;
+    let stats = parse_price_quotes_with(&sample()[..], &ParseOptions::default(), |_| {
+        seen += 1;
+        if seen == 2 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .unwrap();
+    assert_eq!(seen, 2);
+    assert_eq!(stats.packet_count, 2);
+    assert_eq!(stats.successfully_parsed, 2);
+}
+
+#[test]
+fn parses_raw_payloads() {
+    // raw input has no receive times
+    let second = PriceQuoteBuilder::new()
+        .issue_code("KR4301F00002")
+        .seq(2)
+        .rcv_time(Duration::ZERO)
+        .build();
+    let mut raw = QUOTE_PAYLOAD.to_vec();
+    raw.extend(second.encode_b6034());
+    // one with a bad digit fails to decode
+    let mut bad = QUOTE_PAYLOAD.to_vec();
+    bad[30] = b'x';
+    raw.extend(bad);
+    // a partial quote at the end is rejected, not a read error
+    raw.extend(&QUOTE_PAYLOAD[..100]);
+
+    let options = ParseOptions {
+        input_format: Some(InputFormat::Raw),
+        ..Default::default()
+    };
+    let (quotes, stats) = parse_bytes(&raw, &options).unwrap();
+
+    assert_eq!(stats.packet_count, 4);
+    assert_eq!(stats.successfully_parsed, 2);
+    assert_eq!(stats.failed, 1);
+    assert_eq!(
+        stats.parse_failures["best_bid_price_1st (invalid digits)"],
+        1
+    );
+    assert_eq!(stats.rejected, 1);
+    assert_eq!(stats.malformed_quote, 1);
+    assert_eq!(quotes[0].issue_code, "KR4301F42629");
+    assert_eq!(quotes[1], second);
+}
+
+#[test]
+fn rejects_bad_checksums() {
+    let capture = CaptureBuilder::new()
+        .udp(Duration::ZERO, 15515, QUOTE_PAYLOAD)
+        .corrupted(
+            Duration::ZERO,
+            15515,
+            QUOTE_PAYLOAD,
+            Corruption::BadUdpChecksum,
+        )
+        .corrupted(
+            Duration::ZERO,
+            15515,
+            QUOTE_PAYLOAD,
+            Corruption::BadIpChecksum,
+        )
+        .build();
+
+    // only looked at when asked to
+    let (quotes, stats) = parse_bytes(&capture, &ParseOptions::default()).unwrap();
+    assert_eq!(quotes.len(), 3);
+    assert_eq!(stats.checksum_failures, 0);
+    assert_eq!(stats.rejected, 0);
+
+    let options = ParseOptions {
+        verify_checksums: true,
+        ..Default::default()
+    };
+    let (quotes, stats) = parse_bytes(&capture, &options).unwrap();
+    assert_eq!(quotes.len(), 1);
+    assert_eq!(stats.checksum_failures, 2);
+    assert_eq!(stats.rejected, 2);
+}
+
+// seven packets written by hand before testdata existed, with the ipv4 and
+// udp checksums left zeroed like many capture tools leave them: a quote on
+// 15515, a quote on 15516, an A3 message on 15515, a quote on the wrong
+// port, a quote over tcp, a short quote and a quote on 15515
+#[test]
+fn parses_a_capture_with_zeroed_checksums() {
+    let path = format!("{}/tests/fixtures/small.pcap", env!("CARGO_MANIFEST_DIR"));
+    let (quotes, stats) = parse_price_quotes_from_file(&path, &ParseOptions::default()).unwrap();
+    assert_eq!(quotes.len(), 3);
+    assert_eq!(stats.packet_count, 7);
+    assert_eq!(stats.checksum_failures, 0);
+    assert_eq!(
+        quotes[0],
+        PriceQuote::from_bytes(quotes[0].packet_rcv_time, QUOTE_PAYLOAD).unwrap()
+    );
+
+    let options = ParseOptions {
+        verify_checksums: true,
+        ..Default::default()
+    };
+    let (quotes, stats) = parse_price_quotes_from_file(&path, &options).unwrap();
+    assert!(quotes.is_empty());
+    assert_eq!(stats.checksum_failures, 3);
+}
+
+#[test]
+fn filters_one_sided_quotes() {
+    let two_sided = PriceQuoteBuilder::new().build();
+    let no_bid = PriceQuoteBuilder::new().bids(&[]).build();
+    // a missing best ask empties the side even with deeper levels present
+    let no_ask = PriceQuoteBuilder::new().ask(0, 0, 0).build();
+    assert!(!two_sided.is_one_sided());
+    assert!(no_bid.is_one_sided());
+    assert!(no_ask.is_one_sided());
+
+    let all = vec![two_sided.clone(), no_bid.clone(), no_ask.clone()];
+
+    let mut quotes = all.clone();
+    assert_eq!(filter_one_sided(&mut quotes, true, false), 1);
+    assert_eq!(quotes, [two_sided.clone(), no_ask]);
+
+    let mut quotes = all.clone();
+    assert_eq!(filter_one_sided(&mut quotes, true, true), 2);
+    assert_eq!(quotes, [two_sided]);
+}
+
+#[test]
+fn counts_populated_levels_of_the_quotes_kept() {
+    let at = Duration::from_millis;
+    let capture = CaptureBuilder::new()
+        .udp(
+            at(1),
+            15515,
+            &PriceQuoteBuilder::new()
+                .bids(&[(525, 1), (520, 1)])
+                .build()
+                .encode_b6034(),
+        )
+        .udp(
+            at(2),
+            15515,
+            &PriceQuoteBuilder::new()
+                .bids(&[(525, 1), (520, 1)])
+                .build()
+                .encode_b6034(),
+        )
+        .udp(
+            at(3),
+            15515,
+            &PriceQuoteBuilder::new().bids(&[]).build().encode_b6034(),
+        )
+        .build();
+
+    let (_, stats) = parse_bytes(&capture, &ParseOptions::default()).unwrap();
+    assert_eq!(stats.bid_depth, [1, 0, 2, 0, 0, 0]);
+    assert_eq!(stats.ask_depth.iter().sum::<u64>(), 3);
+    assert!(stats
+        .to_string()
+        .contains("  Bid Levels Populated: 0:1 1:0 2:2 3:0 4:0 5:0\n"));
+
+    // quotes that are filtered out aren't counted
+    let options = ParseOptions {
+        filter_no_bid: true,
+        ..Default::default()
+    };
+    let (_, stats) = parse_bytes(&capture, &options).unwrap();
+    assert_eq!(stats.bid_depth, [0, 0, 2, 0, 0, 0]);
+}
+
+#[test]
+fn headers_match_the_full_parse() {
+    let full = PriceQuote::from_bytes(Duration::from_secs(7), QUOTE_PAYLOAD).unwrap();
+    let header = PriceQuote::header_from_bytes(Duration::from_secs(7), QUOTE_PAYLOAD).unwrap();
+    assert_eq!(header, full.header());
+    assert_eq!(header.accept_time(), full.accept_time());
+    assert_eq!(header.market_status(), full.market_status());
+
+    let (quotes, full_stats) =
+        parse_price_quotes_from_bytes(&sample()[..], &ParseOptions::default()).unwrap();
+    let mut headers = vec![];
+    let header_stats =
+        concat_quote_headers_with(vec![&sample()[..]], &ParseOptions::default(), |h| {
+            headers.push(h);
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    let expected: Vec<_> = quotes.iter().map(PriceQuote::header).collect();
+    assert_eq!(headers, expected);
+    assert_eq!(
+        header_stats.successfully_parsed,
+        full_stats.successfully_parsed
+    );
+    assert_eq!(header_stats.rejected, full_stats.rejected);
+
+    // still needs the whole message to reach the accept time
+    let err = PriceQuote::header_from_bytes(Duration::ZERO, &QUOTE_PAYLOAD[..150]).unwrap_err();
+    assert_eq!(err.field(), "book");
+}
+
+#[test]
+fn kept_payload_hex_round_trips() {
+    let mut kept = vec![];
+    let stats = concat_price_quotes_with_payload(
+        vec![&sample()[..]],
+        &ParseOptions::default(),
+        |q, payload| {
+            kept.push((q, encode_hex(payload)));
+            ControlFlow::Continue(())
+        },
+    )
+    .unwrap();
+    assert_eq!(kept.len() as u64, stats.successfully_parsed);
+    assert_eq!(kept.len(), 3);
+    for (quote, hex) in kept {
+        assert_eq!(
+            PriceQuote::from_hex(quote.packet_rcv_time, &hex).unwrap(),
+            quote
+        );
+    }
+}
+
+#[test]
+fn clamps_accept_times_that_run_backwards() {
+    let at = |secs: u32| NaiveTime::from_hms_opt(9, 0, secs).unwrap();
+    // the third quote lags the second by 40 seconds, far past anything a
+    // small reorder buffer would fix, and drags the fourth along with it
+    let quotes: Vec<_> = [1, 45, 5, 6, 50, 49]
+        .into_iter()
+        .map(|secs| PriceQuoteBuilder::new().accept_time(at(secs)).build())
+        .collect();
+
+    let clamped = clamp_accept_times(&quotes);
+    assert_eq!(clamped.clamped, 3);
+    assert_eq!(clamped.max_lag, chrono::Duration::seconds(40));
+    assert_eq!(
+        clamped.times,
+        [at(1), at(45), at(45), at(45), at(50), at(50)].map(Some)
+    );
+
+    let in_order = clamp_accept_times(&quotes[..2]);
+    assert_eq!(in_order.clamped, 0);
+    assert_eq!(in_order.max_lag, chrono::Duration::zero());
+}
+
+#[test]
+fn skip_bytes_strips_encapsulation() {
+    // the sample again, with a fake 4 byte header in front of every frame
+    let capture = sample_capture()
+        .frames()
+        .fold(CaptureBuilder::new(), |capture, (timestamp, frame)| {
+            capture.frame(timestamp, &[&[0xde, 0xad, 0xbe, 0xef], frame].concat())
+        })
+        .build();
+
+    let options = ParseOptions {
+        skip_bytes: 4,
+        ..Default::default()
+    };
+    let (quotes, _) = parse_bytes(&capture, &options).unwrap();
+    let (unskipped, _) = parse_bytes(&capture, &ParseOptions::default()).unwrap();
+
+    assert_eq!(quotes, sample_quotes());
+    assert!(unskipped.is_empty());
+}
+
+// wraps an ethernet frame in ethernet / ipv4 / gre, with the given gre
+// protocol and whatever sits between the gre header and the frame
+fn gre_wrap(flags: u16, protocol: u16, extra: &[u8], frame: &[u8]) -> Vec<u8> {
+    let mut gre = vec![];
+    gre.extend(flags.to_be_bytes());
+    gre.extend(protocol.to_be_bytes());
+    if flags & 0x1000 != 0 {
+        gre.extend(7u32.to_be_bytes());
+    }
+    gre.extend(extra);
+    gre.extend(frame);
+
+    let mut packet = vec![0x02, 0, 0, 0, 0, 1, 0x02, 0, 0, 0, 0, 2, 0x08, 0x00];
+    let total_len = (20 + gre.len()) as u16;
+    packet.extend([0x45, 0]);
+    packet.extend(total_len.to_be_bytes());
+    packet.extend([0, 0, 0x40, 0, 64, 47, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2]);
+    packet.extend(gre);
+    packet
+}
+
+#[test]
+fn decapsulates_gre_and_erspan() {
+    let frame = udp_frame(15515, QUOTE_PAYLOAD);
+
+    let gre = gre_wrap(0, 0x6558, &[], &frame);
+    assert_eq!(decapsulate(&gre), Some(&frame[..]));
+    let erspan_ii = gre_wrap(0x1000, 0x88be, &[0x10, 0, 0, 1, 0, 0, 0, 0], &frame);
+    assert_eq!(decapsulate(&erspan_ii), Some(&frame[..]));
+    let erspan_iii = gre_wrap(
+        0x1000,
+        0x22eb,
+        &[0x20, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+        &frame,
+    );
+    assert_eq!(decapsulate(&erspan_iii), Some(&frame[..]));
+    assert_eq!(decapsulate(&frame), None);
+
+    let capture = CaptureBuilder::new()
+        .frame(Duration::ZERO, &gre)
+        .frame(Duration::ZERO, &erspan_iii)
+        .frame(Duration::ZERO, &frame)
+        .build();
+
+    let (quotes, stats) = parse_bytes(&capture, &ParseOptions::default()).unwrap();
+    assert_eq!(quotes.len(), 3);
+    assert_eq!(stats.decapsulated, 2);
+    assert_eq!(quotes[0].issue_code, "KR4301F42629");
+}
+
+#[test]
+fn counts_quotes_the_snaplen_cut_short() {
+    let capture = CaptureBuilder::new()
+        .udp(Duration::ZERO, 15515, QUOTE_PAYLOAD)
+        .snapped(Duration::ZERO, 15515, QUOTE_PAYLOAD, 96)
+        .snapped(Duration::ZERO, 15516, QUOTE_PAYLOAD, 128)
+        // not a port quotes come in on
+        .snapped(Duration::ZERO, 9999, QUOTE_PAYLOAD, 96)
+        .build();
+
+    let (quotes, stats) = parse_bytes(&capture, &ParseOptions::default()).unwrap();
+    assert_eq!(quotes.len(), 1);
+    assert_eq!(stats.snaplen_truncated, 2);
+    assert_eq!(stats.packet_count, 4);
+    assert!(stats.to_string().contains("  Snaplen Truncated: 2\n"));
+
+    let mut merged = PacketParseStats::new();
+    merged.merge(&stats);
+    assert_eq!(merged.snaplen_truncated(), 2);
+}
+
+#[test]
+fn counts_the_bytes_behind_the_quotes() {
+    // 14 bytes of ethernet, 20 of ipv4 and 8 of udp in front of each payload
+    let capture = CaptureBuilder::new()
+        .udp(Duration::ZERO, 15515, QUOTE_PAYLOAD)
+        .udp(Duration::ZERO, 15515, QUOTE_PAYLOAD)
+        .udp(Duration::ZERO, 9999, &[0; 100])
+        .frame(Duration::ZERO, &[0; 60])
+        .build();
+    let quote_len = QUOTE_PAYLOAD.len() as u64;
+    let (_, stats) = parse_bytes(&capture, &ParseOptions::default()).unwrap();
+    assert_eq!(stats.total_bytes, 2 * (42 + quote_len) + 142 + 60);
+    assert_eq!(stats.record_bytes, stats.total_bytes + 4 * 16);
+    assert_eq!(stats.udp_payload_bytes, 2 * quote_len + 100);
+    assert_eq!(stats.quote_bytes, 2 * quote_len);
+    assert_eq!(stats.quote_record_bytes, 2 * (16 + 42 + quote_len));
+    let text = stats.to_string();
+    assert!(text.contains(&format!(
+        "  Quote Overhead: 58.0 header bytes per {}.0 byte quote (",
+        quote_len
+    )));
+    assert!(text.contains(&format!(
+        "  Record Bytes: {} ({} B), ",
+        stats.record_bytes, stats.record_bytes
+    )));
+
+    let mut merged = stats.clone();
+    merged.merge(&stats);
+    assert_eq!(merged.record_bytes(), 2 * stats.record_bytes);
+    assert_eq!(merged.udp_payload_bytes(), 2 * stats.udp_payload_bytes);
+    assert_eq!(merged.quote_bytes(), 2 * stats.quote_bytes);
+    assert_eq!(merged.quote_record_bytes(), 2 * stats.quote_record_bytes);
+
+    // raw input is nothing but the messages
+    let options = ParseOptions {
+        input_format: Some(InputFormat::Raw),
+        ..Default::default()
+    };
+    let (_, stats) = parse_bytes(QUOTE_PAYLOAD, &options).unwrap();
+    assert_eq!(stats.record_bytes, quote_len);
+    assert_eq!(stats.quote_record_bytes, quote_len);
+    assert!(stats.to_string().contains(&format!(
+        "  Quote Overhead: 0.0 header bytes per {}.0 byte quote (0.00%)\n",
+        quote_len
+    )));
+}
+
+#[test]
+fn options_choose_ports_prefixes_and_filters() {
+    // only 15516 carries KR4301F42959
+    let options = ParseOptions {
+        ports: vec![15516],
+        ..Default::default()
+    };
+    let (quotes, stats) = parse_bytes(&sample()[..], &options).unwrap();
+    assert_eq!(quotes.len(), 1);
+    assert_eq!(quotes[0].issue_code, "KR4301F42959");
+    assert_eq!(stats.wrong_port, 5);
+
+    // now the four quotes on the right ports have the wrong prefix
+    let options = ParseOptions {
+        prefixes: vec![b"A3".to_vec()],
+        ..Default::default()
+    };
+    let (_, stats) = parse_bytes(&sample()[..], &options).unwrap();
+    assert_eq!(stats.not_a_price_quote, 4);
+
+    let one_sided = PriceQuoteBuilder::new().asks(&[]).build().encode_b6034();
+    let raw = [QUOTE_PAYLOAD, &one_sided].concat();
+    let options = ParseOptions {
+        filter_no_ask: true,
+        ..Default::default()
+    };
+    let (quotes, stats) = parse_bytes(&raw, &options).unwrap();
+    assert_eq!(quotes.len(), 1);
+    assert_eq!(stats.successfully_parsed, 2);
+    assert_eq!(stats.one_sided_market, 1);
+}
+
+#[test]
+fn validates_books() {
+    let crossed = PriceQuoteBuilder::new()
+        .bids(&[(525, 1), (530, 1)])
+        .build()
+        .encode_b6034();
+    let raw = [QUOTE_PAYLOAD, &crossed, QUOTE_PAYLOAD].concat();
+
+    let (quotes, stats) = parse_bytes(&raw, &ParseOptions::default()).unwrap();
+    assert_eq!(quotes.len(), 3);
+    assert_eq!(stats.book_violations, 0);
+
+    let options = ParseOptions {
+        validate_book: true,
+        ..Default::default()
+    };
+    let (quotes, stats) = parse_bytes(&raw, &options).unwrap();
+    assert_eq!(quotes.len(), 3);
+    assert_eq!(stats.book_violations, 1);
+
+    let options = ParseOptions {
+        validate_book: true,
+        drop_bad_books: true,
+        ..Default::default()
+    };
+    let (quotes, stats) = parse_bytes(&raw, &options).unwrap();
+    assert_eq!(quotes.len(), 2);
+    assert!(quotes.iter().all(|q| q.validate_book().is_empty()));
+    assert_eq!(stats.book_violations, 1);
+}
+
+#[test]
+fn drops_bad_timestamps() {
+    let quote = PriceQuoteBuilder::new().build().encode_b6034();
+    // epoch 0, then a sane time, then past 2106 where u32 seconds give out
+    let capture = write_capture(&[
+        (Duration::ZERO, 15515, &quote),
+        (Duration::from_secs(1297814400), 15515, &quote),
+        (Duration::from_secs(u32::MAX as u64), 15515, &quote),
+    ]);
+
+    let (quotes, stats) = parse_bytes(&capture, &ParseOptions::default()).unwrap();
+    assert_eq!(quotes.len(), 3);
+    assert_eq!(stats.bad_timestamps, 0);
+
+    // 2000-01-01 up to 2100-01-01
+    let options = ParseOptions {
+        rcv_time_range: Some(Duration::from_secs(946684800)..Duration::from_secs(4102444800)),
+        ..Default::default()
+    };
+    let (quotes, stats) = parse_bytes(&capture, &options).unwrap();
+    assert_eq!(quotes.len(), 1);
+    assert_eq!(quotes[0].packet_rcv_time, Duration::from_secs(1297814400));
+    assert_eq!(stats.successfully_parsed, 3);
+    assert_eq!(stats.bad_timestamps, 2);
+
+    let mut headers = 0;
+    let stats = concat_quote_headers_with(vec![&capture[..]], &options, |_| {
+        headers += 1;
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+    assert_eq!(headers, 1);
+    assert_eq!(stats.bad_timestamps, 2);
+}
+
+#[test]
+fn excludes_market_statuses() {
+    let quote = |status| {
+        PriceQuoteBuilder::new()
+            .market_status(status)
+            .build()
+            .encode_b6034()
+    };
+    let raw = [
+        quote(MarketStatus::PreOpen),
+        quote(MarketStatus::Continuous),
+        quote(MarketStatus::PreOpen),
+        quote(MarketStatus::ClosingAuction),
+    ]
+    .concat();
+
+    let options = ParseOptions {
+        exclude_market_statuses: vec!["pre-open".parse().unwrap(), "30".parse().unwrap()],
+        ..Default::default()
+    };
+    let (quotes, stats) = parse_bytes(&raw, &options).unwrap();
+    assert_eq!(quotes.len(), 1);
+    assert_eq!(quotes[0].market_status(), MarketStatus::Continuous);
+    assert_eq!(stats.excluded_market_statuses[&0], 2);
+    assert_eq!(stats.excluded_market_statuses[&30], 1);
+    assert!("pre-close".parse::<MarketStatus>().is_err());
+}
+
+#[test]
+fn parses_other_issue_code_widths() {
+    let quote = PriceQuoteBuilder::new()
+        .issue_code("005930")
+        .rcv_time(Duration::ZERO)
+        .build();
+    let payload = quote.encode_with_issue_code_len(6);
+    assert_eq!(payload.len(), QUOTE_PAYLOAD.len() - 6);
+
+    let options = ParseOptions {
+        issue_code_len: 6,
+        input_format: Some(InputFormat::Raw),
+        ..Default::default()
+    };
+    let raw = [&payload[..], &payload[..]].concat();
+    let (quotes, stats) = parse_bytes(&raw, &options).unwrap();
+    assert_eq!(stats.successfully_parsed, 2);
+    assert_eq!(quotes, [quote.clone(), quote]);
+
+    // read with the default width every field lands in the wrong place
+    let (quotes, _) = parse_bytes(&raw, &ParseOptions::default()).unwrap();
+    assert!(quotes.iter().all(|q| q.issue_code != "005930"));
+}
+
+// the sample's frames picked by keep, as their own capture
+fn sample_subset(keep: impl Fn(usize) -> bool) -> Vec<u8> {
+    sample_capture()
+        .frames()
+        .enumerate()
+        .filter(|(i, _)| keep(*i))
+        .fold(CaptureBuilder::new(), |capture, (_, (timestamp, frame))| {
+            capture.frame(timestamp, frame)
+        })
+        .build()
+}
+
+#[test]
+fn merges_captures_by_timestamp() {
+    let (single, single_stats) = parse_bytes(&sample(), &ParseOptions::default()).unwrap();
+
+    // the same packets split across two captures, interleaved in time
+    let captures = vec![
+        Cursor::new(sample_subset(|i| i % 2 == 1)),
+        Cursor::new(sample_subset(|i| i % 2 == 0)),
+    ];
+    let (merged, stats) =
+        merge_price_quotes_from_readers(captures, &ParseOptions::default()).unwrap();
+
+    assert_eq!(stats.packet_count, single_stats.packet_count);
+    assert_eq!(stats.successfully_parsed, single_stats.successfully_parsed);
+    assert_eq!(stats.rejected, single_stats.rejected);
+    assert_eq!(merged, single);
+
+    // a capture entirely from earlier comes out first
+    let at = Duration::from_millis;
+    let late = write_capture(&[
+        (at(50), 15515, QUOTE_PAYLOAD),
+        (at(60), 15515, QUOTE_PAYLOAD),
+    ]);
+    let early = write_capture(&[(at(10), 15515, QUOTE_PAYLOAD)]);
+    let (merged, _) = merge_price_quotes_from_readers(
+        vec![Cursor::new(late), Cursor::new(early)],
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    let times: Vec<_> = merged
+        .iter()
+        .map(|q| q.packet_rcv_time.as_millis())
+        .collect();
+    assert_eq!(times, [10, 50, 60]);
+}
+
+#[test]
+fn reads_rotated_captures_in_order() {
+    // the sample rotated into two files, the first four packets and the last
+    // three, written second first so directory order can't be relied on
+    let dir = temp_path("rotated");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("feed-00002.pcap"), sample_subset(|i| i >= 4)).unwrap();
+    std::fs::write(dir.join("feed-00001.pcap"), sample_subset(|i| i < 4)).unwrap();
+    let glob = format!("{}/feed-*.pcap", dir.display());
+
+    let paths = expand_capture_paths(&glob).unwrap();
+    let names: Vec<_> = paths
+        .iter()
+        .map(|p| p.rsplit('/').next().unwrap())
+        .collect();
+    assert_eq!(names, ["feed-00001.pcap", "feed-00002.pcap"]);
+
+    let files = paths
+        .iter()
+        .map(|p| std::fs::File::open(p).unwrap())
+        .collect();
+    let (quotes, stats) =
+        concat_price_quotes_from_readers(files, &ParseOptions::default()).unwrap();
+    let (whole, whole_stats) = parse_bytes(&sample(), &ParseOptions::default()).unwrap();
+    assert_eq!(quotes, whole);
+    assert_eq!(stats.packet_count, whole_stats.packet_count);
+    assert_eq!(stats.malformed_quote, whole_stats.malformed_quote);
+
+    // plain paths pass through as they are
+    assert_eq!(expand_capture_paths(&paths[0]).unwrap(), [paths[0].clone()]);
+    let missing = glob.replace("feed-", "missing-");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(matches!(
+        expand_capture_paths(&missing),
+        Err(ParseFileError::NoMatches { .. })
+    ));
+}
+
+// stats as json without the parse timings, which never match
+fn stats_json(stats: &PacketParseStats) -> serde_json::Value {
+    let mut json = serde_json::to_value(stats).unwrap();
+    for timing in ["parse_time", "io_time", "decode_time"] {
+        json[timing] = serde_json::Value::Null;
+    }
+    json
+}
+
+#[test]
+fn parallel_parse_matches_sequential() {
+    let quote = PriceQuoteBuilder::new()
+        .issue_code("KR4301F52651")
+        .seq(9)
+        .build()
+        .encode_b6034();
+    let mut bad_digit = quote.clone();
+    bad_digit[30] = b'x';
+    let at = Duration::from_millis;
+    let captures = [
+        sample_capture().build(),
+        CaptureBuilder::new()
+            .udp(at(20), 15516, &quote)
+            .udp(at(21), 15515, &bad_digit)
+            .corrupted(at(22), 15515, &quote, Corruption::BadIpChecksum)
+            .udp(at(23), 15515, &quote)
+            .build(),
+        sample_subset(|i| i >= 3),
+    ];
+    let readers = || captures.iter().map(Cursor::new).collect::<Vec<_>>();
+
+    let (sequential, sequential_stats) =
+        concat_price_quotes_from_readers(readers(), &ParseOptions::default()).unwrap();
+    assert_eq!(sequential.len(), 3 + 3 + 1);
+    for jobs in [1, 2, 3, 8] {
+        let (quotes, stats) =
+            concat_price_quotes_from_readers_parallel(readers(), &ParseOptions::default(), jobs)
+                .unwrap();
+        assert_eq!(quotes, sequential, "{jobs} jobs");
+        assert_eq!(stats_json(&stats), stats_json(&sequential_stats));
+    }
+
+    // packet indices count across every capture, as if read in order
+    let options = ParseOptions {
+        strict_fail: true,
+        ..Default::default()
+    };
+    assert!(matches!(
+        concat_price_quotes_from_readers_parallel(readers(), &options, 3),
+        Err(ParseFileError::Decode {
+            packet_index: 9,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn estimates_memory_without_decoding() {
+    let estimate = estimate_memory(vec![&sample()[..]], &ParseOptions::default()).unwrap();
+    assert_eq!(estimate.packets, 7);
+    assert_eq!(estimate.quotes, 3);
+    assert_eq!(estimate.bytes, 3 * std::mem::size_of::<PriceQuote>() as u64);
+
+    // the same port filter a real parse would use
+    let options = ParseOptions {
+        ports: vec![15516],
+        ..Default::default()
+    };
+    assert_eq!(
+        estimate_memory(vec![&sample()[..]], &options)
+            .unwrap()
+            .quotes,
+        1
+    );
+}
+
+#[test]
+fn wrong_length_quotes_are_rejected_with_their_length() {
+    let frame = udp_frame(15515, &QUOTE_PAYLOAD[..100]);
+    let packet = SlicedPacket::from_ethernet(&frame).unwrap();
+    let err = classify_packet(&B6034Parser::default(), &packet).unwrap_err();
+    assert_eq!(
+        err,
+        PacketClassifyError::MalformedQuote {
+            port: 15515,
+            len: 100,
+            expected: 215
+        }
+    );
+    assert_eq!(err.to_string(), "quote payload is 100 bytes, expected 215");
+
+    // counted apart from quotes that fail to decode
+    let capture = write_capture(&[(Duration::ZERO, 15515, &QUOTE_PAYLOAD[..100])]);
+    let (quotes, stats) = parse_bytes(&capture, &ParseOptions::default()).unwrap();
+    assert!(quotes.is_empty());
+    assert_eq!(stats.malformed_quote, 1);
+    assert_eq!(stats.rejected, 1);
+    assert_eq!(stats.failed, 0);
+
+    // a quote with something stuck on the end is no more whole
+    let long = [QUOTE_PAYLOAD, &[b'0'; 5]].concat();
+    let frame = udp_frame(15515, &long);
+    let packet = SlicedPacket::from_ethernet(&frame).unwrap();
+    assert_eq!(
+        classify_packet(&B6034Parser::default(), &packet),
+        Err(PacketClassifyError::MalformedQuote {
+            port: 15515,
+            len: 220,
+            expected: 215
+        })
+    );
+}
+
+#[test]
+fn parses_binary_numbers_when_asked() {
+    let quote = PriceQuoteBuilder::new()
+        .rcv_time(Duration::ZERO)
+        .bids(&[(70_000, 12)])
+        .build();
+    let payload = quote.encode_with(12, NumericEncoding::BigEndian);
+    let options = ParseOptions {
+        numbers: NumericEncoding::BigEndian,
+        input_format: Some(InputFormat::Raw),
+        ..Default::default()
+    };
+    let (quotes, stats) = parse_bytes(&payload, &options).unwrap();
+    assert_eq!(stats.successfully_parsed, 1);
+    assert_eq!(quotes, [quote]);
+
+    // the default ascii decode counts it as a failure
+    let (quotes, stats) = parse_bytes(&payload, &ParseOptions::default()).unwrap();
+    assert!(quotes.is_empty());
+    assert_eq!(stats.failed, 1);
+}
+
+#[test]
+fn counts_packets_per_port() {
+    let (_, stats) = parse_bytes(&sample(), &ParseOptions::default()).unwrap();
+    let counts = |packets, quotes, failed| PortCounts {
+        packets,
+        quotes,
+        failed,
+    };
+    // the tcp packet has no udp port to count it under
+    assert_eq!(
+        stats.ports.iter().collect::<Vec<_>>(),
+        [(&15515, &counts(4, 2, 0)), (&15516, &counts(1, 1, 0))]
+    );
+    assert_eq!(stats.other_ports, counts(1, 0, 0));
+    assert!(stats
+        .to_string()
+        .contains("    Port 15515: 4 packets, 2 quotes, 0 failed\n"));
+
+    // a listed port nothing arrives on still gets a row
+    let options = ParseOptions {
+        ports: vec![15515, 20000],
+        ..Default::default()
+    };
+    let (_, stats) = parse_bytes(&sample(), &options).unwrap();
+    assert_eq!(
+        stats.ports.iter().collect::<Vec<_>>(),
+        [(&15515, &counts(4, 2, 0)), (&20000, &counts(0, 0, 0))]
+    );
+    assert_eq!(stats.other_ports, counts(2, 0, 0));
+
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(json["ports"]["20000"]["packets"], 0);
+    assert_eq!(json["other_ports"]["packets"], 2);
+}
+
+#[test]
+fn keeps_only_listed_issues() {
+    let list = read_issue_list(Cursor::new("  KR4301F42959 \n\n")).unwrap();
+    let options = ParseOptions {
+        valid_issues: Some(list),
+        ..Default::default()
+    };
+    let (quotes, stats) = parse_bytes(&sample(), &options).unwrap();
+    let issues: Vec<&str> = quotes.iter().map(|q| q.issue_code.as_str()).collect();
+    assert_eq!(issues, ["KR4301F42959"]);
+    assert_eq!(stats.unknown_issue, 2);
+    assert!(stats.to_string().contains("  Unknown Issue: 2\n"));
+
+    let mut headers = 0;
+    let stats = concat_quote_headers_with(vec![&sample()[..]], &options, |_| {
+        headers += 1;
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+    assert_eq!(headers, 1);
+    assert_eq!(stats.unknown_issue, 2);
+}
+
+#[test]
+fn records_the_time_range_covered() {
+    let quotes = sample_quotes();
+    let (_, stats) = parse_bytes(&sample(), &ParseOptions::default()).unwrap();
+    assert_eq!(stats.first_rcv_time, Some(quotes[0].packet_rcv_time));
+    assert_eq!(stats.last_rcv_time, Some(quotes[2].packet_rcv_time));
+    assert_eq!(stats.capture_duration(), Some(Duration::from_millis(6)));
+    assert_eq!(stats.first_accept_time, Some(quotes[0].quote_accept_time));
+    assert_eq!(stats.last_accept_time, Some(quotes[2].quote_accept_time));
+    let rate = stats.quotes_per_capture_second().unwrap();
+    assert!((rate - 500.0).abs() < 1e-6);
+    let text = stats.to_string();
+    assert!(text.contains("  Capture Duration: 0.006s\n"));
+    assert!(text.contains("  First Accept Time: 09:00:00.010\n"));
+    assert!(text.contains("  Last Accept Time: 09:00:00.030\n"));
+
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(
+        json["first_rcv_time"],
+        quotes[0].packet_rcv_time.as_nanos() as u64
+    );
+    assert_eq!(json["last_accept_time"], "09:00:00.030");
+
+    // an empty capture covers no time at all
+    let (_, stats) = parse_bytes(&CaptureBuilder::new().build(), &ParseOptions::default()).unwrap();
+    assert_eq!(stats.capture_duration(), None);
+    let text = stats.to_string();
+    assert!(text.contains("  Capture Start: n/a\n"));
+    assert!(text.contains("  First Accept Time: n/a\n"));
+    assert!(text.contains("  Quotes Per Capture Second: n/a\n"));
+    let json = serde_json::to_value(&stats).unwrap();
+    assert!(json["first_rcv_time"].is_null());
+    assert!(json["first_accept_time"].is_null());
+}
+
+#[test]
+fn streaming_counts_match_batch() {
+    let capture = sample_capture();
+    let (quotes, batch) = parse_bytes(&capture.build(), &ParseOptions::default()).unwrap();
+
+    let mut streamed_quotes = 0;
+    let streamed =
+        concat_price_quotes_with(vec![&capture.build()[..]], &ParseOptions::default(), |_| {
+            streamed_quotes += 1;
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    assert_eq!(streamed.successfully_parsed(), streamed_quotes);
+    assert_eq!(streamed.successfully_parsed(), quotes.len() as u64);
+
+    let frame_bytes: usize = capture.frames().map(|(_, frame)| frame.len()).sum();
+    assert_eq!(batch.total_bytes(), frame_bytes as u64);
+    assert!(batch
+        .to_string()
+        .contains(&format!("  Total Bytes: {}\n", frame_bytes)));
+
+    // everything but how long it took
+    assert_eq!(stats_json(&streamed), stats_json(&batch));
+}
+
+#[test]
+fn reads_stats_intervals() {
+    assert_eq!(
+        StatsInterval::parse("5s"),
+        Some(StatsInterval::Every(Duration::from_secs(5)))
+    );
+    assert_eq!(
+        StatsInterval::parse("250ms"),
+        Some(StatsInterval::Every(Duration::from_millis(250)))
+    );
+    assert_eq!(
+        StatsInterval::parse("2M"),
+        Some(StatsInterval::Packets(2_000_000))
+    );
+    assert_eq!(StatsInterval::parse("0s"), None);
+    assert_eq!(StatsInterval::parse("0M"), None);
+    assert_eq!(StatsInterval::parse("M"), None);
+    assert_eq!(StatsInterval::parse("soon"), None);
+}
+
+#[test]
+fn parse_time_splits_into_io_and_decode() {
+    let quote = PriceQuoteBuilder::new().build().encode_b6034();
+    let parse = |packets| {
+        let capture = (0..packets)
+            .fold(CaptureBuilder::new(), |capture, i| {
+                capture.udp(Duration::from_millis(i), 15515, &quote)
+            })
+            .build();
+        let (quotes, stats) = parse_bytes(&capture, &ParseOptions::default()).unwrap();
+        assert_eq!(quotes.len() as u64, packets);
+        stats
+    };
+
+    // few enough that every packet is timed
+    let stats = parse(1_000);
+    let (io, decode) = (stats.io_time(), stats.decode_time());
+    assert!(io > Duration::ZERO && decode > Duration::ZERO);
+    // the rest is collecting the quotes, next to nothing
+    assert!(io + decode <= stats.parse_time());
+    assert!(io + decode >= stats.parse_time() / 2, "{:?}", stats);
+
+    // past that decode_time is from a sample, so only roughly right
+    let stats = parse(5_000);
+    let (io, decode) = (stats.io_time(), stats.decode_time());
+    assert!(io > Duration::ZERO && decode > Duration::ZERO);
+    assert!(io + decode <= stats.parse_time() * 2, "{:?}", stats);
+    assert!(io + decode >= stats.parse_time() / 4, "{:?}", stats);
+
+    let text = stats.to_string();
+    assert!(text.contains("    I/O: "), "{}", text);
+    assert!(text.contains("    Decode: "), "{}", text);
+    assert!(!format!("{:#}", stats).contains("Decode: "));
+}
+
+#[test]
+fn json_stats_carry_the_whole_rate_series() {
+    let (quotes, mut stats) = parse_bytes(&sample(), &ParseOptions::default()).unwrap();
+    let mut rates = tsc_solution::analytics::RateCounter::new(Duration::from_millis(2));
+    for quote in &quotes {
+        rates.record(quote.packet_rcv_time);
+    }
+    stats.rate_series = rates.series();
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(
+        json["rate_series"]["counts"],
+        serde_json::json!([1, 1, 0, 1])
+    );
+    assert_eq!(json["rate_series"]["bucket_ns"], 2_000_000);
+}
@@ -0,0 +1,248 @@
+use std::time::Duration;
+
+use tsc_solution::market::encode_ascii_code;
+use tsc_solution::price::{Price, Qty};
+use tsc_solution::price_quote::{BookViolation, NumericEncoding, Side, B6034_LEN, ISSUE_CODE_LEN};
+use tsc_solution::{PriceQuote, PriceQuoteBuilder};
+
+#[test]
+fn depth_concentration_and_herfindahl() {
+    let quote = PriceQuoteBuilder::new()
+        .bids(&[(525, 90), (520, 10)])
+        .asks(&[(530, 20), (535, 20), (540, 20), (545, 20), (550, 20)])
+        .build();
+    assert!((quote.bid_depth_concentration() - 0.9).abs() < 1e-12);
+    assert!((quote.ask_depth_concentration() - 0.2).abs() < 1e-12);
+    assert!((quote.herfindahl_bid() - (0.81 + 0.01)).abs() < 1e-12);
+    assert!((quote.herfindahl_ask() - 0.2).abs() < 1e-12);
+
+    // the totals come off the wire and needn't match the levels
+    let quote = PriceQuoteBuilder::new()
+        .bids(&[(525, 30)])
+        .total_bid_volume(120)
+        .build();
+    assert_eq!(quote.bid_depth_concentration(), 0.25);
+    assert_eq!(quote.herfindahl_bid(), 1.0);
+
+    let empty = PriceQuoteBuilder::new().bids(&[]).build();
+    assert_eq!(empty.bid_depth_concentration(), 0.0);
+    assert_eq!(empty.herfindahl_bid(), 0.0);
+}
+
+#[test]
+fn top_of_book_measures() {
+    // best bid 525 x 24, best ask 530 x 1
+    let quote = PriceQuoteBuilder::new().build();
+    assert_eq!(quote.spread(), Some(Price(5)));
+    assert_eq!(quote.mid_price(0), Some(527.5));
+    assert_eq!(quote.mid_price(1), Some(52.75));
+    assert_eq!(quote.imbalance(), Some(24.0 / 25.0));
+    assert_eq!(quote.depth(1), (Qty(24), Qty(1)));
+    assert_eq!(quote.depth(2), (Qty(24 + 32), Qty(1 + 7)));
+    assert_eq!(quote.depth(0), (Qty(0), Qty(0)));
+    // past the last level is the whole book
+    assert_eq!(quote.depth(10), quote.depth(5));
+
+    let empty = PriceQuoteBuilder::new().bids(&[]).asks(&[]).build();
+    assert_eq!(empty.spread(), None);
+    assert_eq!(empty.mid_price(0), None);
+    assert_eq!(empty.imbalance(), None);
+    assert_eq!(empty.depth(5), (Qty(0), Qty(0)));
+
+    // only the side that's there counts toward the imbalance
+    let bids_only = PriceQuoteBuilder::new().asks(&[]).build();
+    assert_eq!(bids_only.spread(), None);
+    assert_eq!(bids_only.mid_price(0), None);
+    assert_eq!(bids_only.imbalance(), Some(1.0));
+    assert_eq!(bids_only.depth(1), (Qty(24), Qty(0)));
+    let asks_only = PriceQuoteBuilder::new().bids(&[]).build();
+    assert_eq!(asks_only.spread(), None);
+    assert_eq!(asks_only.imbalance(), Some(0.0));
+
+    // a crossed book has no spread but still has a middle
+    let crossed = PriceQuoteBuilder::new()
+        .bids(&[(535, 10)])
+        .asks(&[(530, 30)])
+        .build();
+    assert_eq!(crossed.spread(), None);
+    assert_eq!(crossed.mid_price(0), Some(532.5));
+    assert_eq!(crossed.imbalance(), Some(0.25));
+    // and a locked one a spread of nothing
+    let locked = PriceQuoteBuilder::new()
+        .bids(&[(530, 10)])
+        .asks(&[(530, 30)])
+        .build();
+    assert_eq!(locked.spread(), Some(Price(0)));
+
+    // prices with nothing behind them
+    let zero_qty = PriceQuoteBuilder::new()
+        .bids(&[(525, 0)])
+        .asks(&[(530, 0)])
+        .build();
+    assert_eq!(zero_qty.spread(), Some(Price(5)));
+    assert_eq!(zero_qty.mid_price(0), Some(527.5));
+    assert_eq!(zero_qty.imbalance(), None);
+    assert_eq!(zero_qty.depth(5), (Qty(0), Qty(0)));
+}
+
+#[test]
+fn counts_populated_levels() {
+    let mut quote = PriceQuoteBuilder::new()
+        .bids(&[(525, 90), (520, 10), (515, 5)])
+        .asks(&[])
+        .build();
+    assert_eq!(quote.populated_bid_levels(), 3);
+    assert_eq!(quote.populated_ask_levels(), 0);
+
+    // a gap doesn't stop the count
+    quote.bids[1].price = Price(0);
+    assert_eq!(quote.populated_bid_levels(), 2);
+    quote.asks[4].price = Price(600);
+    assert_eq!(quote.populated_ask_levels(), 1);
+}
+
+#[test]
+fn validates_the_book() {
+    let quote = PriceQuoteBuilder::new().build();
+    assert!(quote.validate_book().is_empty());
+    assert_eq!(quote.total_depth(), Qty(74 + 33));
+
+    // a partly filled side is fine as long as the empty levels come last
+    let quote = PriceQuoteBuilder::new()
+        .bids(&[(525, 30)])
+        .asks(&[])
+        .build();
+    assert!(quote.validate_book().is_empty());
+
+    let quote = PriceQuoteBuilder::new()
+        .bids(&[(525, 1), (530, 1), (0, 0), (515, 1)])
+        .ask(3, 535, 1)
+        .build();
+    assert_eq!(
+        quote.validate_book(),
+        [
+            BookViolation::OutOfOrder {
+                side: Side::Bid,
+                level: 1
+            },
+            BookViolation::Gap {
+                side: Side::Bid,
+                level: 2
+            },
+            BookViolation::OutOfOrder {
+                side: Side::Ask,
+                level: 3
+            },
+        ]
+    );
+
+    // the total covers the whole book, so it can only be short
+    let mut quote = PriceQuoteBuilder::new().bid_orders(0, 20).build();
+    assert_eq!(
+        quote.validate_book(),
+        [BookViolation::OrderCounts {
+            side: Side::Bid,
+            total: Some(5),
+            sum: Some(24)
+        }]
+    );
+    quote.no_of_best_bid_valid_quote_total = encode_ascii_code(24, 5);
+    quote.no_of_best_ask_valid_quote_total = encode_ascii_code(900, 5);
+    assert!(quote.validate_book().is_empty());
+
+    quote.asks[2].order_count = u32::from_le_bytes(*b"00x1");
+    assert_eq!(
+        quote.validate_book(),
+        [BookViolation::OrderCounts {
+            side: Side::Ask,
+            total: Some(900),
+            sum: None
+        }]
+    );
+    assert_eq!(
+        quote.validate_book()[0].to_string(),
+        "ask order counts add up to invalid against a total of 900"
+    );
+}
+
+#[test]
+fn reads_the_issue_code_as_an_isin() {
+    let quote = PriceQuoteBuilder::new().issue_code("KR4301F42629").build();
+    assert_eq!(quote.country_code(), Some("KR"));
+    assert_eq!(quote.nsin(), Some("4301F4262"));
+    assert_eq!(quote.check_digit(), Some(9));
+    assert!(quote.is_korean());
+    assert_eq!(quote.header().country_code(), Some("KR"));
+
+    let quote = PriceQuoteBuilder::new().issue_code("US0378331005").build();
+    assert_eq!(quote.country_code(), Some("US"));
+    assert!(!quote.is_korean());
+
+    for garbage in [
+        "",
+        "KR",
+        "KR4301F4262",
+        "KR4301F426299",
+        "kr4301F42629",
+        "KR4301F4262X",
+        "KR4301f42629",
+        "12345678901\u{e9}",
+        "KR4301F 2629",
+    ] {
+        let quote = PriceQuoteBuilder::new().issue_code(garbage).build();
+        assert_eq!(quote.country_code(), None, "{:?}", garbage);
+        assert_eq!(quote.nsin(), None, "{:?}", garbage);
+        assert_eq!(quote.check_digit(), None, "{:?}", garbage);
+        assert!(!quote.is_korean(), "{:?}", garbage);
+    }
+}
+
+#[test]
+fn decodes_order_counts() {
+    let quote = PriceQuoteBuilder::new().bid_orders(0, 12).build();
+    assert_eq!(quote.bids[0].orders(), Some(12));
+    assert_eq!(quote.asks[0].orders(), Some(1));
+    assert_eq!(quote.bid_order_total(), Some(5));
+
+    let mut quote = quote;
+    quote.bids[0].order_count = 0;
+    quote.no_of_best_ask_valid_quote_total = 0;
+    assert_eq!(quote.bids[0].orders(), None);
+    assert_eq!(quote.ask_order_total(), None);
+}
+
+#[test]
+fn reads_binary_numbers_in_either_byte_order() {
+    let quote = PriceQuoteBuilder::new()
+        .rcv_time(Duration::ZERO)
+        .bids(&[(70_000, 1_000_000), (69_950, 3)])
+        .asks(&[(70_050, 258)])
+        .build();
+
+    for numbers in [NumericEncoding::BigEndian, NumericEncoding::LittleEndian] {
+        let payload = quote.encode_with(ISSUE_CODE_LEN, numbers);
+        assert_eq!(payload.len(), B6034_LEN);
+        let decoded =
+            PriceQuote::from_bytes_with(Duration::ZERO, &payload, ISSUE_CODE_LEN, numbers).unwrap();
+        assert_eq!(decoded, quote);
+    }
+
+    // the first bid price, 70000 = 0x011170, in five bytes each way round
+    let offset = 2 + 2 + 1 + ISSUE_CODE_LEN + 3 + 2 + 7;
+    let be = quote.encode_with(ISSUE_CODE_LEN, NumericEncoding::BigEndian);
+    assert_eq!(be[offset..offset + 5], [0, 0, 0x01, 0x11, 0x70]);
+    let le = quote.encode_with(ISSUE_CODE_LEN, NumericEncoding::LittleEndian);
+    assert_eq!(le[offset..offset + 5], [0x70, 0x11, 0x01, 0, 0]);
+
+    // binary numbers aren't ascii digits, so the default decode refuses them
+    assert!(PriceQuote::from_bytes(Duration::ZERO, &be).is_err());
+    assert_eq!(
+        PriceQuote::from_bytes_with(
+            Duration::ZERO,
+            &quote.encode_b6034(),
+            ISSUE_CODE_LEN,
+            NumericEncoding::Ascii
+        ),
+        Ok(quote)
+    );
+}
@@ -0,0 +1,177 @@
+#![cfg(feature = "testing")]
+
+use std::cmp::Ordering;
+use std::time::Duration;
+
+use proptest::prelude::*;
+
+use tsc_solution::price::{Price, Qty};
+use tsc_solution::price_quote::SortKey;
+use tsc_solution::testdata::{udp_frame, CaptureBuilder};
+use tsc_solution::testing::{mutated_payload, price_quote};
+use tsc_solution::{
+    parse_price_quotes_from_bytes, InputFormat, PacketParseStats, ParseOptions, PriceQuote,
+};
+
+// a frame carrying a good or mangled quote, on a quote port or another one
+fn packet() -> impl Strategy<Value = (Duration, Vec<u8>)> {
+    (
+        price_quote(),
+        mutated_payload(),
+        any::<bool>(),
+        prop::sample::select(vec![15515u16, 15516, 9999]),
+    )
+        .prop_map(|(quote, (mangled, _), mangle, port)| {
+            let payload = if mangle {
+                mangled
+            } else {
+                quote.encode_b6034()
+            };
+            (quote.packet_rcv_time, udp_frame(port, &payload))
+        })
+}
+
+// a quote and a copy of it that differs in at most one field, so that
+// ties on the leading comparisons actually come up
+fn nearby_quotes() -> impl Strategy<Value = (PriceQuote, PriceQuote)> {
+    (price_quote(), 0..10usize).prop_map(|(a, field)| {
+        let mut b = a.clone();
+        match field {
+            0 => b.packet_rcv_time += Duration::from_nanos(1),
+            1 => b.issue_seq_no ^= 1,
+            2 => b.issue_code.push('X'),
+            3 => b.quote_accept_time ^= 1,
+            4 => b.market_status_type ^= 1,
+            5 => b.total_bid_quote_volume = Qty(b.total_bid_quote_volume.raw() ^ 1),
+            6 => b.bids[4].qty = Qty(b.bids[4].qty.raw() ^ 1),
+            7 => b.asks[0].price = Price(b.asks[0].price.raw() ^ 1),
+            8 => b.no_of_best_ask_valid_quote_total ^= 1,
+            _ => {}
+        }
+        (a, b)
+    })
+}
+
+fn assert_total_order(
+    cmp: impl Fn(&PriceQuote, &PriceQuote) -> Ordering,
+    quotes: [&PriceQuote; 3],
+) -> Result<(), TestCaseError> {
+    for x in quotes {
+        prop_assert_eq!(cmp(x, &x.clone()), Ordering::Equal);
+        for y in quotes {
+            prop_assert_eq!(cmp(x, y) == Ordering::Equal, x == y);
+            prop_assert_eq!(cmp(x, y), cmp(y, x).reverse());
+            for z in quotes {
+                if cmp(x, y).is_le() && cmp(y, z).is_le() {
+                    prop_assert!(cmp(x, z).is_le());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn stats_of(packets: &[(Duration, Vec<u8>)]) -> PacketParseStats {
+    let capture = packets
+        .iter()
+        .fold(CaptureBuilder::new(), |capture, (timestamp, frame)| {
+            capture.frame(*timestamp, frame)
+        });
+    let (_, stats) =
+        parse_price_quotes_from_bytes(&capture.build(), &ParseOptions::default()).unwrap();
+    stats
+}
+
+proptest! {
+    #[test]
+    fn encode_then_decode_is_identity(quote in price_quote()) {
+        let decoded = PriceQuote::from_bytes(quote.packet_rcv_time, &quote.encode_b6034());
+        prop_assert_eq!(decoded, Ok(quote));
+    }
+
+    #[test]
+    fn decode_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..512)) {
+        let _ = PriceQuote::from_bytes(Duration::ZERO, &bytes);
+        let _ = parse_price_quotes_from_bytes(&bytes, &ParseOptions::default());
+    }
+
+    #[test]
+    fn decode_never_panics_on_mangled_captures(
+        (payload, _) in mutated_payload(),
+        cut in 0..215usize,
+    ) {
+        let _ = PriceQuote::from_bytes(Duration::ZERO, &payload[..cut]);
+        let options = ParseOptions {
+            input_format: Some(InputFormat::Raw),
+            ..Default::default()
+        };
+        let _ = parse_price_quotes_from_bytes(&payload[..cut], &options);
+    }
+
+    #[test]
+    fn strict_mode_rejects_exactly_corrupted_payloads((payload, corrupted) in mutated_payload()) {
+        let options = ParseOptions {
+            input_format: Some(InputFormat::Raw),
+            strict_fail: true,
+            ..Default::default()
+        };
+        let result = parse_price_quotes_from_bytes(&payload, &options);
+        prop_assert_eq!(result.is_err(), corrupted, "{:?}", result);
+        prop_assert_eq!(
+            PriceQuote::from_bytes(Duration::ZERO, &payload).is_err(),
+            corrupted
+        );
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn merging_single_packets_matches_one_pass(
+        packets in prop::collection::vec(packet(), 0..20),
+    ) {
+        let mut merged = stats_of(&[]);
+        for packet in &packets {
+            merged.merge(&stats_of(std::slice::from_ref(packet)));
+        }
+        let mut whole = stats_of(&packets);
+        for stats in [&mut merged, &mut whole] {
+            stats.parse_time = Duration::ZERO;
+            stats.io_time = Duration::ZERO;
+            stats.decode_time = Duration::ZERO;
+        }
+        prop_assert_eq!(merged, whole);
+    }
+
+    #[test]
+    fn merge_is_associative(
+        a in prop::collection::vec(packet(), 0..8),
+        b in prop::collection::vec(packet(), 0..8),
+        c in prop::collection::vec(packet(), 0..8),
+    ) {
+        let (a, b, c) = (stats_of(&a), stats_of(&b), stats_of(&c));
+        let mut left = a.clone();
+        left.merge(&b);
+        left.merge(&c);
+        let mut right = b.clone();
+        right.merge(&c);
+        let mut right_first = a;
+        right_first.merge(&right);
+        prop_assert_eq!(left, right_first);
+    }
+}
+
+proptest! {
+    #[test]
+    fn ord_is_a_total_order_agreeing_with_eq(
+        (a, b) in nearby_quotes(),
+        c in price_quote(),
+    ) {
+        assert_total_order(PriceQuote::cmp, [&a, &b, &c])?;
+        for name in SortKey::NAMES {
+            let key = SortKey::from_name(name).unwrap();
+            assert_total_order(key.key_fn(), [&a, &b, &c])?;
+        }
+    }
+}
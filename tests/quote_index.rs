@@ -0,0 +1,65 @@
+#![cfg(feature = "chrono")]
+
+use chrono::NaiveTime;
+
+use tsc_solution::quote_index::{IssueInterner, QuoteIndex, QuoteKey};
+use tsc_solution::PriceQuoteBuilder;
+
+#[test]
+fn keys_quotes_by_issue_and_seq() {
+    let mut interner = IssueInterner::new();
+    let quote = |issue_code, seq| {
+        PriceQuoteBuilder::new()
+            .issue_code(issue_code)
+            .seq(seq)
+            .build()
+    };
+
+    let a1 = quote("KR4301F42629", 1).key(&mut interner).unwrap();
+    let b1 = quote("KR4301F42959", 1).key(&mut interner).unwrap();
+    let a2 = quote("KR4301F42629", 2).key(&mut interner).unwrap();
+    assert_eq!(a1.issue, a2.issue);
+    assert_ne!(a1, b1);
+    assert_eq!(quote("KR4301F42629", 1).key(&mut interner), Some(a1));
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.resolve(b1.issue), "KR4301F42959");
+    assert_eq!(interner.get("KR4301F62551"), None);
+
+    let mut sorted = vec![a2, b1, a1];
+    sorted.sort();
+    assert_eq!(sorted, [a1, a2, b1]);
+
+    let mut garbled = quote("KR4301F62551", 1);
+    garbled.issue_seq_no = u32::from_be_bytes([0, b'0', b'x', b'1']);
+    assert_eq!(garbled.key(&mut interner), None);
+    assert_eq!(interner.len(), 2);
+}
+
+#[test]
+fn evicts_the_earliest_accepted_when_full() {
+    let mut interner = IssueInterner::new();
+    let issue = interner.intern("KR4301F42629");
+    let key = |seq| QuoteKey { issue, seq };
+    let at = |secs| NaiveTime::from_hms_opt(9, 0, secs).unwrap();
+
+    let mut index = QuoteIndex::new(2);
+    assert_eq!(index.insert(key(2), at(2), "b"), None);
+    assert_eq!(index.insert(key(1), at(1), "a"), None);
+    // replacing doesn't evict, and moves the entry to its new time
+    assert_eq!(index.insert(key(1), at(3), "a'"), Some("a"));
+    assert_eq!(index.evicted(), 0);
+
+    index.insert(key(3), at(4), "c");
+    assert_eq!(index.evicted(), 1);
+    assert_eq!(index.len(), 2);
+    assert!(!index.contains_key(&key(2)));
+    assert_eq!(index.get(&key(1)), Some(&"a'"));
+
+    *index.get_mut(&key(3)).unwrap() = "c'";
+    index.remove_before(at(4));
+    assert_eq!(index.len(), 1);
+    assert_eq!(index.get(&key(3)), Some(&"c'"));
+    assert_eq!(index.remove(&key(3)), Some("c'"));
+    assert!(index.is_empty());
+    assert_eq!(index.evicted(), 1);
+}
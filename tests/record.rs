@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use tsc_solution::error::QuoteDecodeError;
+use tsc_solution::record::{encode_record, from_record, write_record, RECORD_LEN};
+use tsc_solution::testdata::sample_quotes;
+use tsc_solution::PriceQuoteBuilder;
+
+#[test]
+fn records_round_trip() {
+    for quote in sample_quotes() {
+        let record = encode_record(&quote);
+        assert_eq!(from_record(&record).unwrap(), quote);
+    }
+
+    // every field lands where it's documented
+    let quote = PriceQuoteBuilder::new()
+        .issue_code("KR4301F42629")
+        .rcv_time(Duration::new(1297814400, 5))
+        .bids(&[(525, 24)])
+        .build();
+    let record = encode_record(&quote);
+    assert_eq!(record.len(), RECORD_LEN);
+    assert_eq!(&record[0..8], &1297814400000000005u64.to_le_bytes());
+    assert_eq!(&record[13..25], b"KR4301F42629");
+    assert_eq!(&record[47..55], &525u64.to_le_bytes());
+    assert_eq!(&record[55..63], &24u64.to_le_bytes());
+}
+
+#[test]
+fn records_are_fixed_width() {
+    // short issue codes are padded, long ones cut
+    let short = PriceQuoteBuilder::new().issue_code("KR43").build();
+    assert_eq!(from_record(&encode_record(&short)).unwrap(), short);
+    let long = PriceQuoteBuilder::new()
+        .issue_code("KR4301F42629XX")
+        .build();
+    assert_eq!(
+        from_record(&encode_record(&long)).unwrap().issue_code,
+        "KR4301F42629"
+    );
+
+    // back to back, a file is just records end to end
+    let mut file = vec![];
+    for quote in sample_quotes() {
+        write_record(&mut file, &quote).unwrap();
+    }
+    assert_eq!(file.len(), 3 * RECORD_LEN);
+    let read: Vec<_> = file
+        .chunks_exact(RECORD_LEN)
+        .map(|record| from_record(record).unwrap())
+        .collect();
+    assert_eq!(read, sample_quotes());
+
+    assert_eq!(
+        from_record(&file[..RECORD_LEN - 1]),
+        Err(QuoteDecodeError::Truncated {
+            field: "record",
+            offset: 0,
+            needed: RECORD_LEN,
+            available: RECORD_LEN - 1,
+        })
+    );
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "redis")]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener};
+use std::thread;
+
+use tsc_solution::sink::redis::{entry_fields, RedisSink};
+use tsc_solution::PriceQuoteBuilder;
+
+// a stand in for redis that answers OK to everything and hands back the
+// commands it got, once the client hangs up
+fn fake_redis() -> (String, thread::JoinHandle<Vec<Vec<String>>>) {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let url = format!("redis://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reply = stream.try_clone().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut commands = vec![];
+        // every command is an array of bulk strings
+        while let Some(len) = read_len(&mut reader, '*') {
+            let command = (0..len)
+                .map(|_| {
+                    let len = read_len(&mut reader, '$').unwrap();
+                    let mut arg = vec![0; len + 2];
+                    reader.read_exact(&mut arg).unwrap();
+                    arg.truncate(len);
+                    String::from_utf8(arg).unwrap()
+                })
+                .collect();
+            commands.push(command);
+            reply.write_all(b"+OK\r\n").unwrap();
+        }
+        commands
+    });
+    (url, server)
+}
+
+fn read_len(reader: &mut impl BufRead, prefix: char) -> Option<usize> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).ok()? == 0 {
+        return None;
+    }
+    line.trim_end().strip_prefix(prefix)?.parse().ok()
+}
+
+#[test]
+fn adds_each_quote_as_a_stream_entry() {
+    let quotes = [
+        PriceQuoteBuilder::new().issue_code("KR4301F42629").build(),
+        PriceQuoteBuilder::new().issue_code("KR4301F42959").build(),
+    ];
+    let (url, server) = fake_redis();
+    let mut sink = RedisSink::connect(&url, "quotes", Some(1000)).unwrap();
+    for quote in &quotes {
+        sink.send(quote).unwrap();
+    }
+    drop(sink);
+
+    // the client may say hello first, only the XADDs matter
+    let xadds: Vec<Vec<String>> = server
+        .join()
+        .unwrap()
+        .into_iter()
+        .filter(|command| command[0] == "XADD")
+        .collect();
+    assert_eq!(xadds.len(), quotes.len());
+    for (xadd, quote) in xadds.iter().zip(&quotes) {
+        assert_eq!(xadd[1..6], ["quotes", "MAXLEN", "~", "1000", "*"]);
+        let fields: Vec<(String, String)> = entry_fields(quote)
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        let sent: Vec<(String, String)> = xadd[6..]
+            .chunks(2)
+            .map(|pair| (pair[0].clone(), pair[1].clone()))
+            .collect();
+        assert_eq!(sent, fields);
+    }
+}
+
+#[test]
+fn entries_have_a_field_for_every_quote_field() {
+    let quote = PriceQuoteBuilder::new()
+        .issue_code("KR4301F42629")
+        .bid(0, 525, 24)
+        .build();
+    let fields = entry_fields(&quote);
+    let value = |name: &str| {
+        fields
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| value.as_str())
+    };
+    // eight header fields, the capture and accept times, two order totals
+    // and a price, quantity and order count for each of the ten levels
+    assert_eq!(fields.len(), 8 + 2 + 2 + 30);
+    assert_eq!(value("issue_code"), Some("KR4301F42629"));
+    assert_eq!(value("best_bid_price_1st"), Some("525"));
+    assert_eq!(value("best_bid_quantity_1st"), Some("24"));
+    assert_eq!(
+        value("quote_accept_time"),
+        Some(quote.quote_accept_time.to_string().as_str())
+    );
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use tsc_solution::report;
+use tsc_solution::PriceQuoteBuilder;
+
+#[test]
+fn top_by_volume_ranks_and_weights() {
+    let quotes = vec![
+        PriceQuoteBuilder::new()
+            .issue_code("KR4301F00001")
+            .bids(&[(100, 10)])
+            .asks(&[(102, 10)])
+            .build(),
+        PriceQuoteBuilder::new()
+            .issue_code("KR4301F00001")
+            .bids(&[(110, 20)])
+            .asks(&[(112, 20)])
+            .build(),
+        PriceQuoteBuilder::new()
+            .issue_code("KR4301F00002")
+            .bids(&[(50, 100)])
+            .asks(&[(52, 100)])
+            .build(),
+    ];
+
+    let ranked = report::top_by_volume(&quotes, 1, 0);
+    assert_eq!(ranked.len(), 1);
+    assert_eq!(ranked[0].0, "KR4301F00002");
+
+    let ranked = report::top_by_volume(&quotes, 5, 0);
+    let (issue, stats) = &ranked[1];
+    assert_eq!(issue, "KR4301F00001");
+    assert_eq!(stats.quotes, 2);
+    assert_eq!(stats.bid_volume, 30);
+    assert_eq!(stats.ask_volume, 30);
+    // (101 * 20 + 111 * 40) / 60
+    let vwap = stats.volume_weighted_mid().unwrap();
+    assert!((vwap - 107.666_666).abs() < 1e-3);
+}
+
+#[test]
+fn issue_list_is_sorted_and_aligned() {
+    let counts = HashMap::from([
+        ("KR4301F62551".to_string(), 7),
+        ("KR4201F32705".to_string(), 12345),
+        ("KR4301F42629".to_string(), 2),
+    ]);
+    assert_eq!(
+        report::format_issue_list(&counts, 1),
+        "     12345 KR4201F32705\n         2 KR4301F42629\n         7 KR4301F62551\n"
+    );
+    assert_eq!(
+        report::format_issue_list(&counts, 3),
+        "     12345 KR4201F32705\n         7 KR4301F62551\n"
+    );
+}
+
+#[test]
+fn heatmap_bins_quotes_across_the_capture() {
+    let at = |issue_code: &str, secs: u64| {
+        PriceQuoteBuilder::new()
+            .issue_code(issue_code)
+            .rcv_time(std::time::Duration::from_secs(secs))
+            .build()
+    };
+    let quotes = vec![
+        at("KR4301F42629", 100),
+        at("KR4301F42629", 101),
+        at("KR4301F42629", 102),
+        at("KR4301F42629", 103),
+        at("KR4301F42959", 160),
+        at("KR4301F42959", 199),
+        at("KR4301F42959", 200),
+    ];
+    let heatmap = report::build_heatmap(&quotes, 4);
+    assert_eq!(heatmap.len(), 2);
+    assert_eq!(heatmap["KR4301F42629"], [4, 0, 0, 0]);
+    // the last quote lands in the last bin, not past it
+    assert_eq!(heatmap["KR4301F42959"], [0, 0, 1, 2]);
+
+    assert_eq!(
+        report::format_heatmap(&heatmap),
+        "Quote Heatmap:\n  KR4301F42629 |\u{2588}   |\n  KR4301F42959 |  \u{2591}\u{2592}|\n"
+    );
+    assert!(report::build_heatmap(&[], 4).is_empty());
+}
@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use tsc_solution::resample::{resample, Resampler};
+use tsc_solution::{PriceQuote, PriceQuoteBuilder};
+
+fn quote(issue_code: &str, seq: u32, millis: u64) -> PriceQuote {
+    PriceQuoteBuilder::new()
+        .issue_code(issue_code)
+        .seq(seq)
+        .rcv_time(Duration::from_millis(millis))
+        .build()
+}
+
+// (issue, seq, rcv millis) of each snapshot
+fn summary(quotes: &[PriceQuote]) -> Vec<(&str, u32, u128)> {
+    quotes
+        .iter()
+        .map(|q| {
+            (
+                q.issue_code.as_str(),
+                q.seq().unwrap(),
+                q.packet_rcv_time.as_millis(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn ticks_hand_back_the_latest_quote_of_each_issue() {
+    let mut resampler = Resampler::new(Duration::from_millis(100), false);
+    assert_eq!(
+        resampler.align(Duration::from_millis(101)),
+        Duration::from_millis(200)
+    );
+    assert_eq!(
+        resampler.align(Duration::from_millis(200)),
+        Duration::from_millis(200)
+    );
+
+    resampler.push(quote("KR4301F00002", 1, 10));
+    resampler.push(quote("KR4301F00001", 2, 20));
+    resampler.push(quote("KR4301F00002", 3, 30));
+    let ticked: Vec<PriceQuote> = resampler
+        .tick(Duration::from_millis(100))
+        .into_iter()
+        .cloned()
+        .collect();
+    assert_eq!(
+        summary(&ticked),
+        [("KR4301F00001", 2, 100), ("KR4301F00002", 3, 100)]
+    );
+    assert!(resampler.is_idle());
+    assert!(resampler.tick(Duration::from_millis(200)).is_empty());
+}
+
+#[test]
+fn resamples_onto_a_grid() {
+    let quotes = [
+        quote("KR4301F00001", 1, 1_050),
+        quote("KR4301F00002", 2, 1_120),
+        quote("KR4301F00001", 3, 1_180),
+        quote("KR4301F00001", 4, 1_450),
+    ];
+    let interval = Duration::from_millis(100);
+
+    // quiet windows are skipped
+    assert_eq!(
+        summary(&resample(&quotes, interval, false)),
+        [
+            ("KR4301F00001", 1, 1_100),
+            ("KR4301F00001", 3, 1_200),
+            ("KR4301F00002", 2, 1_200),
+            ("KR4301F00001", 4, 1_500),
+        ]
+    );
+
+    // or filled in with each issue's last quote
+    let filled = resample(&quotes, interval, true);
+    assert_eq!(filled.len(), 1 + 2 * 4);
+    assert_eq!(
+        summary(&filled[5..7]),
+        [("KR4301F00001", 3, 1_400), ("KR4301F00002", 2, 1_400)]
+    );
+
+    assert!(resample(&[], interval, true).is_empty());
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "lua")]
+
+use tsc_solution::error::ScriptError;
+use tsc_solution::script::ScriptFilter;
+use tsc_solution::PriceQuoteBuilder;
+
+#[test]
+fn keeps_what_filter_returns_true_for() {
+    let script = ScriptFilter::from_source(
+        "test",
+        r#"
+        function filter(quote)
+            return quote.country_code == "KR"
+                and quote.spread ~= nil
+                and quote.bids[1].qty >= 10
+                and quote.bids[1].order_count == 3
+        end
+        "#,
+        0,
+    )
+    .unwrap();
+
+    let kept = PriceQuoteBuilder::new()
+        .bids(&[(525, 10)])
+        .bid_orders(0, 3)
+        .build();
+    assert!(script.keep(&kept).unwrap());
+
+    let small = PriceQuoteBuilder::new()
+        .bids(&[(525, 9)])
+        .bid_orders(0, 3)
+        .build();
+    assert!(!script.keep(&small).unwrap());
+    let one_sided = PriceQuoteBuilder::new().asks(&[]).bid_orders(0, 3).build();
+    assert!(!script.keep(&one_sided).unwrap());
+    let foreign = PriceQuoteBuilder::new()
+        .issue_code("US0378331005")
+        .bid_orders(0, 3)
+        .build();
+    assert!(!script.keep(&foreign).unwrap());
+}
+
+#[test]
+fn sees_the_quote_fields() {
+    let script = ScriptFilter::from_source(
+        "test",
+        r#"
+        function filter(quote)
+            assert(quote.issue_code == "KR4301F42629")
+            assert(quote.seq == 2)
+            assert(quote.accept_time == "09:00:00.020")
+            assert(quote.market_status == 40)
+            assert(quote.spread == 5)
+            assert(quote.mid_price == 527.5)
+            assert(#quote.asks == 5)
+            assert(quote.asks[2].price == 535)
+            return true
+        end
+        "#,
+        0,
+    )
+    .unwrap();
+    assert!(script
+        .keep(&PriceQuoteBuilder::new().seq(2).build())
+        .unwrap());
+}
+
+#[test]
+fn reports_broken_scripts() {
+    assert!(matches!(
+        ScriptFilter::from_source("test", "x = 1", 0),
+        Err(ScriptError::NoFilter)
+    ));
+    assert!(matches!(
+        ScriptFilter::from_source("test", "function filter(", 0),
+        Err(ScriptError::Lua(_))
+    ));
+
+    let script =
+        ScriptFilter::from_source("test", "function filter(quote) error('nope') end", 0).unwrap();
+    let err = script.keep(&PriceQuoteBuilder::new().build()).unwrap_err();
+    assert!(err.to_string().contains("nope"), "{}", err);
+}
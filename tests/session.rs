@@ -0,0 +1,95 @@
+#![cfg(feature = "chrono")]
+
+use std::time::Duration;
+
+use chrono::NaiveTime;
+use tsc_solution::session::SessionStats;
+use tsc_solution::PriceQuoteBuilder;
+
+#[test]
+fn accumulates_per_issue() {
+    let at = |ms: u64| Duration::from_millis(1_000_000 + ms);
+    let quotes = vec![
+        // spread 10 for 1s, then 20 for 3s, the last spread has no duration
+        PriceQuoteBuilder::new()
+            .rcv_time(at(0))
+            .bids(&[(100, 1)])
+            .asks(&[(110, 2)])
+            .build(),
+        PriceQuoteBuilder::new()
+            .issue_code("KR4301F00002")
+            .rcv_time(at(100))
+            .build(),
+        PriceQuoteBuilder::new()
+            .rcv_time(at(1000))
+            .bids(&[(100, 3)])
+            .asks(&[(120, 4)])
+            .build(),
+        PriceQuoteBuilder::new()
+            .rcv_time(at(1500))
+            .bids(&[(100, 3)])
+            .asks(&[(120, 4)])
+            .build(),
+        PriceQuoteBuilder::new()
+            .rcv_time(at(4000))
+            .bids(&[(100, 5)])
+            .asks(&[(200, 6)])
+            .build(),
+    ];
+
+    let session_stats = SessionStats::from_quotes(&quotes, 0);
+    assert_eq!(session_stats.issues.len(), 2);
+
+    let stats = &session_stats.issues["KR4301F42629"];
+    assert_eq!(stats.quotes, 4);
+    assert_eq!(stats.first_rcv_time, at(0));
+    assert_eq!(stats.last_rcv_time, at(4000));
+    assert_eq!(stats.mean_interval_ms(), Some(4000.0 / 3.0));
+    assert_eq!(stats.bid_volume, 1 + 3 + 3 + 5);
+    assert_eq!(stats.ask_volume, 2 + 4 + 4 + 6);
+    assert_eq!(
+        stats.time_weighted_spread(0),
+        Some((10.0 + 20.0 * 3.0) / 4.0)
+    );
+    assert_eq!(stats.max_quotes_per_second, 2);
+
+    let single = &session_stats.issues["KR4301F00002"];
+    assert_eq!(single.quotes, 1);
+    assert_eq!(single.mean_interval_ms(), None);
+    assert_eq!(single.time_weighted_spread(0), None);
+}
+
+#[test]
+fn first_and_last_seen() {
+    let quotes = vec![
+        PriceQuoteBuilder::new()
+            .accept_time(NaiveTime::from_hms_milli_opt(9, 30, 0, 0).unwrap())
+            .build(),
+        PriceQuoteBuilder::new()
+            .issue_code("KR4301F00002")
+            .accept_time(NaiveTime::from_hms_milli_opt(10, 0, 0, 0).unwrap())
+            .build(),
+        PriceQuoteBuilder::new()
+            .accept_time(NaiveTime::from_hms_milli_opt(14, 45, 12, 340).unwrap())
+            .build(),
+        // arrived last but accepted earliest
+        PriceQuoteBuilder::new()
+            .accept_time(NaiveTime::from_hms_milli_opt(9, 5, 0, 0).unwrap())
+            .build(),
+    ];
+
+    let session_stats = SessionStats::from_quotes(&quotes, 0);
+    let stats = &session_stats.issues["KR4301F42629"];
+    assert_eq!(stats.first_accept_time, NaiveTime::from_hms_opt(9, 5, 0));
+    assert_eq!(
+        stats.last_accept_time,
+        NaiveTime::from_hms_milli_opt(14, 45, 12, 340)
+    );
+
+    assert_eq!(
+        session_stats.format_first_last_seen(),
+        "Issue          First Seen    Last Seen  Updates\n\
+         KR4301F00002 10:00:00.000 10:00:00.000        1\n\
+         KR4301F42629 09:05:00.000 14:45:12.340        3\n"
+    );
+}
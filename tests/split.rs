@@ -0,0 +1,100 @@
+#![cfg(feature = "cli")]
+
+use std::time::Duration;
+
+use chrono::{NaiveDate, NaiveTime};
+use chrono_tz::Asia::Seoul;
+
+use tsc_solution::schema::JSON_SCHEMA_VERSION;
+use tsc_solution::split::{DateSplitter, IssueSplitter};
+use tsc_solution::{PriceQuote, PriceQuoteBuilder};
+
+fn read_quotes(path: &std::path::Path) -> Vec<PriceQuote> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn writes_one_file_per_issue() {
+    let dir = std::env::temp_dir().join(format!("tsc-split-{}", std::process::id()));
+    let a1 = PriceQuoteBuilder::new()
+        .issue_code("KR4301F42629")
+        .seq(1)
+        .build();
+    let b1 = PriceQuoteBuilder::new()
+        .issue_code("KR4301F42959")
+        .seq(2)
+        .build();
+    let a2 = PriceQuoteBuilder::new()
+        .issue_code("KR4301F42629")
+        .seq(3)
+        .bid(0, 530, 7)
+        .build();
+
+    // one open file at a time, so a's file is closed for b and reopened
+    let mut splitter = IssueSplitter::new(&dir, 1).unwrap();
+    for quote in [&a1, &b1, &a2] {
+        splitter.write(quote).unwrap();
+    }
+    splitter.flush().unwrap();
+
+    let mut files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["KR4301F42629.jsonl", "KR4301F42959.jsonl"]);
+    assert_eq!(read_quotes(&splitter.path_for("KR4301F42629")), [a1, a2]);
+    assert_eq!(read_quotes(&splitter.path_for("KR4301F42959")), [b1]);
+
+    // every line says which layout it's in, ahead of the quote's own fields
+    let line = std::fs::read_to_string(splitter.path_for("KR4301F42959")).unwrap();
+    let prefix = format!(
+        "{{\"schema_version\":{},\"crate_version\":\"{}\",\"packet_rcv_time\":",
+        JSON_SCHEMA_VERSION,
+        env!("CARGO_PKG_VERSION")
+    );
+    assert!(line.starts_with(&prefix), "{}", line);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn writes_one_file_per_capture_date() {
+    let dir = std::env::temp_dir().join(format!("tsc-split-dates-{}", std::process::id()));
+    // midnight in seoul, 2011-02-17 00:00:00 KST
+    let midnight = Duration::from_secs(1297868400);
+    let quote = |rcv_time: Duration, accept_time: (u32, u32, u32)| {
+        let (h, m, s) = accept_time;
+        PriceQuoteBuilder::new()
+            .rcv_time(rcv_time)
+            .accept_time(NaiveTime::from_hms_opt(h, m, s).unwrap())
+            .build()
+    };
+    let before = quote(midnight - Duration::from_secs(1), (23, 59, 59));
+    // accept times wrap at midnight, the capture time doesn't
+    let after = quote(midnight + Duration::from_secs(1), (0, 0, 1));
+    // out of order, back to the day before
+    let late = quote(midnight - Duration::from_millis(1), (23, 59, 59));
+
+    let mut splitter = DateSplitter::new(&dir, Seoul).unwrap();
+    for q in [&before, &after, &late] {
+        splitter.write(q).unwrap();
+    }
+    splitter.flush().unwrap();
+
+    let feb16 = NaiveDate::from_ymd_opt(2011, 2, 16).unwrap();
+    let feb17 = NaiveDate::from_ymd_opt(2011, 2, 17).unwrap();
+    assert_eq!(splitter.dates(), [feb16, feb17]);
+    assert_eq!(
+        splitter.path_for(feb16).file_name().unwrap(),
+        "2011-02-16.jsonl"
+    );
+    assert_eq!(read_quotes(&splitter.path_for(feb16)), [before, late]);
+    assert_eq!(read_quotes(&splitter.path_for(feb17)), [after]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
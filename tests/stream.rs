@@ -0,0 +1,107 @@
+#![cfg(feature = "async")]
+
+use std::io::Cursor;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_util::StreamExt;
+use tokio::io::{AsyncRead, ReadBuf};
+
+use tsc_solution::error::QuoteStreamError;
+use tsc_solution::testdata::sample_capture;
+use tsc_solution::{
+    parse_price_quotes_from_reader, quote_stream, PacketParseStats, ParseOptions, PriceQuote,
+};
+
+const QUOTE_PAYLOAD: &[u8] = include_bytes!("fixtures/b6034_quote.bin");
+
+// hands out a few bytes per read so records land across buffer boundaries
+struct Dribble<'a> {
+    data: &'a [u8],
+    step: usize,
+}
+
+impl AsyncRead for Dribble<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let n = self.step.min(self.data.len()).min(buf.remaining());
+        buf.put_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Poll::Ready(Ok(()))
+    }
+}
+
+async fn collect<R: AsyncRead + Unpin>(
+    reader: R,
+    options: ParseOptions,
+) -> (Vec<PriceQuote>, PacketParseStats) {
+    let mut stream = quote_stream(reader, options);
+    let mut quotes = vec![];
+    while let Some(quote) = stream.next().await {
+        quotes.push(quote.unwrap());
+    }
+    (quotes, stream.into_stats())
+}
+
+fn assert_same_counts(streamed: &PacketParseStats, sync: &PacketParseStats) {
+    assert_eq!(streamed.packet_count(), sync.packet_count());
+    assert_eq!(streamed.total_bytes(), sync.total_bytes());
+    assert_eq!(streamed.successfully_parsed(), sync.successfully_parsed());
+    assert_eq!(streamed.rejected(), sync.rejected());
+    assert_eq!(streamed.failed(), sync.failed());
+    assert_eq!(streamed.non_udp(), sync.non_udp());
+    assert_eq!(streamed.wrong_port(), sync.wrong_port());
+    assert_eq!(streamed.not_a_price_quote(), sync.not_a_price_quote());
+    assert_eq!(streamed.malformed_quote(), sync.malformed_quote());
+    assert_eq!(streamed.checksum_failures(), sync.checksum_failures());
+    assert_eq!(streamed.parse_failures(), sync.parse_failures());
+    assert_eq!(streamed.ports(), sync.ports());
+}
+
+#[tokio::test]
+async fn stream_matches_sync_parse() {
+    let capture = sample_capture().build();
+    let options = ParseOptions::default();
+    let (sync_quotes, sync_stats) =
+        parse_price_quotes_from_reader(Cursor::new(&capture), &options).unwrap();
+
+    for step in [1, 7, 4096] {
+        let reader = Dribble {
+            data: &capture,
+            step,
+        };
+        let (quotes, stats) = collect(reader, options.clone()).await;
+        assert_eq!(quotes, sync_quotes, "reading {step} bytes at a time");
+        assert_same_counts(&stats, &sync_stats);
+    }
+}
+
+#[tokio::test]
+async fn streams_raw_payloads() {
+    let mut raw = QUOTE_PAYLOAD.repeat(2);
+    raw.extend_from_slice(&QUOTE_PAYLOAD[..100]);
+
+    let (quotes, stats) = collect(Cursor::new(raw), ParseOptions::default()).await;
+    assert_eq!(quotes.len(), 2);
+    assert_eq!(stats.packet_count, 3);
+    assert_eq!(stats.total_bytes, QUOTE_PAYLOAD.len() as u64 * 2 + 100);
+    assert_eq!(stats.malformed_quote, 1);
+}
+
+#[tokio::test]
+async fn stream_ends_on_truncated_record() {
+    let capture = sample_capture().build();
+    let cut = &capture[..capture.len() - 10];
+    let mut stream = quote_stream(Cursor::new(cut), ParseOptions::default());
+    let mut last = None;
+    while let Some(item) = stream.next().await {
+        last = Some(item);
+    }
+    assert!(matches!(
+        last,
+        Some(Err(QuoteStreamError::TruncatedRecord { packet_index: 7 }))
+    ));
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use tsc_solution::testdata::sample_capture;
+use tsc_solution::{
+    parse_price_quotes_from_bytes, ParseOptions, PriceQuoteBuilder, Thresholds, Violation,
+};
+
+#[test]
+fn reports_the_thresholds_a_capture_breaks() {
+    // the sample's three quotes and one that fails to decode
+    let mut bad_digit = PriceQuoteBuilder::new().build().encode_b6034();
+    bad_digit[30] = b'x';
+    let capture = sample_capture()
+        .udp(Duration::from_millis(8), 15515, &bad_digit)
+        .build();
+    let (_, mut stats) = parse_price_quotes_from_bytes(&capture, &ParseOptions::default()).unwrap();
+    assert_eq!((stats.successfully_parsed, stats.failed), (3, 1));
+
+    // nothing to check against, nothing broken
+    assert!(Thresholds::default().check(&stats).is_empty());
+    let within = Thresholds {
+        max_failed: Some(1),
+        max_failed_pct: Some(25.0),
+        min_quotes: Some(3),
+    };
+    assert!(within.check(&stats).is_empty());
+
+    let outside = Thresholds {
+        max_failed: Some(0),
+        max_failed_pct: Some(20.0),
+        min_quotes: Some(4),
+    };
+    stats.violations = outside.check(&stats);
+    assert_eq!(
+        stats.violations,
+        [
+            Violation::MaxFailed { failed: 1, max: 0 },
+            Violation::MaxFailedPct {
+                pct: 25.0,
+                max: 20.0
+            },
+            Violation::MinQuotes { quotes: 3, min: 4 },
+        ]
+    );
+    assert!(stats.to_string().ends_with(
+        "  Threshold Violated: 1 quotes failed to decode, over --max-failed 0\n\
+         \x20 Threshold Violated: 25.00% of quotes failed to decode, over --max-failed-pct 20\n\
+         \x20 Threshold Violated: 3 quotes decoded, under --min-quotes 4\n"
+    ));
+
+    #[cfg(feature = "serde")]
+    assert_eq!(
+        serde_json::to_value(&stats).unwrap()["violations"],
+        serde_json::json!(["max_failed", "max_failed_pct", "min_quotes"])
+    );
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use tsc_solution::price::{Price, Qty};
+use tsc_solution::price_quote::Side;
+use tsc_solution::trade_inferrer::{InferredTrade, TradeInferrer};
+use tsc_solution::PriceQuoteBuilder;
+
+fn trade(millis: u64, issue_code: &str, side: Side, price: u64, qty: u64) -> InferredTrade {
+    InferredTrade {
+        time: Duration::from_millis(millis),
+        issue_code: issue_code.to_string(),
+        side,
+        price: Price(price),
+        inferred_quantity: Qty(qty),
+    }
+}
+
+#[test]
+fn infers_trades_from_the_top_of_book() {
+    // best bid 525 x 24, best ask 530 x 1
+    let quote = |millis| {
+        PriceQuoteBuilder::new()
+            .issue_code("KR4301F42629")
+            .rcv_time(Duration::from_millis(millis))
+    };
+    let mut inferrer = TradeInferrer::new();
+
+    // nothing to compare the first quote of an issue against
+    assert_eq!(inferrer.observe(&quote(1).build()), []);
+    // the bid shrinks at the same price
+    assert_eq!(
+        inferrer.observe(&quote(2).bid(0, 525, 20).build()),
+        [trade(2, "KR4301F42629", Side::Bid, 525, 4)]
+    );
+    // the ask is taken out and the next one up is best, while the bid grows
+    assert_eq!(
+        inferrer.observe(&quote(3).asks(&[(535, 7)]).build()),
+        [trade(3, "KR4301F42629", Side::Ask, 530, 1)]
+    );
+    // a better price or more quantity isn't a trade
+    assert_eq!(
+        inferrer.observe(&quote(4).bid(0, 526, 3).asks(&[(535, 9)]).build()),
+        []
+    );
+    // the bid side empties out
+    assert_eq!(
+        inferrer.observe(&quote(5).bids(&[]).asks(&[(535, 9)]).build()),
+        [trade(5, "KR4301F42629", Side::Bid, 526, 3)]
+    );
+
+    // each issue is compared only with itself
+    let other = PriceQuoteBuilder::new()
+        .issue_code("KR4301F52651")
+        .rcv_time(Duration::from_millis(6))
+        .bid(0, 525, 1)
+        .build();
+    assert_eq!(inferrer.observe(&other), []);
+    assert_eq!(
+        inferrer.observe(&quote(7).bids(&[]).asks(&[(535, 2)]).build()),
+        [trade(7, "KR4301F42629", Side::Ask, 535, 7)]
+    );
+}
@@ -0,0 +1,55 @@
+// run with wasm-pack test --node --no-default-features --features wasm
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use serde::Deserialize;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use tsc_solution::schema::JSON_SCHEMA_VERSION;
+use tsc_solution::testdata::sample_capture;
+use tsc_solution::wasm::parse_pcap_bytes;
+
+// just the parts the test looks at, everything else is skipped
+#[derive(Deserialize)]
+struct Parsed {
+    schema_version: u32,
+    quotes: Vec<Quote>,
+    stats: Stats,
+}
+
+#[derive(Deserialize)]
+struct Quote {
+    issue_code: String,
+}
+
+#[derive(Deserialize)]
+struct Stats {
+    packet_count: u64,
+    successfully_parsed: u64,
+    rejected: u64,
+}
+
+#[wasm_bindgen_test]
+fn parses_sample_capture() {
+    let capture = sample_capture().build();
+    let parsed: Parsed =
+        serde_wasm_bindgen::from_value(parse_pcap_bytes(&capture).unwrap()).unwrap();
+
+    let issue_codes: Vec<_> = parsed
+        .quotes
+        .iter()
+        .map(|q| q.issue_code.as_str())
+        .collect();
+    assert_eq!(
+        issue_codes,
+        ["KR4301F42629", "KR4301F42959", "KR4301F62551"]
+    );
+    assert_eq!(parsed.stats.packet_count, 7);
+    assert_eq!(parsed.stats.successfully_parsed, 3);
+    assert_eq!(parsed.stats.rejected, 4);
+    assert_eq!(parsed.schema_version, JSON_SCHEMA_VERSION);
+}
+
+#[wasm_bindgen_test]
+fn throws_on_garbage() {
+    assert!(parse_pcap_bytes(b"not a capture").is_err());
+}
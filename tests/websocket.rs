@@ -0,0 +1,50 @@
+#![cfg(feature = "websocket")]
+
+use std::thread;
+use std::time::Duration;
+
+use tokio_tungstenite::tungstenite::{connect, Message};
+use tsc_solution::sink::websocket::WsServer;
+use tsc_solution::PriceQuoteBuilder;
+
+fn wait_for_clients(server: &WsServer, n: usize) {
+    while server.client_count() != n {
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn sends_every_quote_to_every_client() {
+    let server = WsServer::bind(0).unwrap();
+    let url = format!("ws://{}", server.local_addr());
+    let (mut a, _) = connect(&url).unwrap();
+    let (mut b, _) = connect(&url).unwrap();
+    let (mut gone, _) = connect(&url).unwrap();
+    server.wait_for_client();
+    wait_for_clients(&server, 3);
+
+    // a client leaving doesn't bother the others
+    gone.close(None).unwrap();
+    wait_for_clients(&server, 2);
+
+    let quotes = [
+        PriceQuoteBuilder::new().issue_code("KR4301F42629").build(),
+        PriceQuoteBuilder::new().issue_code("KR4301F42959").build(),
+    ];
+    for quote in &quotes {
+        server.send(quote);
+    }
+    server.close();
+
+    for client in [&mut a, &mut b] {
+        for quote in &quotes {
+            let Message::Text(text) = client.read().unwrap() else {
+                panic!("expected a text message");
+            };
+            let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+            assert_eq!(json["schema_version"], 1);
+            assert_eq!(json["issue_code"], quote.issue_code.as_str());
+        }
+        assert!(matches!(client.read().unwrap(), Message::Close(_)));
+    }
+}